@@ -1,21 +1,23 @@
-use super::sbox::gen_sbox;
 use super::key_schedule::KeySchedule;
 use super::state::State;
+use super::error::AesError;
+use super::hardware::AesInstructions;
 
 pub struct AES {
-    sbox: [u8; 256],
-    key_schedule: KeySchedule
+    key_schedule: KeySchedule,
+    // AES-NI or the aarch64 crypto extensions, if the CPU has them
+    hardware: Option<AesInstructions>
 }
 
 impl AES {
-    pub fn new(key: &[u8]) -> Self {
-        let sbox = gen_sbox();
-        let key_schedule = KeySchedule::new(key, &sbox);
+    // Creates a cipher for a 16, 24 or 32 byte key (AES-128, AES-192 or AES-256).
+    pub fn new(key: &[u8]) -> Result<Self, AesError> {
+        let key_schedule = KeySchedule::new(key)?;
 
-        Self {
-            sbox,
+        Ok(Self {
             key_schedule,
-        }
+            hardware: AesInstructions::detect()
+        })
     }
 
     // Encrypts arbitrary length data using CBC mode.
@@ -26,13 +28,13 @@ impl AES {
 
         ciphertext.extend_from_slice(iv);
 
-        let mut previous_block = iv.to_vec();
+        let mut previous_block = *iv;
 
         for chunk in padded_plaintext.chunks(16) {
-            let mut block = Vec::with_capacity(16);
+            let mut block = [0u8; 16];
 
-            for (a, b) in chunk.iter().zip(previous_block.iter()) {
-                block.push(a ^ b);
+            for (byte, (a, b)) in block.iter_mut().zip(chunk.iter().zip(previous_block.iter())) {
+                *byte = a ^ b;
             }
 
             let encrypted_block = self.encrypt_block(&block);
@@ -44,26 +46,86 @@ impl AES {
         ciphertext
     }
 
-    fn encrypt_block(&self, input: &[u8]) -> Vec<u8> {
+    // Decrypts data produced by `encrypt`. The first block of the input is the IV.
+    // Returns the plaintext with the PKCS7 padding removed.
+    pub fn decrypt(&self, ciphertext_with_iv: &[u8]) -> Result<Vec<u8>, AesError> {
+        // we need the IV and at least one block, since padding always adds at least one byte
+        if ciphertext_with_iv.len() < 32 || !ciphertext_with_iv.len().is_multiple_of(16) {
+            return Err(AesError::InvalidCiphertextLength(ciphertext_with_iv.len()));
+        }
+
+        let (iv, ciphertext) = ciphertext_with_iv.split_at(16);
+        let mut plaintext = Vec::with_capacity(ciphertext.len());
+
+        let mut previous_block = iv;
+
+        for chunk in ciphertext.chunks(16) {
+            let decrypted_block = self.decrypt_block(chunk);
+
+            for (a, b) in decrypted_block.iter().zip(previous_block.iter()) {
+                plaintext.push(a ^ b);
+            }
+
+            previous_block = chunk;
+        };
+
+        unpad_data(&mut plaintext)?;
+
+        Ok(plaintext)
+    }
+
+    pub fn encrypt_block(&self, input: &[u8; 16]) -> [u8; 16] {
+        match &self.hardware {
+            Some(hardware) => hardware.encrypt_block(&self.key_schedule, input),
+            None => self.portable_encrypt_block(input)
+        }
+    }
+
+    fn portable_encrypt_block(&self, input: &[u8; 16]) -> [u8; 16] {
+        let rounds = self.key_schedule.rounds();
         let mut state = State::new(input);
 
         // initial round - just key addition
         state.add_round_key(self.key_schedule.get_round_key(0));
 
         // main rounds
-        for round in 1..10 {
-            state.sub_bytes(&self.sbox);
+        for round in 1..rounds {
+            state.sub_bytes();
             state.shift_rows();
             state.mix_columns();
             state.add_round_key(self.key_schedule.get_round_key(round));
         }
 
         // final round - no MixColumns
-        state.sub_bytes(&self.sbox);
+        state.sub_bytes();
         state.shift_rows();
-        state.add_round_key(self.key_schedule.get_round_key(10));
+        state.add_round_key(self.key_schedule.get_round_key(rounds));
 
 
+        state.as_bytes()
+    }
+
+    // The inverse cipher, which walks through the round keys in reverse order.
+    fn decrypt_block(&self, input: &[u8]) -> [u8; 16] {
+        let rounds = self.key_schedule.rounds();
+        let mut state = State::new(input);
+
+        // initial round - undo the final key addition
+        state.add_round_key(self.key_schedule.get_round_key(rounds));
+
+        // main rounds
+        for round in (1..rounds).rev() {
+            state.inv_shift_rows();
+            state.inv_sub_bytes();
+            state.add_round_key(self.key_schedule.get_round_key(round));
+            state.inv_mix_columns();
+        }
+
+        // final round - no InvMixColumns
+        state.inv_shift_rows();
+        state.inv_sub_bytes();
+        state.add_round_key(self.key_schedule.get_round_key(0));
+
         state.as_bytes()
     }
 }
@@ -82,4 +144,210 @@ fn pad_data(data: &[u8]) -> Vec<u8> {
     };
 
     padded
-}
\ No newline at end of file
+}
+
+// Removes PKCS7 padding in place. Every padding byte must hold the padding length, which
+// itself must be between 1 and the block size.
+fn unpad_data(data: &mut Vec<u8>) -> Result<(), AesError> {
+    let padding_length = *data.last().ok_or(AesError::InvalidPadding)? as usize;
+
+    if padding_length == 0 || padding_length > 16 || padding_length > data.len() {
+        return Err(AesError::InvalidPadding);
+    }
+
+    let padding_start = data.len() - padding_length;
+    if data[padding_start..].iter().any(|&b| b as usize != padding_length) {
+        return Err(AesError::InvalidPadding);
+    }
+
+    data.truncate(padding_start);
+
+    Ok(())
+}
+
+#[test]
+fn block_test() {
+    // FIPS-197 appendix C
+    let plaintext = [
+        0x00, 0x11, 0x22, 0x33, 0x44, 0x55, 0x66, 0x77,
+        0x88, 0x99, 0xaa, 0xbb, 0xcc, 0xdd, 0xee, 0xff
+    ];
+
+    let test_cases: [(usize, [u8; 16]); 3] = [
+        // C.1 AES-128
+        (16, [0x69, 0xc4, 0xe0, 0xd8, 0x6a, 0x7b, 0x04, 0x30, 0xd8, 0xcd, 0xb7, 0x80, 0x70, 0xb4, 0xc5, 0x5a]),
+        // C.2 AES-192
+        (24, [0xdd, 0xa9, 0x7c, 0xa4, 0x86, 0x4c, 0xdf, 0xe0, 0x6e, 0xaf, 0x70, 0xa0, 0xec, 0x0d, 0x71, 0x91]),
+        // C.3 AES-256
+        (32, [0x8e, 0xa2, 0xb7, 0xca, 0x51, 0x67, 0x45, 0xbf, 0xea, 0xfc, 0x49, 0x90, 0x4b, 0x49, 0x60, 0x89]),
+    ];
+
+    for (key_length, expected_ciphertext) in test_cases {
+        // the appendix keys are 00 01 02 ... up to the key length
+        let key: Vec<u8> = (0..key_length as u8).collect();
+        let aes = AES::new(&key).unwrap();
+
+        assert_eq!(aes.encrypt_block(&plaintext), expected_ciphertext);
+        assert_eq!(aes.decrypt_block(&expected_ciphertext), plaintext);
+    }
+}
+
+#[test]
+fn invalid_key_length_test() {
+    for key_length in [0, 15, 17, 31, 33, 64] {
+        let key = vec![0u8; key_length];
+        assert_eq!(AES::new(&key).err(), Some(AesError::InvalidKeyLength(key_length)));
+    }
+}
+
+#[test]
+fn decrypt_test() {
+    let key = [0x2b, 0x7e, 0x15, 0x16, 0x28, 0xae, 0xd2, 0xa6, 0xab, 0xf7, 0x15, 0x88, 0x09, 0xcf, 0x4f, 0x3c];
+    let iv = [0x0f; 16];
+    let aes = AES::new(&key).unwrap();
+
+    for length in [0, 1, 15, 16, 17, 100] {
+        let plaintext: Vec<u8> = (0..length).map(|i| i as u8).collect();
+        let ciphertext = aes.encrypt(&plaintext, &iv);
+
+        assert_eq!(ciphertext.len(), 16 + (length / 16 + 1) * 16);
+        assert_eq!(aes.decrypt(&ciphertext).unwrap(), plaintext);
+    }
+}
+
+#[test]
+fn decrypt_invalid_input_test() {
+    let key = [0x2b, 0x7e, 0x15, 0x16, 0x28, 0xae, 0xd2, 0xa6, 0xab, 0xf7, 0x15, 0x88, 0x09, 0xcf, 0x4f, 0x3c];
+    let iv = [0x0f; 16];
+    let aes = AES::new(&key).unwrap();
+
+    assert_eq!(aes.decrypt(&[0u8; 16]), Err(AesError::InvalidCiphertextLength(16)));
+    assert_eq!(aes.decrypt(&[0u8; 33]), Err(AesError::InvalidCiphertextLength(33)));
+
+    // flipping a bit in the IV changes the last byte of the first plaintext block, which
+    // is the padding byte for a short message
+    let mut ciphertext = aes.encrypt(b"short", &iv);
+    ciphertext[15] ^= 0x01;
+    assert_eq!(aes.decrypt(&ciphertext), Err(AesError::InvalidPadding));
+}
+
+// encrypt_block as it was with the table based s-box, for comparing against.
+#[cfg(test)]
+fn table_encrypt_block(aes: &AES, sbox: &[u8; 256], input: &[u8]) -> [u8; 16] {
+    let rounds = aes.key_schedule.rounds();
+    let mut state = State::new(input);
+
+    state.add_round_key(aes.key_schedule.get_round_key(0));
+    for round in 1..rounds {
+        state.sub_bytes_with_table(sbox);
+        state.shift_rows();
+        state.mix_columns();
+        state.add_round_key(aes.key_schedule.get_round_key(round));
+    }
+    state.sub_bytes_with_table(sbox);
+    state.shift_rows();
+    state.add_round_key(aes.key_schedule.get_round_key(rounds));
+
+    state.as_bytes()
+}
+
+#[test]
+fn table_comparison_test() {
+    let sbox = super::sbox::gen_sbox();
+
+    for key_length in [16, 24, 32] {
+        for _ in 0..20 {
+            let key = crate::utils::random::random_u8_32();
+            let aes = AES::new(&key[..key_length]).unwrap();
+            let block: [u8; 16] = crate::utils::random::random_u8_32()[..16].try_into().unwrap();
+
+            let ciphertext = aes.portable_encrypt_block(&block);
+            assert_eq!(ciphertext, table_encrypt_block(&aes, &sbox, &block));
+            assert_eq!(aes.decrypt_block(&ciphertext), block);
+        }
+    }
+}
+
+// Compares the throughput of the computed s-box with the old lookup table and with the AES
+// instructions. Run it with
+// `cargo test --release table_benchmark -- --ignored --nocapture`.
+#[test]
+#[ignore]
+fn table_benchmark() {
+    use std::time::Instant;
+
+    let sbox = super::sbox::gen_sbox();
+    let aes = AES::new(&[0x2b; 16]).unwrap();
+    let blocks = 200_000;
+
+    let mut block = [0u8; 16];
+    let start = Instant::now();
+    for _ in 0..blocks {
+        block = table_encrypt_block(&aes, &sbox, &block);
+    }
+    let table_time = start.elapsed();
+    let table_result = block;
+
+    let mut block = [0u8; 16];
+    let start = Instant::now();
+    for _ in 0..blocks {
+        block = aes.portable_encrypt_block(&block);
+    }
+    let constant_time = start.elapsed();
+    assert_eq!(block, table_result);
+
+    // encrypt_block uses the AES instructions when the CPU has them
+    let mut block = [0u8; 16];
+    let start = Instant::now();
+    for _ in 0..blocks {
+        block = aes.encrypt_block(&block);
+    }
+    let hardware_time = start.elapsed();
+    assert_eq!(block, table_result);
+
+    let megabytes = (blocks * 16) as f64 / 1_000_000.0;
+    println!("table lookup:  {:.2} MB/s", megabytes / table_time.as_secs_f64());
+    println!("constant time: {:.2} MB/s", megabytes / constant_time.as_secs_f64());
+    println!("encrypt_block: {:.2} MB/s (AES instructions: {})",
+             megabytes / hardware_time.as_secs_f64(), aes.hardware.is_some());
+}
+
+#[test]
+fn hardware_comparison_test() {
+    // without AES instructions both calls take the portable path, and this compares it with itself
+    for key_length in [16, 24, 32] {
+        for _ in 0..50 {
+            let key = crate::utils::random::random_u8_32();
+            let aes = AES::new(&key[..key_length]).unwrap();
+            let block: [u8; 16] = crate::utils::random::random_u8_32()[..16].try_into().unwrap();
+
+            assert_eq!(aes.encrypt_block(&block), aes.portable_encrypt_block(&block));
+        }
+    }
+}
+
+#[cfg(test)]
+use crate::utils::test_vectors::wycheproof;
+
+#[test]
+fn wycheproof_test() {
+    // CBC with PKCS7 padding, which runs every block through encrypt_block and decrypt_block
+    for group in &wycheproof::load("aes_cbc_pkcs5_test.json") {
+        for test in &group.tests {
+            let (Ok(mut aes), Ok(iv)) = (AES::new(&test.hex("key")), <[u8; 16]>::try_from(test.hex("iv"))) else {
+                test.check(false);
+                continue;
+            };
+            let ciphertext_with_iv = [&iv[..], &test.hex("ct")].concat();
+
+            // with the AES instructions if the CPU has them, and then without
+            for _ in 0..2 {
+                if test.result == wycheproof::Expectation::Valid {
+                    assert_eq!(aes.encrypt(&test.hex("msg"), &iv), ciphertext_with_iv, "{test}");
+                }
+                test.check(aes.decrypt(&ciphertext_with_iv) == Ok(test.hex("msg")));
+                aes.hardware = None;
+            }
+        }
+    }
+}
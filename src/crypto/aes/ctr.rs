@@ -0,0 +1,214 @@
+// Counter (CTR) mode, as specified in NIST SP 800-38A.
+//
+// CTR mode turns the block cipher into a stream cipher by encrypting successive counter
+// blocks and XORing the result (the keystream) with the data. Encryption and decryption are
+// the same operation, data of any length can be processed without padding, and since every
+// counter block can be computed directly from its index, the stream can seek to any block.
+
+use super::aes::AES;
+use super::error::AesError;
+
+// How much of the counter block is incremented for each new block.
+#[derive(Clone, Copy)]
+pub enum CounterWidth {
+    // The whole block is one 128 bit big endian counter (the SP 800-38A standard incrementing function).
+    Bits128,
+    // Only the last 32 bits are incremented, wrapping without carrying into the rest of the
+    // block. This is the inc32 function used by GCM.
+    Bits32
+}
+
+// The keystream state shared by AesCtr and GCM. The cipher is passed in for each call so
+// that the owner of the keystream decides how the AES instance is stored.
+pub struct Keystream {
+    initial_block: u128,
+    counter_width: CounterWidth,
+    // index of the next keystream block to generate
+    block_index: u64,
+    // the current keystream block, and how many of its bytes have been used
+    buffer: [u8; 16],
+    buffer_position: usize
+}
+
+impl Keystream {
+    pub fn new(initial_block: &[u8; 16], counter_width: CounterWidth) -> Self {
+        Self {
+            initial_block: u128::from_be_bytes(*initial_block),
+            counter_width,
+            block_index: 0,
+            buffer: [0u8; 16],
+            buffer_position: 16
+        }
+    }
+
+    // XORs the keystream into the data, continuing where the previous call left off.
+    pub fn apply(&mut self, aes: &AES, data: &mut [u8]) {
+        for byte in data.iter_mut() {
+            if self.buffer_position == 16 {
+                self.refill(aes);
+            }
+
+            *byte ^= self.buffer[self.buffer_position];
+            self.buffer_position += 1;
+        }
+    }
+
+    // Moves the keystream to the start of the given block.
+    pub fn seek(&mut self, block_index: u64) {
+        self.block_index = block_index;
+        self.buffer_position = 16;
+    }
+
+    fn refill(&mut self, aes: &AES) {
+        let counter_block = self.counter_block(self.block_index);
+        self.buffer = aes.encrypt_block(&counter_block.to_be_bytes());
+        self.buffer_position = 0;
+        self.block_index = self.block_index.wrapping_add(1);
+    }
+
+    fn counter_block(&self, block_index: u64) -> u128 {
+        match self.counter_width {
+            CounterWidth::Bits128 => self.initial_block.wrapping_add(block_index as u128),
+            CounterWidth::Bits32 => {
+                let counter = (self.initial_block as u32).wrapping_add(block_index as u32);
+                (self.initial_block & !(u32::MAX as u128)) | counter as u128
+            }
+        }
+    }
+}
+
+pub struct AesCtr {
+    aes: AES,
+    keystream: Keystream
+}
+
+impl AesCtr {
+    // Creates a stream starting at the given initial counter block. The same key and initial
+    // counter must never be used to encrypt two different messages.
+    pub fn new(key: &[u8], initial_counter: &[u8; 16]) -> Result<Self, AesError> {
+        Ok(Self {
+            aes: AES::new(key)?,
+            keystream: Keystream::new(initial_counter, CounterWidth::Bits128)
+        })
+    }
+
+    // Encrypts or decrypts the data in place. Buffers of any size can be passed in; the
+    // stream continues from where the previous call stopped.
+    pub fn apply_keystream(&mut self, data: &mut [u8]) {
+        self.keystream.apply(&self.aes, data);
+    }
+
+    // Moves the stream to the start of the given 16 byte block, e.g. to decrypt part of a
+    // large file without processing everything before it.
+    pub fn seek(&mut self, block_index: u64) {
+        self.keystream.seek(block_index);
+    }
+}
+
+#[cfg(test)]
+use crate::utils::formatting::hex_to_bytes;
+
+#[cfg(test)]
+const SP800_38A_PLAINTEXT: &str = "6bc1bee22e409f96e93d7e117393172aae2d8a571e03ac9c9eb76fac45af8e51\
+                                   30c81c46a35ce411e5fbc1191a0a52eff69f2445df4f9b17ad2b417be66c3710";
+
+#[cfg(test)]
+const SP800_38A_COUNTER: [u8; 16] = [
+    0xf0, 0xf1, 0xf2, 0xf3, 0xf4, 0xf5, 0xf6, 0xf7,
+    0xf8, 0xf9, 0xfa, 0xfb, 0xfc, 0xfd, 0xfe, 0xff
+];
+
+#[test]
+fn sp800_38a_test() {
+    // NIST SP 800-38A appendix F.5
+    let test_cases = [
+        // F.5.1 CTR-AES128
+        ("2b7e151628aed2a6abf7158809cf4f3c",
+         "874d6191b620e3261bef6864990db6ce9806f66b7970fdff8617187bb9fffdff\
+          5ae4df3edbd5d35e5b4f09020db03eab1e031dda2fbe03d1792170a0f3009cee"),
+        // F.5.3 CTR-AES192
+        ("8e73b0f7da0e6452c810f32b809079e562f8ead2522c6b7b",
+         "1abc932417521ca24f2b0459fe7e6e0b090339ec0aa6faefd5ccc2c6f4ce8e94\
+          1e36b26bd1ebc670d1bd1d665620abf74f78a7f6d29809585a97daec58c6b050"),
+        // F.5.5 CTR-AES256
+        ("603deb1015ca71be2b73aef0857d77811f352c073b6108d72d9810a30914dff4",
+         "601ec313775789a5b7a7f504bbf3d228f443e3ca4d62b59aca84e990cacaf5c5\
+          2b0930daa23de94ce87017ba2d84988ddfc9c58db67aada613c2dd08457941a6"),
+    ];
+
+    for (key, ciphertext) in test_cases {
+        let key = hex_to_bytes(key).unwrap();
+        let plaintext = hex_to_bytes(SP800_38A_PLAINTEXT).unwrap();
+        let ciphertext = hex_to_bytes(ciphertext).unwrap();
+
+        // encrypt
+        let mut data = plaintext.clone();
+        AesCtr::new(&key, &SP800_38A_COUNTER).unwrap().apply_keystream(&mut data);
+        assert_eq!(data, ciphertext);
+
+        // decrypt (F.5.2, F.5.4 and F.5.6)
+        AesCtr::new(&key, &SP800_38A_COUNTER).unwrap().apply_keystream(&mut data);
+        assert_eq!(data, plaintext);
+    }
+}
+
+#[test]
+fn incremental_test() {
+    let key = hex_to_bytes("2b7e151628aed2a6abf7158809cf4f3c").unwrap();
+    let plaintext = hex_to_bytes(SP800_38A_PLAINTEXT).unwrap();
+
+    let mut expected = plaintext.clone();
+    AesCtr::new(&key, &SP800_38A_COUNTER).unwrap().apply_keystream(&mut expected);
+
+    // processing the data in uneven chunks must give the same result as one call
+    for chunk_size in [1, 5, 15, 16, 17, 33] {
+        let mut ctr = AesCtr::new(&key, &SP800_38A_COUNTER).unwrap();
+        let mut data = plaintext.clone();
+        for chunk in data.chunks_mut(chunk_size) {
+            ctr.apply_keystream(chunk);
+        }
+
+        assert_eq!(data, expected);
+    }
+}
+
+#[test]
+fn seek_test() {
+    let key = hex_to_bytes("2b7e151628aed2a6abf7158809cf4f3c").unwrap();
+    let plaintext = hex_to_bytes(SP800_38A_PLAINTEXT).unwrap();
+
+    let mut expected = plaintext.clone();
+    AesCtr::new(&key, &SP800_38A_COUNTER).unwrap().apply_keystream(&mut expected);
+
+    let mut ctr = AesCtr::new(&key, &SP800_38A_COUNTER).unwrap();
+    for block_index in [3, 0, 2, 1] {
+        let range = block_index * 16..(block_index + 1) * 16;
+        let mut block = plaintext[range.clone()].to_vec();
+
+        ctr.seek(block_index as u64);
+        ctr.apply_keystream(&mut block);
+        assert_eq!(block, expected[range]);
+    }
+}
+
+#[test]
+fn counter_wrap_test() {
+    let key = [0x11; 16];
+    let aes = AES::new(&key).unwrap();
+
+    // the 128 bit counter carries across the whole block and wraps to zero
+    let mut ctr = AesCtr::new(&key, &[0xff; 16]).unwrap();
+    let mut data = [0u8; 32];
+    ctr.apply_keystream(&mut data);
+    assert_eq!(data[..16], aes.encrypt_block(&[0xff; 16]));
+    assert_eq!(data[16..], aes.encrypt_block(&[0x00; 16]));
+
+    // the 32 bit counter wraps without touching the first 12 bytes
+    let mut keystream = Keystream::new(&[0xff; 16], CounterWidth::Bits32);
+    let mut data = [0u8; 32];
+    keystream.apply(&aes, &mut data);
+
+    let mut wrapped_block = [0xff; 16];
+    wrapped_block[12..].copy_from_slice(&[0x00; 4]);
+    assert_eq!(data[16..], aes.encrypt_block(&wrapped_block));
+}
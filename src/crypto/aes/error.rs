@@ -0,0 +1,28 @@
+use std::fmt::{Display, Formatter};
+
+#[derive(Debug, PartialEq)]
+pub enum AesError {
+    // The key is not 16, 24 or 32 bytes long.
+    InvalidKeyLength(usize),
+    // The ciphertext is not a whole number of blocks, or is too short to contain an IV
+    // and at least one block.
+    InvalidCiphertextLength(usize),
+    // The plaintext is longer than the mode can encrypt under one nonce.
+    InvalidPlaintextLength(usize),
+    // The decrypted data does not end in valid PKCS7 padding.
+    InvalidPadding,
+    // The authentication tag does not match the ciphertext and associated data.
+    AuthenticationFailed,
+}
+
+impl Display for AesError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AesError::InvalidKeyLength(length) => write!(f, "invalid key length: {} bytes", length),
+            AesError::InvalidCiphertextLength(length) => write!(f, "invalid ciphertext length: {} bytes", length),
+            AesError::InvalidPlaintextLength(length) => write!(f, "invalid plaintext length: {} bytes", length),
+            AesError::InvalidPadding => write!(f, "invalid PKCS7 padding"),
+            AesError::AuthenticationFailed => write!(f, "authentication failed"),
+        }
+    }
+}
@@ -0,0 +1,228 @@
+// Galois/Counter Mode (GCM), as specified in NIST SP 800-38D.
+//
+// GCM is an AEAD (authenticated encryption with associated data) mode. The plaintext is
+// encrypted with a counter mode keystream, and a GHASH tag is computed over the associated
+// data and the ciphertext, so any tampering is detected when opening.
+
+use super::aes::AES;
+use super::ctr::{CounterWidth, Keystream};
+use super::error::AesError;
+use crate::crypto::ghash::{self, Ghash};
+use crate::utils::constant_time;
+use crate::utils::secret::Secret;
+
+pub const NONCE_LENGTH: usize = 12;
+pub const TAG_LENGTH: usize = 16;
+// 2^39 - 256 bits, the most the 32 bit counter can encrypt before it wraps around into the
+// blocks used for the tag and earlier keystream
+pub const MAX_PLAINTEXT_LENGTH: u64 = (1 << 36) - 32;
+
+pub struct AesGcm {
+    aes: AES,
+    // the hash key H, which is the encryption of the all-zero block
+    h: Secret<[u8; 16]>
+}
+
+impl AesGcm {
+    pub fn new(key: &[u8]) -> Result<Self, AesError> {
+        let aes = AES::new(key)?;
+        let h = Secret::new(aes.encrypt_block(&[0u8; 16]));
+
+        Ok(Self {
+            aes,
+            h
+        })
+    }
+
+    // Encrypts and authenticates the plaintext, and authenticates the associated data.
+    // Returns the ciphertext with the 16 byte tag appended.
+    //
+    // A nonce must never be reused with the same key.
+    pub fn seal(&self, nonce: &[u8; NONCE_LENGTH], associated_data: &[u8], plaintext: &[u8]) -> Result<Vec<u8>, AesError> {
+        if plaintext.len() as u64 > MAX_PLAINTEXT_LENGTH {
+            return Err(AesError::InvalidPlaintextLength(plaintext.len()));
+        }
+
+        let mut output = plaintext.to_vec();
+        self.apply_keystream(nonce, &mut output);
+
+        let tag = self.compute_tag(nonce, associated_data, &output);
+        output.extend_from_slice(&tag);
+
+        Ok(output)
+    }
+
+    // Checks the tag and decrypts data produced by `seal`. Nothing is returned unless the
+    // ciphertext and associated data are authentic.
+    pub fn open(&self, nonce: &[u8; NONCE_LENGTH], associated_data: &[u8], ciphertext_with_tag: &[u8]) -> Result<Vec<u8>, AesError> {
+        if ciphertext_with_tag.len() < TAG_LENGTH || (ciphertext_with_tag.len() - TAG_LENGTH) as u64 > MAX_PLAINTEXT_LENGTH {
+            return Err(AesError::InvalidCiphertextLength(ciphertext_with_tag.len()));
+        }
+
+        let (ciphertext, tag) = ciphertext_with_tag.split_at(ciphertext_with_tag.len() - TAG_LENGTH);
+
+        let expected_tag = self.compute_tag(nonce, associated_data, ciphertext);
+        if !constant_time::compare(&expected_tag, tag) {
+            return Err(AesError::AuthenticationFailed);
+        }
+
+        let mut plaintext = ciphertext.to_vec();
+        self.apply_keystream(nonce, &mut plaintext);
+
+        Ok(plaintext)
+    }
+
+    // Encrypts or decrypts the data in place. The first counter block (J0) is reserved for
+    // the tag, so the keystream starts at J0 + 1.
+    fn apply_keystream(&self, nonce: &[u8; NONCE_LENGTH], data: &mut [u8]) {
+        let mut keystream = Keystream::new(&counter_block(nonce, 2), CounterWidth::Bits32);
+        keystream.apply(&self.aes, data);
+    }
+
+    // GHASH over the zero padded associated data and ciphertext, then a block with the bit
+    // lengths of both, masked with the encryption of J0.
+    fn compute_tag(&self, nonce: &[u8; NONCE_LENGTH], associated_data: &[u8], ciphertext: &[u8]) -> [u8; TAG_LENGTH] {
+        let mut key = Secret::new([0u8; ghash::KEY_LENGTH]);
+        key.expose_mut()[..16].copy_from_slice(self.h.expose());
+        key.expose_mut()[16..].copy_from_slice(&self.aes.encrypt_block(&counter_block(nonce, 1)));
+
+        let mut length_block = [0u8; 16];
+        length_block[..8].copy_from_slice(&(associated_data.len() as u64 * 8).to_be_bytes());
+        length_block[8..].copy_from_slice(&(ciphertext.len() as u64 * 8).to_be_bytes());
+
+        let mut ghash = Ghash::new(key.expose());
+        ghash.update_padded(associated_data);
+        ghash.update_padded(ciphertext);
+        ghash.update(&length_block);
+
+        ghash.finalize_raw()
+    }
+}
+
+// With a 96 bit nonce, each counter block is the nonce followed by a 32 bit big endian counter.
+fn counter_block(nonce: &[u8; NONCE_LENGTH], counter: u32) -> [u8; 16] {
+    let mut block = [0u8; 16];
+    block[..NONCE_LENGTH].copy_from_slice(nonce);
+    block[NONCE_LENGTH..].copy_from_slice(&counter.to_be_bytes());
+    block
+}
+
+#[cfg(test)]
+use crate::utils::formatting::hex_to_bytes;
+
+#[test]
+fn seal_test() {
+    // Test cases 1-4 and 13-16 from the original GCM specification (McGrew & Viega)
+    let plaintext = "d9313225f88406e5a55909c5aff5269a86a7a9531534f7da2e4c303d8a318a72\
+                     1c3c0c95956809532fcf0e2449a6b525b16aedf5aa0de657ba637b391aafd255";
+    let associated_data = "feedfacedeadbeeffeedfacedeadbeefabaddad2";
+
+    let test_cases = [
+        // (key, nonce, plaintext, associated data, ciphertext, tag)
+        ("00000000000000000000000000000000", "000000000000000000000000", "", "", "",
+         "58e2fccefa7e3061367f1d57a4e7455a"),
+        ("00000000000000000000000000000000", "000000000000000000000000", "00000000000000000000000000000000", "",
+         "0388dace60b6a392f328c2b971b2fe78", "ab6e47d42cec13bdf53a67b21257bddf"),
+        ("feffe9928665731c6d6a8f9467308308", "cafebabefacedbaddecaf888", plaintext, "",
+         "42831ec2217774244b7221b784d0d49ce3aa212f2c02a4e035c17e2329aca12e\
+          21d514b25466931c7d8f6a5aac84aa051ba30b396a0aac973d58e091473f5985",
+         "4d5c2af327cd64a62cf35abd2ba6fab4"),
+        ("feffe9928665731c6d6a8f9467308308", "cafebabefacedbaddecaf888", &plaintext[..120], associated_data,
+         "42831ec2217774244b7221b784d0d49ce3aa212f2c02a4e035c17e2329aca12e\
+          21d514b25466931c7d8f6a5aac84aa051ba30b396a0aac973d58e091",
+         "5bc94fbc3221a5db94fae95ae7121a47"),
+        ("0000000000000000000000000000000000000000000000000000000000000000", "000000000000000000000000", "", "", "",
+         "530f8afbc74536b9a963b4f1c4cb738b"),
+        ("0000000000000000000000000000000000000000000000000000000000000000", "000000000000000000000000",
+         "00000000000000000000000000000000", "",
+         "cea7403d4d606b6e074ec5d3baf39d18", "d0d1c8a799996bf0265b98b5d48ab919"),
+        ("feffe9928665731c6d6a8f9467308308feffe9928665731c6d6a8f9467308308", "cafebabefacedbaddecaf888", plaintext, "",
+         "522dc1f099567d07f47f37a32a84427d643a8cdcbfe5c0c97598a2bd2555d1aa\
+          8cb08e48590dbb3da7b08b1056828838c5f61e6393ba7a0abcc9f662898015ad",
+         "b094dac5d93471bdec1a502270e3cc6c"),
+        ("feffe9928665731c6d6a8f9467308308feffe9928665731c6d6a8f9467308308", "cafebabefacedbaddecaf888", &plaintext[..120], associated_data,
+         "522dc1f099567d07f47f37a32a84427d643a8cdcbfe5c0c97598a2bd2555d1aa\
+          8cb08e48590dbb3da7b08b1056828838c5f61e6393ba7a0abcc9f662",
+         "76fc6ece0f4e1768cddf8853bb2d551b"),
+    ];
+
+    for (key, nonce, plaintext, associated_data, ciphertext, tag) in test_cases {
+        let gcm = AesGcm::new(&hex_to_bytes(key).unwrap()).unwrap();
+        let nonce: [u8; NONCE_LENGTH] = hex_to_bytes(nonce).unwrap().try_into().unwrap();
+        let plaintext = hex_to_bytes(plaintext).unwrap();
+        let associated_data = hex_to_bytes(associated_data).unwrap();
+
+        let mut expected = hex_to_bytes(ciphertext).unwrap();
+        expected.extend_from_slice(&hex_to_bytes(tag).unwrap());
+
+        let sealed = gcm.seal(&nonce, &associated_data, &plaintext).unwrap();
+        assert_eq!(sealed, expected);
+        assert_eq!(gcm.open(&nonce, &associated_data, &sealed).unwrap(), plaintext);
+    }
+}
+
+#[test]
+fn open_tampered_test() {
+    let gcm = AesGcm::new(&[0x42; 16]).unwrap();
+    let nonce = [0x24; NONCE_LENGTH];
+    let sealed = gcm.seal(&nonce, b"header", b"attack at dawn").unwrap();
+
+    // flipping any bit of the ciphertext or tag must be detected
+    for i in 0..sealed.len() {
+        let mut tampered = sealed.clone();
+        tampered[i] ^= 0x80;
+        assert_eq!(gcm.open(&nonce, b"header", &tampered), Err(AesError::AuthenticationFailed));
+    }
+
+    assert_eq!(gcm.open(&nonce, b"footer", &sealed), Err(AesError::AuthenticationFailed));
+    assert_eq!(gcm.open(&[0x25; NONCE_LENGTH], b"header", &sealed), Err(AesError::AuthenticationFailed));
+    assert_eq!(gcm.open(&nonce, b"header", &sealed[..15]), Err(AesError::InvalidCiphertextLength(15)));
+}
+
+#[test]
+fn plaintext_length_limit_test() {
+    // The longest plaintext ends exactly at the last 32 bit counter value, so no counter
+    // block is used twice. Anything longer can't be allocated here, so only the limit itself
+    // is checked against the keystream.
+    let gcm = AesGcm::new(&[0x42; 16]).unwrap();
+    let nonce = [0x24; NONCE_LENGTH];
+    assert_eq!(MAX_PLAINTEXT_LENGTH % 16, 0);
+
+    let mut last_block = [0u8; 16];
+    let mut keystream = Keystream::new(&counter_block(&nonce, 2), CounterWidth::Bits32);
+    keystream.seek(MAX_PLAINTEXT_LENGTH / 16 - 1);
+    keystream.apply(&gcm.aes, &mut last_block);
+    assert_eq!(last_block, gcm.aes.encrypt_block(&counter_block(&nonce, u32::MAX)));
+
+    // one block further the counter wraps to 0, and the block after that would reuse J0,
+    // which masks the tag
+    let mut wrapped_block = [0u8; 16];
+    keystream.apply(&gcm.aes, &mut wrapped_block);
+    assert_eq!(wrapped_block, gcm.aes.encrypt_block(&counter_block(&nonce, 0)));
+}
+
+#[cfg(test)]
+use crate::utils::test_vectors::wycheproof;
+
+
+#[test]
+fn wycheproof_test() {
+    let groups = wycheproof::load("aes_gcm_test.json");
+
+    // only 96 bit nonces and full length tags are supported, the other groups are skipped
+    for group in groups.iter().filter(|group| group.usize("ivSize") == 96 && group.usize("tagSize") == 128) {
+        for test in &group.tests {
+            let (Ok(gcm), Ok(nonce)) = (AesGcm::new(&test.hex("key")), test.hex("iv").try_into()) else {
+                test.check(false);
+                continue;
+            };
+            let (associated_data, message) = (test.hex("aad"), test.hex("msg"));
+            let ciphertext_with_tag = [test.hex("ct"), test.hex("tag")].concat();
+
+            if test.result == wycheproof::Expectation::Valid {
+                assert_eq!(gcm.seal(&nonce, &associated_data, &message).unwrap(), ciphertext_with_tag, "{test}");
+            }
+            test.check(gcm.open(&nonce, &associated_data, &ciphertext_with_tag) == Ok(message));
+        }
+    }
+}
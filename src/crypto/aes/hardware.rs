@@ -0,0 +1,90 @@
+// AES using the CPU's own instructions, when it has them.
+//
+// On x86_64 this is AES-NI, and on aarch64 the AESE/AESMC instructions from the crypto
+// extensions. Support is detected at runtime, and the type here can only be created once it
+// has been, so holding one is the proof that the unsafe intrinsics below may be called.
+//
+// The instructions run in constant time, and are much faster than the portable code, which
+// stays as the fallback for every other CPU.
+
+use super::key_schedule::KeySchedule;
+
+#[cfg(target_arch = "x86_64")]
+use std::arch::x86_64::*;
+#[cfg(target_arch = "aarch64")]
+use std::arch::aarch64::*;
+
+// Hardware AES block encryption.
+#[derive(Clone, Copy)]
+pub struct AesInstructions {
+    _detected: ()
+}
+
+impl AesInstructions {
+    pub fn detect() -> Option<Self> {
+        #[cfg(target_arch = "x86_64")]
+        if is_x86_feature_detected!("aes") && is_x86_feature_detected!("sse2") {
+            return Some(Self { _detected: () });
+        }
+
+        #[cfg(target_arch = "aarch64")]
+        if std::arch::is_aarch64_feature_detected!("aes") {
+            return Some(Self { _detected: () });
+        }
+
+        None
+    }
+
+    // Encrypts one block with the round keys from the portable key schedule.
+    pub fn encrypt_block(&self, key_schedule: &KeySchedule, input: &[u8; 16]) -> [u8; 16] {
+        #[cfg(target_arch = "x86_64")]
+        return unsafe { encrypt_block_x86(key_schedule, input) };
+
+        #[cfg(target_arch = "aarch64")]
+        return unsafe { encrypt_block_aarch64(key_schedule, input) };
+
+        #[cfg(not(any(target_arch = "x86_64", target_arch = "aarch64")))]
+        unreachable!("AES instructions are never detected on this architecture")
+    }
+}
+
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "aes,sse2")]
+fn encrypt_block_x86(key_schedule: &KeySchedule, input: &[u8; 16]) -> [u8; 16] {
+    let rounds = key_schedule.rounds();
+    let load = |bytes: &[u8]| {
+        let value = u128::from_le_bytes(bytes.try_into().unwrap());
+        _mm_set_epi64x((value >> 64) as i64, value as i64)
+    };
+
+    let mut state = _mm_xor_si128(load(input), load(key_schedule.get_round_key(0)));
+    for round in 1..rounds {
+        state = _mm_aesenc_si128(state, load(key_schedule.get_round_key(round)));
+    }
+    state = _mm_aesenclast_si128(state, load(key_schedule.get_round_key(rounds)));
+
+    let low = _mm_cvtsi128_si64(state) as u64 as u128;
+    let high = _mm_cvtsi128_si64(_mm_unpackhi_epi64(state, state)) as u64 as u128;
+    ((high << 64) | low).to_le_bytes()
+}
+
+// AESE does AddRoundKey, SubBytes and ShiftRows, so the key addition comes first in each
+// round and the last round key is XORed on at the end.
+#[cfg(target_arch = "aarch64")]
+#[target_feature(enable = "aes,neon")]
+fn encrypt_block_aarch64(key_schedule: &KeySchedule, input: &[u8; 16]) -> [u8; 16] {
+    let rounds = key_schedule.rounds();
+    // the round keys are always 16 bytes, so the loads read within bounds
+    let load = |bytes: &[u8]| unsafe { vld1q_u8(bytes[..16].as_ptr()) };
+
+    let mut state = load(input);
+    for round in 0..rounds - 1 {
+        state = vaesmcq_u8(vaeseq_u8(state, load(key_schedule.get_round_key(round))));
+    }
+    state = vaeseq_u8(state, load(key_schedule.get_round_key(rounds - 1)));
+    state = veorq_u8(state, load(key_schedule.get_round_key(rounds)));
+
+    let mut output = [0u8; 16];
+    unsafe { vst1q_u8(output.as_mut_ptr(), state) };
+    output
+}
@@ -4,39 +4,56 @@
 // be used in each round of the encryption process.
 
 
+use super::error::AesError;
+use super::sbox::sub_word;
+use crate::utils::secret::Secret;
+
 pub struct KeySchedule {
-    round_keys: Vec<u8>,
+    round_keys: Secret<Vec<u8>>,
     rounds: usize
 }
 
 impl KeySchedule {
-    pub fn new(key: &[u8], sbox: &[u8;256]) -> Self {
-        let round_keys = expand_key(key, sbox);
-        Self {
+    pub fn new(key: &[u8]) -> Result<Self, AesError> {
+        // 10 rounds for AES-128, 12 for AES-192 and 14 for AES-256,
+        // +1 for the initial key in each case
+        let rounds = match key.len() {
+            16 => 10,
+            24 => 12,
+            32 => 14,
+            _ => return Err(AesError::InvalidKeyLength(key.len()))
+        };
+
+        let round_keys = Secret::new(expand_key(key, rounds));
+        Ok(Self {
             round_keys,
-            rounds: 10 // 10 rounds for AES-128, +1 for initial key for total of 11
-        }
+            rounds
+        })
+    }
+
+    pub fn rounds(&self) -> usize {
+        self.rounds
     }
 
     pub fn get_round_key(&self, round: usize) -> &[u8] {
         let start = round * 16;
-        &self.round_keys[start..start + 16]
+        &self.round_keys.expose()[start..start + 16]
     }
 }
 
-fn expand_key(key: &[u8], sbox: &[u8;256]) -> Vec<u8> {
-    let rounds = 10; // For AES-128
-    let round_key_size = 16; // 128 bits = 16 bytes
+fn expand_key(key: &[u8], rounds: usize) -> Vec<u8> {
+    let round_key_size = 16; // round keys are always one block, regardless of key size
+    let key_size = key.len();
     let total_size = round_key_size * (rounds + 1);
     let mut expanded_key = vec![0u8;total_size];
     let rcon = generate_rcon();
 
-    // Copy the original key into the first round key
-    expanded_key[..16].copy_from_slice(&key[..16]);
+    // Copy the original key into the first round key(s)
+    expanded_key[..key_size].copy_from_slice(key);
 
     // Now generate the remaining round keys
     let mut i = 1;
-    let mut pos = 16;
+    let mut pos = key_size;
 
     while pos < total_size {
         let mut temp = [
@@ -46,16 +63,19 @@ fn expand_key(key: &[u8], sbox: &[u8;256]) -> Vec<u8> {
             expanded_key[pos - 1],
         ];
 
-        if pos % 16 == 0 {
+        if pos.is_multiple_of(key_size) {
             temp = rot_word(temp);
-            temp = sub_word(temp, sbox);
+            temp = sub_word(temp);
             temp[0] ^= rcon[i - 1];
             i += 1;
+        } else if key_size == 32 && pos % key_size == 16 {
+            // AES-256 applies an extra SubWord halfway through each key-sized chunk
+            temp = sub_word(temp);
         }
 
-        // XOR with bytes 16 positions earlier
+        // XOR with bytes one key length earlier
         for j in 0..4 {
-            expanded_key[pos + j] = expanded_key[pos - 16 + j] ^ temp[j];
+            expanded_key[pos + j] = expanded_key[pos - key_size + j] ^ temp[j];
         }
 
         pos += 4;
@@ -71,7 +91,7 @@ fn generate_rcon() -> [u8;10] {
     for i in 0..10 {
         rcon[i] = x;
         // Multiply it by 2 in GF(2^8)
-        x = (x << 1) ^ ((x >> 7) & 0x1b);
+        x = (x << 1) ^ ((x >> 7) * 0x1b);
     }
 
     rcon
@@ -81,11 +101,3 @@ fn generate_rcon() -> [u8;10] {
 fn rot_word(word: [u8;4]) -> [u8;4] {
     [word[1], word[2], word[3], word[0]]
 }
-
-fn sub_word(word: [u8;4], sbox: &[u8; 256]) -> [u8;4] {
-    let mut result = [0u8;4];
-    for i in 0..4 {
-        result[i] = sbox[word[i] as usize]
-    }
-    result
-}
\ No newline at end of file
@@ -4,71 +4,200 @@
 // differential cryptanalysis. It works in two main mathematical steps:
 // 1. Find the multiplicative inverse in the Galois Field GF(2^8)
 // 2. Applying an affine transformation over GF(2)
+//
+// A lookup table indexed by the (secret) state bytes leaks those bytes through cache timing,
+// so the S-Box is computed instead. The 16 bytes of a block are bitsliced into 8 "planes",
+// where plane i holds bit i of every byte, and the inverse is computed for all of them at once
+// with AND and XOR. Nothing here branches on or indexes memory by the input.
 
-pub fn gen_sbox() -> [u8; 256] {
-    let mut sbox = [0u8; 256];
+// Applies the S-Box to every byte of a block.
+pub fn sub_bytes(bytes: &mut [u8; 16]) {
+    let inverse = from_planes(&plane_inverse(&to_planes(bytes)));
+    for (byte, value) in bytes.iter_mut().zip(inverse) {
+        *byte = affine_transform(value);
+    }
+}
 
-    for i in 0..256 {
-        let inverse = gf_inverse(i as u8);
-        sbox[i] = affine_transform(inverse);
+// Applies the inverse S-Box to every byte of a block. Undoes the affine transformation first,
+// then inverts, since the inverse in GF(2^8) is its own inverse.
+pub fn inv_sub_bytes(bytes: &mut [u8; 16]) {
+    for byte in bytes.iter_mut() {
+        *byte = inv_affine_transform(*byte);
     }
+    *bytes = from_planes(&plane_inverse(&to_planes(bytes)));
+}
 
-    sbox
+// Applies the S-Box to a 4-byte word of the key schedule.
+pub fn sub_word(word: [u8; 4]) -> [u8; 4] {
+    let mut block = [0u8; 16];
+    block[..4].copy_from_slice(&word);
+    sub_bytes(&mut block);
+    [block[0], block[1], block[2], block[3]]
 }
 
+// Multiplies by x in GF(2^8), reducing with the AES polynomial when the top bit is set.
+pub fn xtime(b: u8) -> u8 {
+    (b << 1) ^ ((b >> 7).wrapping_neg() & 0x1b) // AES irreducible polynomial: x^8 + x^4 + x^3 + x + 1
+}
 
 // Formula to multiply two numbers in GF(2^8). Uses standard multiplication algorithm, just with
-// XOR instead of additions.
-fn gf_multiply(mut a: u8, mut b: u8) -> u8 {
+// XOR instead of additions, and masks instead of branches on the bits.
+pub fn gf_multiply(mut a: u8, mut b: u8) -> u8 {
     let mut product = 0;
-    let mut hi_bit_set;
 
     for _ in 0..8 {
-        if b & 1 == 1 {
-            product ^= a;
-        }
-        hi_bit_set = a & 0x80;
-        a <<= 1;
-        if hi_bit_set == 0x80 {
-            a ^= 0x1b; // AES irreducible polynomial: x^8 + x^4 + x^3 + x + 1
-        }
+        product ^= a & (b & 1).wrapping_neg();
+        a = xtime(a);
         b >>= 1;
     }
     product
 }
 
-// Function that finds the multiplicative inverse in GF(2^8)
-fn gf_inverse(x: u8) -> u8 {
-    if x == 0 {
-        return 0;
+// Splits the block into bit planes: bit j of plane i is bit i of byte j.
+fn to_planes(bytes: &[u8; 16]) -> [u16; 8] {
+    let mut planes = [0u16; 8];
+
+    for (bit, plane) in planes.iter_mut().enumerate() {
+        for (lane, &byte) in bytes.iter().enumerate() {
+            *plane |= (((byte >> bit) & 1) as u16) << lane;
+        }
+    }
+
+    planes
+}
+
+fn from_planes(planes: &[u16; 8]) -> [u8; 16] {
+    let mut bytes = [0u8; 16];
+
+    for (lane, byte) in bytes.iter_mut().enumerate() {
+        for (bit, &plane) in planes.iter().enumerate() {
+            *byte |= (((plane >> lane) & 1) as u8) << bit;
+        }
     }
 
-    let mut x_power = x;
-    let mut result = 1;
+    bytes
+}
+
+// Multiplies 16 pairs of field elements at once.
+fn plane_multiply(a: &[u16; 8], b: &[u16; 8]) -> [u16; 8] {
+    let mut product = [0u16; 15];
 
-    // Fermat's Little Theorem: a^254 = a^(-1) mod p in GF(2^8)
-    // Therefore, we need 7 multiplications to compute x^254
-    for _ in 0..7 {
-        result = gf_multiply(result, x_power);
-        x_power = gf_multiply(x_power, x_power)
+    for (i, &a_plane) in a.iter().enumerate() {
+        for (j, &b_plane) in b.iter().enumerate() {
+            product[i + j] ^= a_plane & b_plane;
+        }
     }
 
+    plane_reduce(product)
+}
+
+// Squaring is linear in GF(2^8): the coefficient of x^i moves to x^2i.
+fn plane_square(a: &[u16; 8]) -> [u16; 8] {
+    let mut product = [0u16; 15];
+
+    for (i, &plane) in a.iter().enumerate() {
+        product[2 * i] = plane;
+    }
+
+    plane_reduce(product)
+}
+
+// Reduces a product of degree up to 14 using x^8 = x^4 + x^3 + x + 1, working down from the
+// top so that terms folded into degrees of 8 or more are reduced again.
+fn plane_reduce(mut product: [u16; 15]) -> [u16; 8] {
+    for k in (8..15).rev() {
+        product[k - 4] ^= product[k];
+        product[k - 5] ^= product[k];
+        product[k - 7] ^= product[k];
+        product[k - 8] ^= product[k];
+    }
+
+    let mut result = [0u16; 8];
+    result.copy_from_slice(&product[..8]);
     result
 }
 
+// Fermat's Little Theorem: a^254 = a^(-1) in GF(2^8), which also maps 0 to 0 as AES requires.
+// 254 = 240 + 14, with x^240 = (x^15)^16, x^15 = x^12 * x^3 and x^14 = x^12 * x^2.
+fn plane_inverse(x: &[u16; 8]) -> [u16; 8] {
+    let x2 = plane_square(x);
+    let x3 = plane_multiply(&x2, x);
+    let x12 = plane_square(&plane_square(&x3));
+    let x15 = plane_multiply(&x12, &x3);
+    let x14 = plane_multiply(&x12, &x2);
+
+    let mut x240 = x15;
+    for _ in 0..4 {
+        x240 = plane_square(&x240);
+    }
+
+    plane_multiply(&x240, &x14)
+}
+
+// b'_i = b_i ^ b_(i+4) ^ b_(i+5) ^ b_(i+6) ^ b_(i+7) ^ c_i, written as byte rotations.
 fn affine_transform(x: u8) -> u8 {
-    let mut result = 0;
-    let c = 0x64; // The constant vector used in AES
-
-    for i in 0..8 {
-        let bit = ((x >> i) & 1)
-                ^ ((x >> ((i + 1) % 8)) & 1)
-                ^ ((x >> ((i + 2) % 8)) & 1)
-                ^ ((x >> ((i + 3) % 8)) & 1)
-                ^ ((x >> ((i + 4) % 8)) & 1);
-        result |= bit << i;
+    let c = 0x63; // The constant vector used in AES
+    x ^ x.rotate_left(1) ^ x.rotate_left(2) ^ x.rotate_left(3) ^ x.rotate_left(4) ^ c
+}
+
+// b_i = b'_(i+2) ^ b'_(i+5) ^ b'_(i+7) ^ d_i
+fn inv_affine_transform(x: u8) -> u8 {
+    let d = 0x05;
+    x.rotate_left(1) ^ x.rotate_left(3) ^ x.rotate_left(6) ^ d
+}
+
+// The table based S-Box this module used to build for every cipher, kept to check the
+// computed S-Box against and to compare throughput with.
+#[cfg(test)]
+pub fn gen_sbox() -> [u8; 256] {
+    let mut sbox = [0u8; 256];
+
+    for (i, value) in sbox.iter_mut().enumerate() {
+        // x^254 one multiplication at a time
+        let mut inverse = 1;
+        for _ in 0..254 {
+            inverse = gf_multiply(inverse, i as u8);
+        }
+        *value = affine_transform(if i == 0 { 0 } else { inverse });
     }
 
-    result ^= c;
-    result
-}
\ No newline at end of file
+    sbox
+}
+
+#[cfg(test)]
+pub fn gen_inv_sbox(sbox: &[u8; 256]) -> [u8; 256] {
+    let mut inv_sbox = [0u8; 256];
+
+    for (i, &value) in sbox.iter().enumerate() {
+        inv_sbox[value as usize] = i as u8;
+    }
+
+    inv_sbox
+}
+
+#[test]
+fn sbox_test() {
+    let sbox = gen_sbox();
+    let inv_sbox = gen_inv_sbox(&sbox);
+
+    // FIPS-197 figure 7
+    assert_eq!(sbox[0x00], 0x63);
+    assert_eq!(sbox[0x53], 0xed);
+    assert_eq!(sbox[0xff], 0x16);
+
+    for chunk in 0..16 {
+        let input: [u8; 16] = core::array::from_fn(|i| (chunk * 16 + i) as u8);
+
+        let mut output = input;
+        sub_bytes(&mut output);
+        for (x, y) in input.iter().zip(output) {
+            assert_eq!(y, sbox[*x as usize]);
+        }
+
+        let mut output = input;
+        inv_sub_bytes(&mut output);
+        for (x, y) in input.iter().zip(output) {
+            assert_eq!(y, inv_sbox[*x as usize]);
+        }
+    }
+}
@@ -1,3 +1,5 @@
+use super::sbox::{self, gf_multiply, xtime};
+
 // Represents the current state of the AES encryption process.
 // Maintained as a 4x4 matrix of bytes per the AES specification.
 // Data is stored in column-major order to match AESs mathematical structure.
@@ -48,12 +50,10 @@ impl State {
     // This transformation processes each byte of the state matrix independently,
     // replacing it with its corresponding value from the s-box.
     // Provides non-linearity to the cipher.
-    pub fn sub_bytes(&mut self, sbox: &[u8; 256]) {
-        for row in 0..4 {
-            for col in 0..4 {
-                self.data[row][col] = sbox[self.data[row][col] as usize]
-            }
-        }
+    pub fn sub_bytes(&mut self) {
+        let mut bytes = self.flatten();
+        sbox::sub_bytes(&mut bytes);
+        self.unflatten(&bytes);
     }
 
     // Applies the ShiftRows transformation to the current state.
@@ -83,12 +83,7 @@ impl State {
     // Multiplies it with a fixed polynomial c(x) = '03'x^3 + '01'x^2 + '01'x + '02' mod x^4 + 1
     pub fn mix_columns(&mut self) {
         fn multiply_by_2(b: u8) -> u8 {
-            let shifted = b << 1;
-            if (b & 0x80) != 0 {
-                shifted ^ 0x1B
-            } else {
-                shifted
-            }
+            xtime(b)
         }
 
         fn multiply_by_3(b: u8) -> u8 {
@@ -130,19 +125,104 @@ impl State {
         }
     }
 
-    // Converts the current state back into a vector of bytes.
+    // Applies the InvSubBytes transformation to the current state.
+    //
+    // The inverse of SubBytes, replacing each byte with its corresponding value from the
+    // inverse s-box.
+    pub fn inv_sub_bytes(&mut self) {
+        let mut bytes = self.flatten();
+        sbox::inv_sub_bytes(&mut bytes);
+        self.unflatten(&bytes);
+    }
+
+    // SubBytes with a lookup table, the way it was done before the s-box was computed in
+    // constant time. Only used to check against and benchmark the computed version.
+    #[cfg(test)]
+    pub fn sub_bytes_with_table(&mut self, sbox: &[u8; 256]) {
+        for row in 0..4 {
+            for col in 0..4 {
+                self.data[row][col] = sbox[self.data[row][col] as usize]
+            }
+        }
+    }
+
+    // Applies the InvShiftRows transformation to the current state.
+    //
+    // The inverse of ShiftRows, cyclically shifting each row of the state matrix to the right
+    // by the same offsets that ShiftRows shifted it to the left.
+    pub fn inv_shift_rows(&mut self) {
+        for (row, data) in self.data.iter_mut().enumerate() {
+            data.rotate_right(row);
+        }
+    }
+
+    // Applies the InvMixColumns transformation to the current state.
+    //
+    // Multiplies each column with the inverse of the MixColumns polynomial:
+    // c^-1(x) = '0b'x^3 + '0d'x^2 + '09'x + '0e' mod x^4 + 1
+    pub fn inv_mix_columns(&mut self) {
+        for col in 0..4 {
+            let original = [
+                self.data[0][col],
+                self.data[1][col],
+                self.data[2][col],
+                self.data[3][col],
+            ];
+
+            self.data[0][col] =
+                gf_multiply(original[0], 0x0e) ^
+                    gf_multiply(original[1], 0x0b) ^
+                    gf_multiply(original[2], 0x0d) ^
+                    gf_multiply(original[3], 0x09);
+
+            self.data[1][col] =
+                gf_multiply(original[0], 0x09) ^
+                    gf_multiply(original[1], 0x0e) ^
+                    gf_multiply(original[2], 0x0b) ^
+                    gf_multiply(original[3], 0x0d);
+
+            self.data[2][col] =
+                gf_multiply(original[0], 0x0d) ^
+                    gf_multiply(original[1], 0x09) ^
+                    gf_multiply(original[2], 0x0e) ^
+                    gf_multiply(original[3], 0x0b);
+
+            self.data[3][col] =
+                gf_multiply(original[0], 0x0b) ^
+                    gf_multiply(original[1], 0x0d) ^
+                    gf_multiply(original[2], 0x09) ^
+                    gf_multiply(original[3], 0x0e);
+        }
+    }
+
+    // Converts the current state back into a block of bytes.
     //
     // The bytes are extracted from the state matrix in column-major order, so that is matches the
     // original ordering used to create the state.
-    pub fn as_bytes(&self) -> Vec<u8> {
-        let mut result = Vec::with_capacity(16);
+    pub fn as_bytes(&self) -> [u8; 16] {
+        let mut result = [0u8; 16];
 
         for col in 0..4 {
             for row in 0..4 {
-                result.push(self.data[row][col]);
+                result[col * 4 + row] = self.data[row][col];
             }
         }
 
         result
     }
+
+    // The s-box works on each byte independently, so the order of the bytes doesn't matter.
+    fn flatten(&self) -> [u8; 16] {
+        let mut bytes = [0u8; 16];
+        for (chunk, row) in bytes.chunks_mut(4).zip(self.data.iter()) {
+            chunk.copy_from_slice(row);
+        }
+        bytes
+    }
+
+    fn unflatten(&mut self, bytes: &[u8; 16]) {
+        for (chunk, row) in bytes.chunks(4).zip(self.data.iter_mut()) {
+            row.copy_from_slice(chunk);
+        }
+    }
 }
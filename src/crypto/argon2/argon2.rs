@@ -0,0 +1,432 @@
+// The Argon2 memory-hard password hash, as specified in RFC 9106 (version 0x13).
+//
+// Argon2 fills `memory_cost` KiB of memory with 1 KiB blocks, each computed from the previous
+// block and a pseudo-randomly chosen earlier one, so guessing passwords needs that much memory
+// per guess. Argon2id is the recommended variant; Argon2d is included because KeePass KDBX4
+// files may use it. The lanes are computed one after the other rather than on separate threads,
+// which gives the same output.
+
+use std::fmt::{Display, Formatter};
+use crate::crypto::blake2b::blake2b::{self, Blake2b};
+use crate::utils::secret::Secret;
+use crate::utils::zeroize::{zeroize, zeroize_words};
+
+const VERSION: u32 = 0x13;
+const BLOCK_WORDS: usize = 128;
+const SYNC_POINTS: u32 = 4;
+// number of reference indices in one generated address block
+const ADDRESSES_PER_BLOCK: u32 = 128;
+
+type Block = [u64; BLOCK_WORDS];
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum Variant {
+    Argon2d = 0,
+    Argon2i = 1,
+    Argon2id = 2
+}
+
+pub struct Params {
+    // memory size in KiB, at least 8 * parallelism
+    pub memory_cost: u32,
+    // number of passes over the memory
+    pub iterations: u32,
+    // number of independent lanes
+    pub parallelism: u32,
+    // tag length in bytes, at least 4
+    pub output_length: u32
+}
+
+#[derive(Debug, PartialEq)]
+pub enum Argon2Error {
+    InvalidParallelism(u32),
+    InvalidIterations(u32),
+    MemoryTooSmall(u32),
+    OutputTooShort(u32),
+    SaltTooShort(usize),
+}
+
+impl Display for Argon2Error {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Argon2Error::InvalidParallelism(lanes) => write!(f, "Argon2 parallelism {} is not between 1 and 2^24 - 1", lanes),
+            Argon2Error::InvalidIterations(passes) => write!(f, "Argon2 needs at least one iteration, got {}", passes),
+            Argon2Error::MemoryTooSmall(kib) => write!(f, "Argon2 memory of {} KiB is less than 8 KiB per lane", kib),
+            Argon2Error::OutputTooShort(length) => write!(f, "Argon2 output of {} bytes is shorter than 4 bytes", length),
+            Argon2Error::SaltTooShort(length) => write!(f, "Argon2 salt of {} bytes is shorter than 8 bytes", length),
+        }
+    }
+}
+
+// Argon2id without a secret key or associated data.
+pub fn argon2id(password: &[u8], salt: &[u8], params: &Params) -> Result<Secret<Vec<u8>>, Argon2Error> {
+    hash(Variant::Argon2id, password, salt, &[], &[], params)
+}
+
+pub fn hash(
+    variant: Variant,
+    password: &[u8],
+    salt: &[u8],
+    secret: &[u8],
+    associated_data: &[u8],
+    params: &Params
+) -> Result<Secret<Vec<u8>>, Argon2Error> {
+    if params.parallelism == 0 || params.parallelism > 0x00ff_ffff {
+        return Err(Argon2Error::InvalidParallelism(params.parallelism));
+    }
+    if params.iterations == 0 {
+        return Err(Argon2Error::InvalidIterations(params.iterations));
+    }
+    if params.memory_cost < 8 * params.parallelism {
+        return Err(Argon2Error::MemoryTooSmall(params.memory_cost));
+    }
+    if params.output_length < 4 {
+        return Err(Argon2Error::OutputTooShort(params.output_length));
+    }
+    if salt.len() < 8 {
+        return Err(Argon2Error::SaltTooShort(salt.len()));
+    }
+
+    // H0, the 64 byte digest of all the parameters and inputs
+    let mut h0_hasher = Blake2b::new(64);
+    for value in [params.parallelism, params.output_length, params.memory_cost, params.iterations, VERSION, variant as u32] {
+        h0_hasher.update(&value.to_le_bytes());
+    }
+    for input in [password, salt, secret, associated_data] {
+        h0_hasher.update(&(input.len() as u32).to_le_bytes());
+        h0_hasher.update(input);
+    }
+    let mut h0 = h0_hasher.finalize();
+
+    let mut memory = Memory::new(variant, params);
+    memory.initialize(&h0);
+    memory.fill();
+    let tag = memory.finalize(params.output_length);
+
+    zeroize(&mut h0);
+    Ok(Secret::new(tag))
+}
+
+struct Memory {
+    blocks: Vec<Block>,
+    variant: Variant,
+    lanes: u32,
+    iterations: u32,
+    lane_length: u32,
+    segment_length: u32
+}
+
+impl Memory {
+    fn new(variant: Variant, params: &Params) -> Self {
+        // round the memory down to a multiple of 4 * lanes blocks
+        let segment_length = params.memory_cost / (SYNC_POINTS * params.parallelism);
+        let lane_length = segment_length * SYNC_POINTS;
+
+        Self {
+            blocks: vec![[0u64; BLOCK_WORDS]; (lane_length * params.parallelism) as usize],
+            variant,
+            lanes: params.parallelism,
+            iterations: params.iterations,
+            lane_length,
+            segment_length
+        }
+    }
+
+    fn index(&self, lane: u32, column: u32) -> usize {
+        (lane * self.lane_length + column) as usize
+    }
+
+    // B[i][0] = H'(H0 || 0 || i) and B[i][1] = H'(H0 || 1 || i)
+    fn initialize(&mut self, h0: &[u8]) {
+        for lane in 0..self.lanes {
+            for column in 0..2u32 {
+                let mut input = h0.to_vec();
+                input.extend_from_slice(&column.to_le_bytes());
+                input.extend_from_slice(&lane.to_le_bytes());
+
+                let mut bytes = variable_length_hash(&input, 1024);
+                let index = self.index(lane, column);
+                self.blocks[index] = bytes_to_block(&bytes);
+
+                zeroize(&mut input);
+                zeroize(&mut bytes);
+            }
+        }
+    }
+
+    fn fill(&mut self) {
+        for pass in 0..self.iterations {
+            for slice in 0..SYNC_POINTS {
+                for lane in 0..self.lanes {
+                    self.fill_segment(pass, slice, lane);
+                }
+            }
+        }
+    }
+
+    fn fill_segment(&mut self, pass: u32, slice: u32, lane: u32) {
+        // Argon2i always, and Argon2id in the first half of the first pass, pick reference blocks
+        // independently of the password to resist side-channel attacks
+        let data_independent = match self.variant {
+            Variant::Argon2d => false,
+            Variant::Argon2i => true,
+            Variant::Argon2id => pass == 0 && slice < SYNC_POINTS / 2
+        };
+
+        let mut address_input = [0u64; BLOCK_WORDS];
+        let mut addresses = [0u64; BLOCK_WORDS];
+        address_input[..6].copy_from_slice(&[
+            pass as u64, lane as u64, slice as u64,
+            self.blocks.len() as u64, self.iterations as u64, self.variant as u64
+        ]);
+
+        // the first two blocks of each lane come from initialize
+        let start = if pass == 0 && slice == 0 { 2 } else { 0 };
+
+        for index in start..self.segment_length {
+            let column = slice * self.segment_length + index;
+            let previous_column = if column == 0 { self.lane_length - 1 } else { column - 1 };
+            let previous = self.blocks[self.index(lane, previous_column)];
+
+            let pseudo_random = if data_independent {
+                if index % ADDRESSES_PER_BLOCK == 0 || index == start {
+                    // address block = G(0, G(0, input)), with a counter in word 6
+                    address_input[6] += 1;
+                    let zero = [0u64; BLOCK_WORDS];
+                    addresses = compress(&zero, &compress(&zero, &address_input));
+                }
+                addresses[(index % ADDRESSES_PER_BLOCK) as usize]
+            } else {
+                previous[0]
+            };
+
+            // J2 picks the lane, except in the first slice of the first pass
+            let reference_lane = if pass == 0 && slice == 0 {
+                lane
+            } else {
+                ((pseudo_random >> 32) % self.lanes as u64) as u32
+            };
+            let reference_column = self.reference_column(pass, slice, index, reference_lane == lane, pseudo_random as u32);
+            let reference = self.blocks[self.index(reference_lane, reference_column)];
+
+            let mut new_block = compress(&previous, &reference);
+            let current = self.index(lane, column);
+            // from the second pass on, version 0x13 XORs the new block into the old one
+            if pass > 0 {
+                for (new_word, old_word) in new_block.iter_mut().zip(self.blocks[current].iter()) {
+                    *new_word ^= old_word;
+                }
+            }
+            self.blocks[current] = new_block;
+        }
+    }
+
+    // Maps J1 onto the blocks that may be referenced (RFC 9106 section 3.4.1.2): every finished
+    // segment of the lane except the one being overwritten, plus, in the same lane, the blocks
+    // of the current segment before the previous one.
+    fn reference_column(&self, pass: u32, slice: u32, index: u32, same_lane: bool, j1: u32) -> u32 {
+        let finished_blocks = if pass == 0 {
+            slice * self.segment_length
+        } else {
+            self.lane_length - self.segment_length
+        };
+
+        let reference_area_size = if same_lane {
+            finished_blocks + index - 1
+        } else if index == 0 {
+            finished_blocks - 1
+        } else {
+            finished_blocks
+        } as u64;
+
+        // a non-uniform distribution that favours recent blocks
+        let x = (j1 as u64 * j1 as u64) >> 32;
+        let y = (reference_area_size * x) >> 32;
+        let relative_position = reference_area_size - 1 - y;
+
+        let start_position = if pass == 0 || slice == SYNC_POINTS - 1 {
+            0
+        } else {
+            (slice + 1) * self.segment_length
+        } as u64;
+
+        ((start_position + relative_position) % self.lane_length as u64) as u32
+    }
+
+    // The tag is H' of the XOR of the last block in every lane.
+    fn finalize(mut self, output_length: u32) -> Vec<u8> {
+        let mut final_block = self.blocks[self.index(0, self.lane_length - 1)];
+        for lane in 1..self.lanes {
+            let last = self.blocks[self.index(lane, self.lane_length - 1)];
+            for (word, last_word) in final_block.iter_mut().zip(last.iter()) {
+                *word ^= last_word;
+            }
+        }
+
+        let mut bytes = block_to_bytes(&final_block);
+        let tag = variable_length_hash(&bytes, output_length as usize);
+
+        zeroize(&mut bytes);
+        zeroize_words(&mut final_block);
+        for block in self.blocks.iter_mut() {
+            zeroize_words(block);
+        }
+
+        tag
+    }
+}
+
+fn bytes_to_block(bytes: &[u8]) -> Block {
+    let mut block = [0u64; BLOCK_WORDS];
+    for (word, chunk) in block.iter_mut().zip(bytes.chunks_exact(8)) {
+        *word = u64::from_le_bytes(chunk.try_into().unwrap());
+    }
+    block
+}
+
+fn block_to_bytes(block: &Block) -> Vec<u8> {
+    block.iter().flat_map(|word| word.to_le_bytes()).collect()
+}
+
+// H'^T (RFC 9106 section 3.3): BLAKE2b extended to any output length by chaining 64 byte
+// digests and keeping the first half of each one.
+fn variable_length_hash(input: &[u8], length: usize) -> Vec<u8> {
+    let mut hasher = Blake2b::new(length.min(64));
+    hasher.update(&(length as u32).to_le_bytes());
+    hasher.update(input);
+    let mut digest = hasher.finalize();
+
+    if length <= 64 {
+        return digest;
+    }
+
+    let mut output = Vec::with_capacity(length);
+    while length - output.len() > 64 {
+        output.extend_from_slice(&digest[..32]);
+        let remaining = length - output.len();
+        digest = blake2b::hash(&digest, remaining.min(64));
+    }
+    output.extend_from_slice(&digest);
+    output
+}
+
+// The compression function G(X, Y): the BLAKE2b round permutation applied to the rows and then
+// the columns of X ^ Y, viewed as an 8x8 matrix of 16 byte registers, XORed with X ^ Y.
+fn compress(x: &Block, y: &Block) -> Block {
+    let mut r = [0u64; BLOCK_WORDS];
+    for i in 0..BLOCK_WORDS {
+        r[i] = x[i] ^ y[i];
+    }
+
+    let mut z = r;
+    for row in 0..8 {
+        let mut indices = [0usize; 16];
+        for (i, index) in indices.iter_mut().enumerate() {
+            *index = row * 16 + i;
+        }
+        permute(&mut z, &indices);
+    }
+    for column in 0..8 {
+        let mut indices = [0usize; 16];
+        for (i, index) in indices.iter_mut().enumerate() {
+            *index = (i / 2) * 16 + column * 2 + i % 2;
+        }
+        permute(&mut z, &indices);
+    }
+
+    for i in 0..BLOCK_WORDS {
+        z[i] ^= r[i];
+    }
+    z
+}
+
+// The permutation P on 16 words of the block
+fn permute(block: &mut Block, indices: &[usize; 16]) {
+    let mut v = [0u64; 16];
+    for (value, &index) in v.iter_mut().zip(indices.iter()) {
+        *value = block[index];
+    }
+
+    mix(&mut v, 0, 4, 8, 12);
+    mix(&mut v, 1, 5, 9, 13);
+    mix(&mut v, 2, 6, 10, 14);
+    mix(&mut v, 3, 7, 11, 15);
+    mix(&mut v, 0, 5, 10, 15);
+    mix(&mut v, 1, 6, 11, 12);
+    mix(&mut v, 2, 7, 8, 13);
+    mix(&mut v, 3, 4, 9, 14);
+
+    for (value, &index) in v.iter().zip(indices.iter()) {
+        block[index] = *value;
+    }
+}
+
+// GB, the BLAKE2b mixing function with the additions replaced by a + b + 2 * lo(a) * lo(b)
+fn mix(v: &mut [u64; 16], a: usize, b: usize, c: usize, d: usize) {
+    fn multiply_add(x: u64, y: u64) -> u64 {
+        let low_product = (x & 0xffffffff).wrapping_mul(y & 0xffffffff);
+        x.wrapping_add(y).wrapping_add(low_product.wrapping_mul(2))
+    }
+
+    v[a] = multiply_add(v[a], v[b]);
+    v[d] = (v[d] ^ v[a]).rotate_right(32);
+    v[c] = multiply_add(v[c], v[d]);
+    v[b] = (v[b] ^ v[c]).rotate_right(24);
+    v[a] = multiply_add(v[a], v[b]);
+    v[d] = (v[d] ^ v[a]).rotate_right(16);
+    v[c] = multiply_add(v[c], v[d]);
+    v[b] = (v[b] ^ v[c]).rotate_right(63);
+}
+
+#[cfg(test)]
+use crate::utils::formatting::hex_to_bytes;
+
+#[test]
+fn rfc9106_test() {
+    // RFC 9106 sections 5.1 to 5.3
+    let params = Params {
+        memory_cost: 32,
+        iterations: 3,
+        parallelism: 4,
+        output_length: 32
+    };
+    let test_cases = [
+        (Variant::Argon2d, "512b391b6f1162975371d30919734294f868e3be3984f3c1a13a4db9fabe4acb"),
+        (Variant::Argon2i, "c814d9d1dc7f37aa13f0d77f2494bda1c8de6b016dd388d29952a4c4672b6ce8"),
+        (Variant::Argon2id, "0d640df58d78766c08c037a34a8b53c9d01ef0452d75b65eb52520e96b01e659"),
+    ];
+
+    for (variant, expected_tag) in test_cases {
+        let tag = hash(variant, &[0x01; 32], &[0x02; 16], &[0x03; 8], &[0x04; 12], &params).unwrap();
+        assert_eq!(tag.expose(), &hex_to_bytes(expected_tag).unwrap());
+    }
+}
+
+#[test]
+fn argon2id_test() {
+    // a single lane over more than one address block, and a tag longer than one BLAKE2b digest,
+    // checked against the Python cryptography package
+    let params = Params {
+        memory_cost: 1024,
+        iterations: 2,
+        parallelism: 1,
+        output_length: 100
+    };
+    assert_eq!(argon2id(b"password", b"somesalt", &params).unwrap().expose(), &hex_to_bytes("\
+        cb5313b46ce4f829fdad34c5306caa14db87af19415fad09e22beccdc75b430d4bfb67e12e551f77fa2cca333e79ca4f\
+        4317e804e44c2356729191258e8d4beba96b23743784089fb777f415c18abd2557d9b90dbe22e5591dcc959032ca93b7\
+        902e46dd").unwrap());
+}
+
+#[test]
+fn invalid_params_test() {
+    let params = |memory_cost, iterations, parallelism, output_length| Params {
+        memory_cost, iterations, parallelism, output_length
+    };
+
+    assert_eq!(argon2id(b"password", b"somesalt", &params(32, 1, 0, 32)).err(), Some(Argon2Error::InvalidParallelism(0)));
+    assert_eq!(argon2id(b"password", b"somesalt", &params(32, 0, 1, 32)).err(), Some(Argon2Error::InvalidIterations(0)));
+    assert_eq!(argon2id(b"password", b"somesalt", &params(31, 1, 4, 32)).err(), Some(Argon2Error::MemoryTooSmall(31)));
+    assert_eq!(argon2id(b"password", b"somesalt", &params(32, 1, 1, 3)).err(), Some(Argon2Error::OutputTooShort(3)));
+    assert_eq!(argon2id(b"password", b"short", &params(32, 1, 1, 32)).err(), Some(Argon2Error::SaltTooShort(5)));
+}
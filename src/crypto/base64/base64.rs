@@ -1,3 +1,5 @@
+use std::fmt::{Display, Formatter};
+
 #[test]
 fn b64_encode_test() {
     let input_text = "This is a testing string.";
@@ -18,21 +20,190 @@ fn b64url_encode_test() {
     assert_eq!(encoded_value, expected_result)
 }
 
-// TODO: implement decoding
+#[test]
+fn b64_decode_test() {
+    // RFC 4648 section 10
+    let test_cases = [
+        ("", ""),
+        ("f", "Zg=="),
+        ("fo", "Zm8="),
+        ("foo", "Zm9v"),
+        ("foob", "Zm9vYg=="),
+        ("fooba", "Zm9vYmE="),
+        ("foobar", "Zm9vYmFy"),
+    ];
+
+    for (expected_result, input) in test_cases {
+        assert_eq!(decode_b64(input.as_bytes()).unwrap(), expected_result.as_bytes());
+    }
+
+    let binary: Vec<u8> = (0..=255).collect();
+    assert_eq!(decode_b64(encode_b64_bytes(&binary).as_bytes()).unwrap(), binary);
+}
+
+#[test]
+fn b64url_decode_test() {
+    let binary: Vec<u8> = (0..=255).rev().collect();
+    let encoded = encode_b64url_bytes(&binary);
+    assert_eq!(decode_b64url(encoded.as_bytes()).unwrap(), binary);
+
+    // unpadded input, as used by JWTs
+    assert_eq!(decode_b64url(b"eyJhbGciOiJIUzI1NiJ9").unwrap(), br#"{"alg":"HS256"}"#);
+    assert_eq!(decode_b64url(b"_-8").unwrap(), [0xff, 0xef]);
+    assert_eq!(decode_b64url(b"_-8=").unwrap(), [0xff, 0xef]);
+}
+
+#[test]
+fn b64_decode_invalid_test() {
+    // characters from the wrong alphabet, whitespace and misplaced padding
+    assert_eq!(decode_b64(b"Zm9v_-8="), Err(Base64Error::InvalidCharacter(4)));
+    assert_eq!(decode_b64url(b"Zm9v+/8"), Err(Base64Error::InvalidCharacter(4)));
+    assert_eq!(decode_b64(b"Zm9v\nYmE"), Err(Base64Error::InvalidCharacter(4)));
+    assert_eq!(decode_b64(b"Zg=a"), Err(Base64Error::InvalidCharacter(2)));
+    assert_eq!(decode_b64(b"Z==="), Err(Base64Error::InvalidPadding));
+    assert_eq!(decode_b64(b"Zg==Zg=="), Err(Base64Error::InvalidCharacter(2)));
+
+    // padding is required for standard base64
+    assert_eq!(decode_b64(b"Zg"), Err(Base64Error::InvalidLength(2)));
+    assert_eq!(decode_b64(b"Zm9vY"), Err(Base64Error::InvalidLength(5)));
+    assert_eq!(decode_b64url(b"Zm9vY"), Err(Base64Error::InvalidLength(5)));
+    assert_eq!(decode_b64url(b"Zg="), Err(Base64Error::InvalidPadding));
+
+    // the unused low bits of the last character must be zero
+    assert_eq!(decode_b64(b"Zh=="), Err(Base64Error::NonCanonicalEncoding));
+    assert_eq!(decode_b64(b"Zm9="), Err(Base64Error::NonCanonicalEncoding));
+    assert_eq!(decode_b64url(b"Zm9"), Err(Base64Error::NonCanonicalEncoding));
+}
+
+#[derive(Debug, PartialEq)]
+pub enum Base64Error {
+    // A character outside the alphabet, or padding somewhere other than the end.
+    // Holds the position of the character in the input.
+    InvalidCharacter(usize),
+    // The input can't be a complete encoding, e.g. a missing padding or a single leftover character.
+    InvalidLength(usize),
+    // The wrong number of padding characters.
+    InvalidPadding,
+    // The unused bits of the final character are not zero, so the input is not the canonical
+    // encoding of the decoded bytes.
+    NonCanonicalEncoding,
+}
+
+impl Display for Base64Error {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Base64Error::InvalidCharacter(position) => write!(f, "invalid base64 character at position {}", position),
+            Base64Error::InvalidLength(length) => write!(f, "invalid base64 length: {}", length),
+            Base64Error::InvalidPadding => write!(f, "invalid base64 padding"),
+            Base64Error::NonCanonicalEncoding => write!(f, "non-canonical base64 encoding"),
+        }
+    }
+}
 
 pub fn encode_b64url(input: &str) -> String {
-    convert(input, &B64_URL_INDEX_TO_CHAR)
+    convert(input.as_bytes(), &B64_URL_INDEX_TO_CHAR)
 }
 
 pub fn encode_b64(input: &str) -> String {
+    convert(input.as_bytes(), &B64_INDEX_TO_CHAR)
+}
+
+pub fn encode_b64url_bytes(input: &[u8]) -> String {
+    convert(input, &B64_URL_INDEX_TO_CHAR)
+}
+
+pub fn encode_b64_bytes(input: &[u8]) -> String {
     convert(input, &B64_INDEX_TO_CHAR)
 }
 
-fn convert(input: &str, lookup_table: &[char; 64]) -> String {
-    // first we split the input into an array of bytes
-    let input_bytes = input.as_bytes();
+// Decodes standard base64. The input must be padded to a multiple of 4 characters.
+pub fn decode_b64(input: &[u8]) -> Result<Vec<u8>, Base64Error> {
+    if !input.len().is_multiple_of(4) {
+        return Err(Base64Error::InvalidLength(input.len()));
+    }
+
+    decode(input, &B64_INDEX_TO_CHAR)
+}
+
+// Decodes base64url. Padding is optional, since it is usually left out (e.g. in JWTs), but
+// if present it must be correct.
+pub fn decode_b64url(input: &[u8]) -> Result<Vec<u8>, Base64Error> {
+    if input.contains(&b'=') && !input.len().is_multiple_of(4) {
+        return Err(Base64Error::InvalidPadding);
+    }
+
+    decode(input, &B64_URL_INDEX_TO_CHAR)
+}
+
+fn decode(input: &[u8], lookup_table: &[char; 64]) -> Result<Vec<u8>, Base64Error> {
+    // padding is only allowed as the last one or two characters
+    let padding_length = input.iter().rev().take_while(|&&c| c == b'=').count();
+    if padding_length > 2 {
+        return Err(Base64Error::InvalidPadding);
+    }
+
+    let data = &input[..input.len() - padding_length];
+
+    // a single character only holds 6 bits, which isn't enough for a byte
+    if data.len() % 4 == 1 {
+        return Err(Base64Error::InvalidLength(input.len()));
+    }
+
+    // when padded, the padding must complete the last group of 4
+    if padding_length > 0 && !(data.len() + padding_length).is_multiple_of(4) {
+        return Err(Base64Error::InvalidPadding);
+    }
+
+    let mut result = Vec::with_capacity(data.len() * 3 / 4);
+
+    for (chunk_index, chunk) in data.chunks(4).enumerate() {
+        let mut values = [0u8; 4];
+        for (i, &c) in chunk.iter().enumerate() {
+            values[i] = char_to_index(c, lookup_table)
+                .ok_or(Base64Error::InvalidCharacter(chunk_index * 4 + i))?;
+        }
+
+        // reverse of the bit shuffling in `convert`
+        result.push((values[0] << 2) | (values[1] >> 4));
+
+        match chunk.len() {
+            4 => {
+                result.push((values[1] << 4) | (values[2] >> 2));
+                result.push((values[2] << 6) | values[3]);
+            },
+            3 => {
+                if values[2] & 0b00000011 != 0 {
+                    return Err(Base64Error::NonCanonicalEncoding);
+                }
+                result.push((values[1] << 4) | (values[2] >> 2));
+            },
+            2 => {
+                if values[1] & 0b00001111 != 0 {
+                    return Err(Base64Error::NonCanonicalEncoding);
+                }
+            },
+            _ => unreachable!()
+        }
+    }
+
+    Ok(result)
+}
+
+// Finds the 6 bit value of a character. Symbols 62 and 63 are the only difference between the
+// two alphabets, so they are taken from the lookup table.
+fn char_to_index(c: u8, lookup_table: &[char; 64]) -> Option<u8> {
+    match c {
+        b'A'..=b'Z' => Some(c - b'A'),
+        b'a'..=b'z' => Some(c - b'a' + 26),
+        b'0'..=b'9' => Some(c - b'0' + 52),
+        _ if c as char == lookup_table[62] => Some(62),
+        _ if c as char == lookup_table[63] => Some(63),
+        _ => None
+    }
+}
 
-    let mut result = String::with_capacity(4 * ((input.len() + 2) / 3));
+fn convert(input_bytes: &[u8], lookup_table: &[char; 64]) -> String {
+    let mut result = String::with_capacity(4 * input_bytes.len().div_ceil(3));
 
     // we then need to process 3 bytes at a time, using the builtin chunk method.
     // this gives us 24 bits to work with (8 * 3)
@@ -0,0 +1,185 @@
+// BLAKE2b, as specified in RFC 7693, with output lengths of 1 to 64 bytes and an optional key.
+
+use crate::utils::secret::Secret;
+
+pub const MAX_OUTPUT_LENGTH: usize = 64;
+pub const MAX_KEY_LENGTH: usize = 64;
+const BLOCK_SIZE: usize = 128;
+
+// the same initialization vector as SHA-512
+const IV: [u64; 8] = [
+    0x6a09e667f3bcc908, 0xbb67ae8584caa73b, 0x3c6ef372fe94f82b, 0xa54ff53a5f1d36f1,
+    0x510e527fade682d1, 0x9b05688c2b3e6c1f, 0x1f83d9abfb41bd6b, 0x5be0cd19137e2179
+];
+
+// message word permutations for each round; rounds 10 and 11 reuse the first two
+const SIGMA: [[usize; 16]; 10] = [
+    [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15],
+    [14, 10, 4, 8, 9, 15, 13, 6, 1, 12, 0, 2, 11, 7, 5, 3],
+    [11, 8, 12, 0, 5, 2, 15, 13, 10, 14, 3, 6, 7, 1, 9, 4],
+    [7, 9, 3, 1, 13, 12, 11, 14, 2, 6, 5, 10, 4, 0, 15, 8],
+    [9, 0, 5, 7, 2, 4, 10, 15, 14, 1, 11, 12, 6, 8, 3, 13],
+    [2, 12, 6, 10, 0, 11, 8, 3, 4, 13, 7, 5, 15, 14, 1, 9],
+    [12, 5, 1, 15, 14, 13, 4, 10, 0, 7, 6, 3, 9, 2, 8, 11],
+    [13, 11, 7, 14, 12, 1, 3, 9, 5, 0, 15, 4, 8, 6, 2, 10],
+    [6, 15, 14, 9, 11, 3, 0, 8, 12, 2, 13, 7, 1, 4, 10, 5],
+    [10, 2, 8, 4, 7, 6, 1, 5, 15, 11, 9, 14, 3, 12, 13, 0]
+];
+
+// Hashes the input in one go, with an output of the given length and no key.
+pub fn hash(input: &[u8], output_length: usize) -> Vec<u8> {
+    let mut hasher = Blake2b::new(output_length);
+    hasher.update(input);
+    hasher.finalize()
+}
+
+#[derive(Clone)]
+pub struct Blake2b {
+    hash_values: [u64; 8],
+    // number of bytes compressed so far, a 128 bit counter in the specification
+    byte_count: u128,
+    // can hold the key, or the password when used by Argon2
+    buffer: Secret<[u8; BLOCK_SIZE]>,
+    buffer_length: usize,
+    output_length: usize
+}
+
+impl Blake2b {
+    pub fn new(output_length: usize) -> Self {
+        Self::new_keyed(&[], output_length)
+    }
+
+    // Panics unless 1 <= output_length <= 64 and the key is at most 64 bytes, as these are
+    // fixed by the caller's protocol rather than by input data.
+    pub fn new_keyed(key: &[u8], output_length: usize) -> Self {
+        assert!((1..=MAX_OUTPUT_LENGTH).contains(&output_length), "BLAKE2b output length must be 1 to 64 bytes");
+        assert!(key.len() <= MAX_KEY_LENGTH, "BLAKE2b key must be at most 64 bytes");
+
+        let mut hash_values = IV;
+        // parameter block: digest length, key length, fanout = 1, depth = 1
+        hash_values[0] ^= 0x01010000 ^ ((key.len() as u64) << 8) ^ output_length as u64;
+
+        let mut hasher = Self {
+            hash_values,
+            byte_count: 0,
+            buffer: Secret::new([0u8; BLOCK_SIZE]),
+            buffer_length: 0,
+            output_length
+        };
+
+        // a key is processed as a full block of its own, padded with zeros
+        if !key.is_empty() {
+            hasher.buffer.expose_mut()[..key.len()].copy_from_slice(key);
+            hasher.buffer_length = BLOCK_SIZE;
+        }
+
+        hasher
+    }
+
+    pub fn update(&mut self, mut data: &[u8]) {
+        while !data.is_empty() {
+            // the last block has to go through the final compression, so a full buffer is only
+            // compressed once we know more data follows
+            if self.buffer_length == BLOCK_SIZE {
+                self.byte_count += BLOCK_SIZE as u128;
+                let block = self.buffer.clone();
+                self.compress(block.expose(), false);
+                self.buffer_length = 0;
+            }
+
+            let take = (BLOCK_SIZE - self.buffer_length).min(data.len());
+            self.buffer.expose_mut()[self.buffer_length..self.buffer_length + take].copy_from_slice(&data[..take]);
+            self.buffer_length += take;
+            data = &data[take..];
+        }
+    }
+
+    pub fn finalize(mut self) -> Vec<u8> {
+        self.byte_count += self.buffer_length as u128;
+        self.buffer.expose_mut()[self.buffer_length..].fill(0);
+        let block = self.buffer.clone();
+        self.compress(block.expose(), true);
+
+        self.hash_values.iter()
+            .flat_map(|value| value.to_le_bytes())
+            .take(self.output_length)
+            .collect()
+    }
+
+    fn compress(&mut self, block: &[u8; BLOCK_SIZE], is_last_block: bool) {
+        let mut message = [0u64; 16];
+        for (word, chunk) in message.iter_mut().zip(block.chunks_exact(8)) {
+            *word = u64::from_le_bytes(chunk.try_into().unwrap());
+        }
+
+        let mut v = [0u64; 16];
+        v[..8].copy_from_slice(&self.hash_values);
+        v[8..].copy_from_slice(&IV);
+        v[12] ^= self.byte_count as u64;
+        v[13] ^= (self.byte_count >> 64) as u64;
+        if is_last_block {
+            v[14] = !v[14];
+        }
+
+        for round in 0..12 {
+            let s = &SIGMA[round % 10];
+            mix(&mut v, 0, 4, 8, 12, message[s[0]], message[s[1]]);
+            mix(&mut v, 1, 5, 9, 13, message[s[2]], message[s[3]]);
+            mix(&mut v, 2, 6, 10, 14, message[s[4]], message[s[5]]);
+            mix(&mut v, 3, 7, 11, 15, message[s[6]], message[s[7]]);
+            mix(&mut v, 0, 5, 10, 15, message[s[8]], message[s[9]]);
+            mix(&mut v, 1, 6, 11, 12, message[s[10]], message[s[11]]);
+            mix(&mut v, 2, 7, 8, 13, message[s[12]], message[s[13]]);
+            mix(&mut v, 3, 4, 9, 14, message[s[14]], message[s[15]]);
+        }
+
+        for i in 0..8 {
+            self.hash_values[i] ^= v[i] ^ v[i + 8];
+        }
+    }
+}
+
+// The mixing function G
+fn mix(v: &mut [u64; 16], a: usize, b: usize, c: usize, d: usize, x: u64, y: u64) {
+    v[a] = v[a].wrapping_add(v[b]).wrapping_add(x);
+    v[d] = (v[d] ^ v[a]).rotate_right(32);
+    v[c] = v[c].wrapping_add(v[d]);
+    v[b] = (v[b] ^ v[c]).rotate_right(24);
+    v[a] = v[a].wrapping_add(v[b]).wrapping_add(y);
+    v[d] = (v[d] ^ v[a]).rotate_right(16);
+    v[c] = v[c].wrapping_add(v[d]);
+    v[b] = (v[b] ^ v[c]).rotate_right(63);
+}
+
+#[cfg(test)]
+use crate::utils::formatting::hex_to_bytes;
+
+#[test]
+fn hash_test() {
+    // RFC 7693 appendix A
+    assert_eq!(hash(b"abc", 64), hex_to_bytes("\
+        ba80a53f981c4d0d6a2797b69f12f6e94c212f14685ac4b74b12bb6fdbffa2d1\
+        7d87c5392aab792dc252d5de4533cc9518d38aa8dbf1925ab92386edd4009923").unwrap());
+
+    // keyed and truncated outputs, checked against Python's hashlib
+    let mut keyed = Blake2b::new_keyed(b"key", 32);
+    keyed.update(b"abc");
+    assert_eq!(keyed.finalize(), hex_to_bytes("0330531d097355a3f72e80d55c1245ccf79f1704431c6e3887938320442c23c0").unwrap());
+
+    let input: Vec<u8> = (0..=255u8).cycle().take(512).collect();
+    assert_eq!(hash(&input, 20), hex_to_bytes("e1695d971d2357c55fe6824cf175e915700ea2f2").unwrap());
+}
+
+#[test]
+fn incremental_hash_test() {
+    let input: Vec<u8> = (0..=255u8).cycle().take(1000).collect();
+    let expected = hash(&input, 64);
+
+    // split points around the block boundary, including an exact multiple of the block size
+    for split in [0, 1, 127, 128, 129, 256, 999, 1000] {
+        let mut hasher = Blake2b::new(64);
+        hasher.update(&input[..split]);
+        hasher.update(&input[split..]);
+        assert_eq!(hasher.finalize(), expected);
+    }
+}
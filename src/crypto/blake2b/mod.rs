@@ -0,0 +1 @@
+pub mod blake2b;
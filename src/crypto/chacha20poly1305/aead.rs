@@ -0,0 +1,178 @@
+// The ChaCha20-Poly1305 AEAD construction, as specified in RFC 8439 section 2.8.
+//
+// The first ChaCha20 block (counter 0) is used to generate a one-time Poly1305 key, and the
+// plaintext is encrypted starting at counter 1. The tag covers the associated data and the
+// ciphertext, each padded to 16 bytes, followed by both of their lengths.
+
+use super::chacha20;
+use super::chacha20::{BLOCK_LENGTH, KEY_LENGTH, NONCE_LENGTH};
+use super::error::ChaCha20Poly1305Error;
+use crate::crypto::poly1305::{Poly1305, TAG_LENGTH};
+use crate::utils::constant_time;
+use crate::utils::secret::Secret;
+
+// The plaintext is encrypted with counters 1 to 2^32 - 1, so it can be at most that many
+// blocks long before the counter wraps around to the Poly1305 key block.
+pub const MAX_PLAINTEXT_LENGTH: u64 = u32::MAX as u64 * BLOCK_LENGTH as u64;
+
+pub struct ChaCha20Poly1305 {
+    key: Secret<[u8; KEY_LENGTH]>
+}
+
+impl ChaCha20Poly1305 {
+    pub fn new(key: &[u8; KEY_LENGTH]) -> Self {
+        Self {
+            key: Secret::new(*key)
+        }
+    }
+
+    // Encrypts and authenticates the plaintext, and authenticates the associated data.
+    // Returns the ciphertext with the 16 byte tag appended.
+    //
+    // A nonce must never be reused with the same key.
+    pub fn seal(&self, nonce: &[u8; NONCE_LENGTH], associated_data: &[u8], plaintext: &[u8]) -> Result<Vec<u8>, ChaCha20Poly1305Error> {
+        if plaintext.len() as u64 > MAX_PLAINTEXT_LENGTH {
+            return Err(ChaCha20Poly1305Error::InvalidPlaintextLength(plaintext.len()));
+        }
+
+        let mut output = plaintext.to_vec();
+        chacha20::apply_keystream(self.key.expose(), 1, nonce, &mut output);
+
+        let tag = self.compute_tag(nonce, associated_data, &output);
+        output.extend_from_slice(&tag);
+
+        Ok(output)
+    }
+
+    // Checks the tag and decrypts data produced by `seal`. Nothing is returned unless the
+    // ciphertext and associated data are authentic.
+    pub fn open(&self, nonce: &[u8; NONCE_LENGTH], associated_data: &[u8], ciphertext_with_tag: &[u8]) -> Result<Vec<u8>, ChaCha20Poly1305Error> {
+        if ciphertext_with_tag.len() < TAG_LENGTH || (ciphertext_with_tag.len() - TAG_LENGTH) as u64 > MAX_PLAINTEXT_LENGTH {
+            return Err(ChaCha20Poly1305Error::InvalidCiphertextLength(ciphertext_with_tag.len()));
+        }
+
+        let (ciphertext, tag) = ciphertext_with_tag.split_at(ciphertext_with_tag.len() - TAG_LENGTH);
+
+        let expected_tag = self.compute_tag(nonce, associated_data, ciphertext);
+        if !constant_time::compare(&expected_tag, tag) {
+            return Err(ChaCha20Poly1305Error::AuthenticationFailed);
+        }
+
+        let mut plaintext = ciphertext.to_vec();
+        chacha20::apply_keystream(self.key.expose(), 1, nonce, &mut plaintext);
+
+        Ok(plaintext)
+    }
+
+    fn compute_tag(&self, nonce: &[u8; NONCE_LENGTH], associated_data: &[u8], ciphertext: &[u8]) -> [u8; TAG_LENGTH] {
+        let poly1305_key = generate_poly1305_key(self.key.expose(), nonce);
+        let mut poly1305 = Poly1305::new(poly1305_key.expose());
+
+        poly1305.update(associated_data);
+        poly1305.update(&padding(associated_data.len()));
+        poly1305.update(ciphertext);
+        poly1305.update(&padding(ciphertext.len()));
+        poly1305.update(&(associated_data.len() as u64).to_le_bytes());
+        poly1305.update(&(ciphertext.len() as u64).to_le_bytes());
+
+        poly1305.finalize()
+    }
+}
+
+// The one-time key is the first 32 bytes of the keystream block with counter 0.
+fn generate_poly1305_key(key: &[u8; KEY_LENGTH], nonce: &[u8; NONCE_LENGTH]) -> Secret<[u8; 32]> {
+    let block = Secret::new(chacha20::block(key, 0, nonce));
+    let mut poly1305_key = Secret::new([0u8; 32]);
+    poly1305_key.expose_mut().copy_from_slice(&block.expose()[..32]);
+    poly1305_key
+}
+
+// The zero bytes needed to pad data of the given length to a multiple of 16.
+fn padding(length: usize) -> Vec<u8> {
+    vec![0u8; (16 - length % 16) % 16]
+}
+
+#[cfg(test)]
+use crate::utils::formatting::hex_to_bytes;
+
+#[test]
+fn poly1305_key_generation_test() {
+    // RFC 8439 section 2.6.2
+    let key: [u8; KEY_LENGTH] = core::array::from_fn(|i| 0x80 + i as u8);
+    let nonce = [0x00, 0x00, 0x00, 0x00, 0x00, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07];
+    let expected = hex_to_bytes("8ad5a08b905f81cc815040274ab29471a833b637e3fd0da508dbb8e2fdd1a646").unwrap();
+
+    assert_eq!(generate_poly1305_key(&key, &nonce).expose().to_vec(), expected);
+}
+
+#[test]
+fn seal_test() {
+    // RFC 8439 section 2.8.2
+    let key: [u8; KEY_LENGTH] = core::array::from_fn(|i| 0x80 + i as u8);
+    let nonce = [0x07, 0x00, 0x00, 0x00, 0x40, 0x41, 0x42, 0x43, 0x44, 0x45, 0x46, 0x47];
+    let associated_data = hex_to_bytes("50515253c0c1c2c3c4c5c6c7").unwrap();
+    let plaintext = b"Ladies and Gentlemen of the class of '99: If I could offer you only one tip for the future, sunscreen would be it.";
+    let expected = hex_to_bytes(
+        "d31a8d34648e60db7b86afbc53ef7ec2a4aded51296e08fea9e2b5a736ee62d6\
+         3dbea45e8ca9671282fafb69da92728b1a71de0a9e060b2905d6a5b67ecd3b36\
+         92ddbd7f2d778b8c9803aee328091b58fab324e4fad675945585808b4831d7bc\
+         3ff4def08e4b7a9de576d26586cec64b6116\
+         1ae10b594f09e26a7e902ecbd0600691"
+    ).unwrap();
+
+    let aead = ChaCha20Poly1305::new(&key);
+    let sealed = aead.seal(&nonce, &associated_data, plaintext).unwrap();
+    assert_eq!(sealed, expected);
+    assert_eq!(aead.open(&nonce, &associated_data, &sealed).unwrap(), plaintext);
+}
+
+#[test]
+fn open_tampered_test() {
+    let aead = ChaCha20Poly1305::new(&[0x42; KEY_LENGTH]);
+    let nonce = [0x24; NONCE_LENGTH];
+    let sealed = aead.seal(&nonce, b"header", b"attack at dawn").unwrap();
+
+    // flipping any bit of the ciphertext or tag must be detected
+    for i in 0..sealed.len() {
+        let mut tampered = sealed.clone();
+        tampered[i] ^= 0x80;
+        assert_eq!(aead.open(&nonce, b"header", &tampered), Err(ChaCha20Poly1305Error::AuthenticationFailed));
+    }
+
+    assert_eq!(aead.open(&nonce, b"footer", &sealed), Err(ChaCha20Poly1305Error::AuthenticationFailed));
+    assert_eq!(aead.open(&nonce, b"header", &sealed[..15]), Err(ChaCha20Poly1305Error::InvalidCiphertextLength(15)));
+}
+
+#[test]
+fn plaintext_length_limit_test() {
+    // The last block of the longest plaintext uses the last counter value, and the block
+    // after it would wrap around to counter 0, the Poly1305 key. A plaintext that long can't
+    // be allocated here, so only the limit itself is checked.
+    let last_counter = 1 + (MAX_PLAINTEXT_LENGTH / BLOCK_LENGTH as u64 - 1);
+    assert_eq!(last_counter, u32::MAX as u64);
+    assert_eq!(MAX_PLAINTEXT_LENGTH % BLOCK_LENGTH as u64, 0);
+}
+
+#[cfg(test)]
+use crate::utils::test_vectors::wycheproof;
+
+#[test]
+fn wycheproof_test() {
+    for group in &wycheproof::load("chacha20_poly1305_test.json") {
+        for test in &group.tests {
+            // keys and nonces of other sizes can't be passed in at all
+            let (Ok(key), Ok(nonce)) = (test.hex("key").try_into(), test.hex("iv").try_into()) else {
+                test.check(false);
+                continue;
+            };
+            let aead = ChaCha20Poly1305::new(&key);
+            let (associated_data, message) = (test.hex("aad"), test.hex("msg"));
+            let ciphertext_with_tag = [test.hex("ct"), test.hex("tag")].concat();
+
+            if test.result == wycheproof::Expectation::Valid {
+                assert_eq!(aead.seal(&nonce, &associated_data, &message).unwrap(), ciphertext_with_tag, "{test}");
+            }
+            test.check(aead.open(&nonce, &associated_data, &ciphertext_with_tag) == Ok(message));
+        }
+    }
+}
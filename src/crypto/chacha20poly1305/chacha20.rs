@@ -0,0 +1,133 @@
+// The ChaCha20 stream cipher, as specified in RFC 8439 section 2.
+//
+// The state is a 4x4 matrix of 32 bit words made from constants, the 256 bit key, a 32 bit
+// block counter and a 96 bit nonce. Each 64 byte block of keystream comes from running 20
+// rounds of quarter rounds over the state, and adding the original state back in.
+// Only additions, rotations and XORs are used, so the cipher runs in constant time.
+
+pub const KEY_LENGTH: usize = 32;
+pub const NONCE_LENGTH: usize = 12;
+pub const BLOCK_LENGTH: usize = 64;
+
+// "expand 32-byte k" as four little endian words
+const CONSTANTS: [u32; 4] = [0x61707865, 0x3320646e, 0x79622d32, 0x6b206574];
+
+// Generates one 64 byte block of keystream.
+pub fn block(key: &[u8; KEY_LENGTH], counter: u32, nonce: &[u8; NONCE_LENGTH]) -> [u8; BLOCK_LENGTH] {
+    let mut initial_state = [0u32; 16];
+    initial_state[..4].copy_from_slice(&CONSTANTS);
+    for i in 0..8 {
+        initial_state[4 + i] = read_u32_le(&key[i * 4..]);
+    }
+    initial_state[12] = counter;
+    for i in 0..3 {
+        initial_state[13 + i] = read_u32_le(&nonce[i * 4..]);
+    }
+
+    let mut state = initial_state;
+
+    // 20 rounds, as 10 iterations of a column round followed by a diagonal round
+    for _ in 0..10 {
+        quarter_round(&mut state, 0, 4, 8, 12);
+        quarter_round(&mut state, 1, 5, 9, 13);
+        quarter_round(&mut state, 2, 6, 10, 14);
+        quarter_round(&mut state, 3, 7, 11, 15);
+
+        quarter_round(&mut state, 0, 5, 10, 15);
+        quarter_round(&mut state, 1, 6, 11, 12);
+        quarter_round(&mut state, 2, 7, 8, 13);
+        quarter_round(&mut state, 3, 4, 9, 14);
+    }
+
+    let mut output = [0u8; BLOCK_LENGTH];
+    for (i, word) in state.iter().enumerate() {
+        let value = word.wrapping_add(initial_state[i]);
+        output[i * 4..(i + 1) * 4].copy_from_slice(&value.to_le_bytes());
+    }
+
+    output
+}
+
+// Encrypts or decrypts the data in place, starting the block counter at initial_counter.
+pub fn apply_keystream(key: &[u8; KEY_LENGTH], initial_counter: u32, nonce: &[u8; NONCE_LENGTH], data: &mut [u8]) {
+    let mut counter = initial_counter;
+
+    for chunk in data.chunks_mut(BLOCK_LENGTH) {
+        let keystream = block(key, counter, nonce);
+
+        for (byte, key_byte) in chunk.iter_mut().zip(keystream.iter()) {
+            *byte ^= key_byte;
+        }
+
+        counter = counter.wrapping_add(1);
+    }
+}
+
+fn quarter_round(state: &mut [u32; 16], a: usize, b: usize, c: usize, d: usize) {
+    state[a] = state[a].wrapping_add(state[b]);
+    state[d] = (state[d] ^ state[a]).rotate_left(16);
+
+    state[c] = state[c].wrapping_add(state[d]);
+    state[b] = (state[b] ^ state[c]).rotate_left(12);
+
+    state[a] = state[a].wrapping_add(state[b]);
+    state[d] = (state[d] ^ state[a]).rotate_left(8);
+
+    state[c] = state[c].wrapping_add(state[d]);
+    state[b] = (state[b] ^ state[c]).rotate_left(7);
+}
+
+fn read_u32_le(bytes: &[u8]) -> u32 {
+    u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]])
+}
+
+#[cfg(test)]
+use crate::utils::formatting::hex_to_bytes;
+
+#[test]
+fn quarter_round_test() {
+    // RFC 8439 section 2.2.1
+    let mut state = [0u32; 16];
+    state[0] = 0x11111111;
+    state[1] = 0x01020304;
+    state[2] = 0x9b8d6f43;
+    state[3] = 0x01234567;
+
+    quarter_round(&mut state, 0, 1, 2, 3);
+
+    assert_eq!(state[..4], [0xea2a92f4, 0xcb1cf8ce, 0x4581472e, 0x5881c4bb]);
+}
+
+#[test]
+fn block_test() {
+    // RFC 8439 section 2.3.2
+    let key: [u8; KEY_LENGTH] = core::array::from_fn(|i| i as u8);
+    let nonce = [0x00, 0x00, 0x00, 0x09, 0x00, 0x00, 0x00, 0x4a, 0x00, 0x00, 0x00, 0x00];
+    let expected = hex_to_bytes(
+        "10f1e7e4d13b5915500fdd1fa32071c4c7d1f4c733c068030422aa9ac3d46c4e\
+         d2826446079faa0914c2d705d98b02a2b5129cd1de164eb9cbd083e8a2503c4e"
+    ).unwrap();
+
+    assert_eq!(block(&key, 1, &nonce).to_vec(), expected);
+}
+
+#[test]
+fn encryption_test() {
+    // RFC 8439 section 2.4.2
+    let key: [u8; KEY_LENGTH] = core::array::from_fn(|i| i as u8);
+    let nonce = [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x4a, 0x00, 0x00, 0x00, 0x00];
+    let plaintext = b"Ladies and Gentlemen of the class of '99: If I could offer you only one tip for the future, sunscreen would be it.";
+    let expected = hex_to_bytes(
+        "6e2e359a2568f98041ba0728dd0d6981e97e7aec1d4360c20a27afccfd9fae0b\
+         f91b65c5524733ab8f593dabcd62b3571639d624e65152ab8f530c359f0861d8\
+         07ca0dbf500d6a6156a38e088a22b65e52bc514d16ccf806818ce91ab7793736\
+         5af90bbf74a35be6b40b8eedf2785e42874d"
+    ).unwrap();
+
+    let mut data = plaintext.to_vec();
+    apply_keystream(&key, 1, &nonce, &mut data);
+    assert_eq!(data, expected);
+
+    apply_keystream(&key, 1, &nonce, &mut data);
+    assert_eq!(data, plaintext);
+}
@@ -0,0 +1,21 @@
+use std::fmt::{Display, Formatter};
+
+#[derive(Debug, PartialEq)]
+pub enum ChaCha20Poly1305Error {
+    // The ciphertext is too short to contain the authentication tag.
+    InvalidCiphertextLength(usize),
+    // The plaintext is longer than the 32 bit block counter can encrypt under one nonce.
+    InvalidPlaintextLength(usize),
+    // The authentication tag does not match the ciphertext and associated data.
+    AuthenticationFailed,
+}
+
+impl Display for ChaCha20Poly1305Error {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ChaCha20Poly1305Error::InvalidCiphertextLength(length) => write!(f, "invalid ciphertext length: {} bytes", length),
+            ChaCha20Poly1305Error::InvalidPlaintextLength(length) => write!(f, "invalid plaintext length: {} bytes", length),
+            ChaCha20Poly1305Error::AuthenticationFailed => write!(f, "authentication failed"),
+        }
+    }
+}
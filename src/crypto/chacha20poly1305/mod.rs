@@ -0,0 +1,3 @@
+pub mod chacha20;
+mod aead;
+mod error;
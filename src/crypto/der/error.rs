@@ -0,0 +1,40 @@
+use std::fmt::{Display, Formatter};
+
+#[derive(Debug, PartialEq)]
+pub enum DerError {
+    // The input ended in the middle of an element.
+    UnexpectedEnd,
+    // An element had a different tag than the structure requires.
+    UnexpectedTag(u8),
+    // Multi-byte tag numbers are never needed for the structures we read.
+    UnsupportedTag(u8),
+    // Indefinite lengths or lengths that don't fit the input.
+    InvalidLength,
+    // DER only allows one encoding of every value; this one is not the shortest.
+    NonMinimalEncoding,
+    // Bytes left over after the last expected element, with the number of bytes.
+    TrailingData(usize),
+    InvalidInteger,
+    InvalidBitString,
+    InvalidBoolean,
+    InvalidString,
+    InvalidTime,
+}
+
+impl Display for DerError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DerError::UnexpectedEnd => write!(f, "DER input ended unexpectedly"),
+            DerError::UnexpectedTag(tag) => write!(f, "unexpected DER tag 0x{:02x}", tag),
+            DerError::UnsupportedTag(tag) => write!(f, "unsupported DER tag 0x{:02x}", tag),
+            DerError::InvalidLength => write!(f, "invalid DER length"),
+            DerError::NonMinimalEncoding => write!(f, "DER value is not minimally encoded"),
+            DerError::TrailingData(length) => write!(f, "{} bytes of trailing DER data", length),
+            DerError::InvalidInteger => write!(f, "invalid DER integer"),
+            DerError::InvalidBitString => write!(f, "invalid DER bit string"),
+            DerError::InvalidBoolean => write!(f, "invalid DER boolean"),
+            DerError::InvalidString => write!(f, "invalid DER string"),
+            DerError::InvalidTime => write!(f, "invalid DER time"),
+        }
+    }
+}
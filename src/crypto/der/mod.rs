@@ -0,0 +1,10 @@
+mod error;
+mod reader;
+mod writer;
+mod time;
+pub mod tag;
+pub mod oid;
+
+pub use error::DerError;
+pub use reader::DerReader;
+pub use writer::DerWriter;
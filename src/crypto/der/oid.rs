@@ -0,0 +1,67 @@
+// Encoded contents of the object identifiers we read and write.
+
+// 1.2.840.10045.2.1
+pub const EC_PUBLIC_KEY: &[u8] = &[0x2a, 0x86, 0x48, 0xce, 0x3d, 0x02, 0x01];
+// 1.2.840.10045.3.1.7, also known as secp256r1
+pub const PRIME256V1: &[u8] = &[0x2a, 0x86, 0x48, 0xce, 0x3d, 0x03, 0x01, 0x07];
+// 1.3.101.110
+pub const X25519: &[u8] = &[0x2b, 0x65, 0x6e];
+// 1.3.101.112
+pub const ED25519: &[u8] = &[0x2b, 0x65, 0x70];
+// 1.2.840.113549.1.1.1
+pub const RSA_ENCRYPTION: &[u8] = &[0x2a, 0x86, 0x48, 0x86, 0xf7, 0x0d, 0x01, 0x01, 0x01];
+
+// 1.2.840.10045.4.3.2
+pub const ECDSA_WITH_SHA256: &[u8] = &[0x2a, 0x86, 0x48, 0xce, 0x3d, 0x04, 0x03, 0x02];
+// 1.2.840.10045.4.3.3
+pub const ECDSA_WITH_SHA384: &[u8] = &[0x2a, 0x86, 0x48, 0xce, 0x3d, 0x04, 0x03, 0x03];
+// 1.2.840.113549.1.1.11
+pub const SHA256_WITH_RSA_ENCRYPTION: &[u8] = &[0x2a, 0x86, 0x48, 0x86, 0xf7, 0x0d, 0x01, 0x01, 0x0b];
+
+// 2.5.4.3
+pub const COMMON_NAME: &[u8] = &[0x55, 0x04, 0x03];
+// 2.5.4.10
+pub const ORGANIZATION_NAME: &[u8] = &[0x55, 0x04, 0x0a];
+
+// 2.5.29.15
+pub const KEY_USAGE: &[u8] = &[0x55, 0x1d, 0x0f];
+// 2.5.29.17
+pub const SUBJECT_ALT_NAME: &[u8] = &[0x55, 0x1d, 0x11];
+// 2.5.29.19
+pub const BASIC_CONSTRAINTS: &[u8] = &[0x55, 0x1d, 0x13];
+// 2.5.29.37
+pub const EXTENDED_KEY_USAGE: &[u8] = &[0x55, 0x1d, 0x25];
+// 1.3.6.1.5.5.7.3.1
+pub const SERVER_AUTH: &[u8] = &[0x2b, 0x06, 0x01, 0x05, 0x05, 0x07, 0x03, 0x01];
+
+// Formats encoded object identifier contents in dotted form, e.g. for error messages.
+pub fn to_string(oid: &[u8]) -> String {
+    let mut arcs: Vec<String> = vec![];
+    let mut value: u64 = 0;
+
+    for &byte in oid {
+        value = (value << 7) | (byte & 0x7f) as u64;
+        if byte & 0x80 != 0 {
+            continue;
+        }
+
+        // the first byte holds the first two arcs as 40 * first + second
+        if arcs.is_empty() {
+            let first = (value / 40).min(2);
+            arcs.push(first.to_string());
+            arcs.push((value - first * 40).to_string());
+        } else {
+            arcs.push(value.to_string());
+        }
+        value = 0;
+    }
+
+    arcs.join(".")
+}
+
+#[test]
+fn to_string_test() {
+    assert_eq!(to_string(ECDSA_WITH_SHA256), "1.2.840.10045.4.3.2");
+    assert_eq!(to_string(ED25519), "1.3.101.112");
+    assert_eq!(to_string(SERVER_AUTH), "1.3.6.1.5.5.7.3.1");
+}
@@ -0,0 +1,273 @@
+// A reader for DER encoded ASN.1 (ITU-T X.690). Only definite, minimal lengths and single byte
+// tags are accepted, which covers X.509 and PKCS#8.
+
+use super::error::DerError;
+use super::tag;
+use super::time;
+
+#[derive(Clone)]
+pub struct DerReader<'a> {
+    data: &'a [u8],
+    position: usize
+}
+
+// One tag-length-value element.
+pub struct Element<'a> {
+    pub tag: u8,
+    pub contents: &'a [u8],
+    // the whole encoding, including the tag and length
+    pub raw: &'a [u8]
+}
+
+impl<'a> DerReader<'a> {
+    pub fn new(data: &'a [u8]) -> Self {
+        Self {
+            data,
+            position: 0
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.position == self.data.len()
+    }
+
+    pub fn peek_tag(&self) -> Option<u8> {
+        self.data.get(self.position).copied()
+    }
+
+    // Fails unless every element has been read.
+    pub fn finish(&self) -> Result<(), DerError> {
+        match self.data.len() - self.position {
+            0 => Ok(()),
+            remaining => Err(DerError::TrailingData(remaining))
+        }
+    }
+
+    fn read_byte(&mut self) -> Result<u8, DerError> {
+        let byte = *self.data.get(self.position).ok_or(DerError::UnexpectedEnd)?;
+        self.position += 1;
+        Ok(byte)
+    }
+
+    fn read_length(&mut self) -> Result<usize, DerError> {
+        let first = self.read_byte()?;
+        if first < 0x80 {
+            return Ok(first as usize);
+        }
+
+        // 0x80 is the indefinite length, which DER doesn't allow
+        let length_bytes = (first & 0x7f) as usize;
+        if length_bytes == 0 || length_bytes > 4 {
+            return Err(DerError::InvalidLength);
+        }
+
+        let mut length = 0usize;
+        for i in 0..length_bytes {
+            let byte = self.read_byte()?;
+            if i == 0 && byte == 0 {
+                return Err(DerError::NonMinimalEncoding);
+            }
+            length = (length << 8) | byte as usize;
+        }
+
+        // lengths below 128 must use the short form
+        if length < 0x80 {
+            return Err(DerError::NonMinimalEncoding);
+        }
+
+        Ok(length)
+    }
+
+    pub fn read_element(&mut self) -> Result<Element<'a>, DerError> {
+        let start = self.position;
+
+        let element_tag = self.read_byte()?;
+        if element_tag & 0x1f == 0x1f {
+            return Err(DerError::UnsupportedTag(element_tag));
+        }
+
+        let length = self.read_length()?;
+        if length > self.data.len() - self.position {
+            return Err(DerError::InvalidLength);
+        }
+
+        let contents = &self.data[self.position..self.position + length];
+        self.position += length;
+
+        Ok(Element {
+            tag: element_tag,
+            contents,
+            raw: &self.data[start..self.position]
+        })
+    }
+
+    // Reads the next element, which must have the given tag, and returns its contents.
+    pub fn read(&mut self, expected_tag: u8) -> Result<&'a [u8], DerError> {
+        let element = self.read_element()?;
+        if element.tag != expected_tag {
+            return Err(DerError::UnexpectedTag(element.tag));
+        }
+
+        Ok(element.contents)
+    }
+
+    // Reads the next element only if it has the given tag, e.g. for OPTIONAL fields.
+    pub fn read_optional(&mut self, expected_tag: u8) -> Result<Option<&'a [u8]>, DerError> {
+        if self.peek_tag() != Some(expected_tag) {
+            return Ok(None);
+        }
+
+        self.read(expected_tag).map(Some)
+    }
+
+    pub fn read_sequence(&mut self) -> Result<DerReader<'a>, DerError> {
+        Ok(DerReader::new(self.read(tag::SEQUENCE)?))
+    }
+
+    pub fn read_set(&mut self) -> Result<DerReader<'a>, DerError> {
+        Ok(DerReader::new(self.read(tag::SET)?))
+    }
+
+    // Reads a non-negative INTEGER as its big-endian magnitude without leading zeros.
+    pub fn read_unsigned_integer(&mut self) -> Result<&'a [u8], DerError> {
+        let contents = self.read(tag::INTEGER)?;
+        if contents.is_empty() || contents[0] & 0x80 != 0 {
+            return Err(DerError::InvalidInteger);
+        }
+
+        if contents.len() > 1 && contents[0] == 0 {
+            if contents[1] & 0x80 == 0 {
+                return Err(DerError::NonMinimalEncoding);
+            }
+            return Ok(&contents[1..]);
+        }
+
+        Ok(contents)
+    }
+
+    pub fn read_small_integer(&mut self) -> Result<u64, DerError> {
+        let magnitude = self.read_unsigned_integer()?;
+        if magnitude.len() > 8 {
+            return Err(DerError::InvalidInteger);
+        }
+
+        Ok(magnitude.iter().fold(0, |value, &byte| (value << 8) | byte as u64))
+    }
+
+    pub fn read_boolean(&mut self) -> Result<bool, DerError> {
+        match self.read(tag::BOOLEAN)? {
+            [0x00] => Ok(false),
+            [0xff] => Ok(true),
+            _ => Err(DerError::InvalidBoolean)
+        }
+    }
+
+    pub fn read_null(&mut self) -> Result<(), DerError> {
+        match self.read(tag::NULL)? {
+            [] => Ok(()),
+            _ => Err(DerError::InvalidLength)
+        }
+    }
+
+    // Returns the encoded contents of an OBJECT IDENTIFIER, to compare against the oid constants.
+    pub fn read_oid(&mut self) -> Result<&'a [u8], DerError> {
+        self.read(tag::OBJECT_IDENTIFIER)
+    }
+
+    pub fn read_octet_string(&mut self) -> Result<&'a [u8], DerError> {
+        self.read(tag::OCTET_STRING)
+    }
+
+    // Reads a BIT STRING holding whole bytes, as used for keys and signatures.
+    pub fn read_bit_string(&mut self) -> Result<&'a [u8], DerError> {
+        match self.read(tag::BIT_STRING)? {
+            [0x00, bytes @ ..] => Ok(bytes),
+            _ => Err(DerError::InvalidBitString)
+        }
+    }
+
+    // Reads any of the string types used in names.
+    pub fn read_string(&mut self) -> Result<String, DerError> {
+        let element = self.read_element()?;
+        match element.tag {
+            tag::UTF8_STRING | tag::PRINTABLE_STRING | tag::IA5_STRING => {
+                String::from_utf8(element.contents.to_vec()).map_err(|_| DerError::InvalidString)
+            },
+            other => Err(DerError::UnexpectedTag(other))
+        }
+    }
+
+    // Reads a UTCTime or GeneralizedTime as a unix timestamp.
+    pub fn read_time(&mut self) -> Result<i64, DerError> {
+        let element = self.read_element()?;
+        time::parse(element.tag, element.contents)
+    }
+}
+
+#[cfg(test)]
+use super::writer::DerWriter;
+
+#[test]
+fn round_trip_test() {
+    let mut writer = DerWriter::new();
+    writer.write_sequence(|sequence| {
+        sequence.write_small_integer(0);
+        sequence.write_small_integer(128);
+        sequence.write_unsigned_integer(&[0x00, 0x00, 0xff]);
+        sequence.write_boolean(true);
+        sequence.write_null();
+        sequence.write_oid(super::oid::ED25519);
+        sequence.write_octet_string(&[0xab; 200]);
+        sequence.write_bit_string(&[0x01, 0x02]);
+        sequence.write_utf8_string("g-vault");
+        sequence.write_time(1704067200);
+    });
+    let der = writer.finish();
+
+    // the 200 byte octet string needs the long length form
+    assert_eq!(&der[..4], &[0x30, 0x81, 0xfd, 0x02]);
+
+    let mut reader = DerReader::new(&der);
+    let mut sequence = reader.read_sequence().unwrap();
+    assert!(reader.finish().is_ok());
+
+    assert_eq!(sequence.read_small_integer(), Ok(0));
+    assert_eq!(sequence.read_small_integer(), Ok(128));
+    assert_eq!(sequence.read_unsigned_integer(), Ok(&[0xff][..]));
+    assert_eq!(sequence.read_boolean(), Ok(true));
+    assert_eq!(sequence.read_null(), Ok(()));
+    assert_eq!(sequence.read_oid(), Ok(super::oid::ED25519));
+    assert_eq!(sequence.read_octet_string(), Ok(&[0xab; 200][..]));
+    assert_eq!(sequence.read_bit_string(), Ok(&[0x01, 0x02][..]));
+    assert_eq!(sequence.read_string(), Ok("g-vault".to_string()));
+    assert_eq!(sequence.read_time(), Ok(1704067200));
+    assert!(sequence.is_empty());
+}
+
+#[test]
+fn invalid_encoding_test() {
+    let test_cases: [(&[u8], DerError); 8] = [
+        (&[0x04, 0x02, 0x00], DerError::InvalidLength),
+        (&[0x04], DerError::UnexpectedEnd),
+        // indefinite length
+        (&[0x04, 0x80, 0x00, 0x00], DerError::InvalidLength),
+        // long form for a length below 128
+        (&[0x04, 0x81, 0x01, 0x00], DerError::NonMinimalEncoding),
+        // long form with a leading zero byte
+        (&[0x04, 0x82, 0x00, 0x81], DerError::NonMinimalEncoding),
+        (&[0x1f, 0x01, 0x00], DerError::UnsupportedTag(0x1f)),
+        (&[0x02, 0x01, 0x00], DerError::UnexpectedTag(0x02)),
+        (&[0x04, 0x00, 0x00], DerError::TrailingData(1)),
+    ];
+
+    for (der, expected_error) in test_cases {
+        let mut reader = DerReader::new(der);
+        let result = reader.read_octet_string().and_then(|_| reader.finish());
+        assert_eq!(result, Err(expected_error));
+    }
+
+    // integers must be minimal and, for the unsigned reader, non-negative
+    assert_eq!(DerReader::new(&[0x02, 0x02, 0x00, 0x01]).read_unsigned_integer(), Err(DerError::NonMinimalEncoding));
+    assert_eq!(DerReader::new(&[0x02, 0x01, 0x80]).read_unsigned_integer(), Err(DerError::InvalidInteger));
+    assert_eq!(DerReader::new(&[0x01, 0x01, 0x01]).read_boolean(), Err(DerError::InvalidBoolean));
+}
@@ -0,0 +1,20 @@
+// Universal tags, including the constructed bit where the type is always constructed.
+
+pub const BOOLEAN: u8 = 0x01;
+pub const INTEGER: u8 = 0x02;
+pub const BIT_STRING: u8 = 0x03;
+pub const OCTET_STRING: u8 = 0x04;
+pub const NULL: u8 = 0x05;
+pub const OBJECT_IDENTIFIER: u8 = 0x06;
+pub const UTF8_STRING: u8 = 0x0c;
+pub const PRINTABLE_STRING: u8 = 0x13;
+pub const IA5_STRING: u8 = 0x16;
+pub const UTC_TIME: u8 = 0x17;
+pub const GENERALIZED_TIME: u8 = 0x18;
+pub const SEQUENCE: u8 = 0x30;
+pub const SET: u8 = 0x31;
+
+// [number] in a context-specific class, e.g. the [0] EXPLICIT version in a certificate
+pub const fn context_specific(number: u8, constructed: bool) -> u8 {
+    0x80 | if constructed { 0x20 } else { 0x00 } | number
+}
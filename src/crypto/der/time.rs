@@ -0,0 +1,110 @@
+// Conversion between unix timestamps and the UTCTime / GeneralizedTime forms DER requires:
+// YYMMDDHHMMSSZ and YYYYMMDDHHMMSSZ, always in UTC with whole seconds.
+
+use super::error::DerError;
+use super::tag;
+
+// Days since 1970-01-01 for a proleptic Gregorian date (Howard Hinnant's days_from_civil).
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * (if month > 2 { month - 3 } else { month + 9 }) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146097 + day_of_era - 719468
+}
+
+// The inverse of days_from_civil, returning (year, month, day).
+fn civil_from_days(days: i64) -> (i64, i64, i64) {
+    let days = days + 719468;
+    let era = days.div_euclid(146097);
+    let day_of_era = days - era * 146097;
+    let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 { month_index + 3 } else { month_index - 9 };
+    let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+    (year, month, day)
+}
+
+fn parse_digits(digits: &[u8]) -> Result<i64, DerError> {
+    let mut value = 0;
+    for &digit in digits {
+        if !digit.is_ascii_digit() {
+            return Err(DerError::InvalidTime);
+        }
+        value = value * 10 + (digit - b'0') as i64;
+    }
+    Ok(value)
+}
+
+// Parses the contents of a UTCTime or GeneralizedTime element into a unix timestamp.
+pub fn parse(time_tag: u8, contents: &[u8]) -> Result<i64, DerError> {
+    let (year, rest) = match time_tag {
+        tag::UTC_TIME if contents.len() == 13 => {
+            // RFC 5280 section 4.1.2.5.1: two digit years are 1950 to 2049
+            let year = parse_digits(&contents[..2])?;
+            (if year >= 50 { 1900 + year } else { 2000 + year }, &contents[2..])
+        },
+        tag::GENERALIZED_TIME if contents.len() == 15 => {
+            (parse_digits(&contents[..4])?, &contents[4..])
+        },
+        _ => return Err(DerError::InvalidTime)
+    };
+
+    if rest[10] != b'Z' {
+        return Err(DerError::InvalidTime);
+    }
+
+    let month = parse_digits(&rest[0..2])?;
+    let day = parse_digits(&rest[2..4])?;
+    let hour = parse_digits(&rest[4..6])?;
+    let minute = parse_digits(&rest[6..8])?;
+    let second = parse_digits(&rest[8..10])?;
+
+    let days = days_from_civil(year, month, day);
+    // reject dates like February 30th by checking they survive the round trip
+    if !(1..=12).contains(&month) || civil_from_days(days) != (year, month, day)
+        || hour > 23 || minute > 59 || second > 59 {
+        return Err(DerError::InvalidTime);
+    }
+
+    Ok(days * 86400 + hour * 3600 + minute * 60 + second)
+}
+
+// Formats a unix timestamp, returning the tag to use and the element contents. RFC 5280 requires
+// UTCTime for dates before 2050 and GeneralizedTime from then on.
+pub fn format(timestamp: i64) -> (u8, Vec<u8>) {
+    let (year, month, day) = civil_from_days(timestamp.div_euclid(86400));
+    let seconds_of_day = timestamp.rem_euclid(86400);
+    let time = format!("{:02}{:02}{:02}{:02}{:02}Z",
+        month, day, seconds_of_day / 3600, seconds_of_day % 3600 / 60, seconds_of_day % 60);
+
+    if (1950..2050).contains(&year) {
+        (tag::UTC_TIME, format!("{:02}{}", year % 100, time).into_bytes())
+    } else {
+        (tag::GENERALIZED_TIME, format!("{:04}{}", year, time).into_bytes())
+    }
+}
+
+#[test]
+fn time_test() {
+    let test_cases: [(u8, &str, i64); 5] = [
+        (tag::UTC_TIME, "700101000000Z", 0),
+        (tag::UTC_TIME, "000229120000Z", 951825600),
+        (tag::UTC_TIME, "491231235959Z", 2524607999),
+        (tag::UTC_TIME, "500101000000Z", -631152000),
+        (tag::GENERALIZED_TIME, "20500101000000Z", 2524608000),
+    ];
+
+    for (time_tag, text, timestamp) in test_cases {
+        assert_eq!(parse(time_tag, text.as_bytes()), Ok(timestamp));
+        assert_eq!(format(timestamp), (time_tag, text.as_bytes().to_vec()));
+    }
+
+    let invalid = ["700101000000", "700230000000Z", "701301000000Z", "700101240000Z", "7001010000a0Z"];
+    for text in invalid {
+        assert_eq!(parse(tag::UTC_TIME, text.as_bytes()), Err(DerError::InvalidTime));
+    }
+}
@@ -0,0 +1,114 @@
+// A writer for DER encoded ASN.1. Constructed elements are written through closures, so the
+// length of the contents is known before the header is emitted.
+
+use super::tag;
+use super::time;
+
+pub struct DerWriter {
+    buffer: Vec<u8>
+}
+
+impl DerWriter {
+    pub fn new() -> Self {
+        Self {
+            buffer: vec![]
+        }
+    }
+
+    pub fn finish(self) -> Vec<u8> {
+        self.buffer
+    }
+
+    fn write_length(&mut self, length: usize) {
+        if length < 0x80 {
+            self.buffer.push(length as u8);
+            return;
+        }
+
+        let bytes = length.to_be_bytes();
+        let leading_zeros = bytes.iter().take_while(|&&b| b == 0).count();
+        self.buffer.push(0x80 | (bytes.len() - leading_zeros) as u8);
+        self.buffer.extend_from_slice(&bytes[leading_zeros..]);
+    }
+
+    pub fn write(&mut self, element_tag: u8, contents: &[u8]) {
+        self.buffer.push(element_tag);
+        self.write_length(contents.len());
+        self.buffer.extend_from_slice(contents);
+    }
+
+    // Appends an already encoded element.
+    pub fn write_raw(&mut self, encoded: &[u8]) {
+        self.buffer.extend_from_slice(encoded);
+    }
+
+    pub fn write_constructed(&mut self, element_tag: u8, write_contents: impl FnOnce(&mut DerWriter)) {
+        let mut contents = DerWriter::new();
+        write_contents(&mut contents);
+        self.write(element_tag, &contents.buffer);
+    }
+
+    pub fn write_sequence(&mut self, write_contents: impl FnOnce(&mut DerWriter)) {
+        self.write_constructed(tag::SEQUENCE, write_contents);
+    }
+
+    pub fn write_set(&mut self, write_contents: impl FnOnce(&mut DerWriter)) {
+        self.write_constructed(tag::SET, write_contents);
+    }
+
+    // Writes a non-negative INTEGER from its big-endian magnitude.
+    pub fn write_unsigned_integer(&mut self, magnitude: &[u8]) {
+        let leading_zeros = magnitude.iter().take_while(|&&b| b == 0).count();
+        let magnitude = &magnitude[leading_zeros..];
+
+        let mut contents = vec![];
+        // a leading zero keeps the number positive when the top bit is set
+        if magnitude.is_empty() || magnitude[0] & 0x80 != 0 {
+            contents.push(0x00);
+        }
+        contents.extend_from_slice(magnitude);
+
+        self.write(tag::INTEGER, &contents);
+    }
+
+    pub fn write_small_integer(&mut self, value: u64) {
+        self.write_unsigned_integer(&value.to_be_bytes());
+    }
+
+    pub fn write_boolean(&mut self, value: bool) {
+        self.write(tag::BOOLEAN, &[if value { 0xff } else { 0x00 }]);
+    }
+
+    pub fn write_null(&mut self) {
+        self.write(tag::NULL, &[]);
+    }
+
+    pub fn write_oid(&mut self, oid: &[u8]) {
+        self.write(tag::OBJECT_IDENTIFIER, oid);
+    }
+
+    pub fn write_octet_string(&mut self, bytes: &[u8]) {
+        self.write(tag::OCTET_STRING, bytes);
+    }
+
+    pub fn write_bit_string(&mut self, bytes: &[u8]) {
+        let mut contents = vec![0x00];
+        contents.extend_from_slice(bytes);
+        self.write(tag::BIT_STRING, &contents);
+    }
+
+    pub fn write_utf8_string(&mut self, value: &str) {
+        self.write(tag::UTF8_STRING, value.as_bytes());
+    }
+
+    pub fn write_time(&mut self, timestamp: i64) {
+        let (time_tag, contents) = time::format(timestamp);
+        self.write(time_tag, &contents);
+    }
+}
+
+impl Default for DerWriter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
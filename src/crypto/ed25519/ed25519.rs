@@ -0,0 +1,260 @@
+// Ed25519 signatures, as specified in RFC 8032 section 5.1.
+
+use std::fmt::{Display, Formatter};
+use super::point::Point;
+use super::scalar;
+use crate::crypto::sha512::sha512;
+use crate::utils::secret::Secret;
+
+pub const SIGNATURE_LENGTH: usize = 64;
+
+#[derive(Debug, PartialEq)]
+pub enum Ed25519Error {
+    // The bytes are not the encoding of a point on the curve.
+    InvalidPublicKey,
+}
+
+impl Display for Ed25519Error {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Ed25519Error::InvalidPublicKey => write!(f, "invalid Ed25519 public key"),
+        }
+    }
+}
+
+pub struct SigningKey {
+    seed: Secret<[u8; 32]>,
+    // the clamped secret scalar, from the first half of SHA-512(seed)
+    scalar: Secret<[u8; 32]>,
+    // the second half of SHA-512(seed), used to derive the per-signature nonce
+    prefix: Secret<[u8; 32]>,
+    verifying_key: VerifyingKey
+}
+
+impl SigningKey {
+    pub fn generate() -> Self {
+        Self::from_seed(&crate::utils::random::random_u8_32())
+    }
+
+    // Creates the key from the 32 byte private key (the "seed" in RFC 8032).
+    pub fn from_seed(seed: &[u8; 32]) -> Self {
+        let hash = Secret::new(sha512::hash(seed));
+
+        let mut scalar = Secret::new([0u8; 32]);
+        let scalar_bytes = scalar.expose_mut();
+        scalar_bytes.copy_from_slice(&hash.expose()[..32]);
+        scalar_bytes[0] &= 0xf8;
+        scalar_bytes[31] = (scalar_bytes[31] & 0x7f) | 0x40;
+
+        let mut prefix = Secret::new([0u8; 32]);
+        prefix.expose_mut().copy_from_slice(&hash.expose()[32..]);
+
+        let point = Point::base().scalar_multiply(scalar.expose());
+        let verifying_key = VerifyingKey {
+            value: point.compress(),
+            point
+        };
+
+        Self {
+            seed: Secret::new(*seed),
+            scalar,
+            prefix,
+            verifying_key
+        }
+    }
+
+    pub fn seed(&self) -> &[u8; 32] {
+        self.seed.expose()
+    }
+
+    pub fn verifying_key(&self) -> &VerifyingKey {
+        &self.verifying_key
+    }
+
+    pub fn sign(&self, message: &[u8]) -> [u8; SIGNATURE_LENGTH] {
+        // r = SHA-512(prefix || M) mod L, R = [r]B
+        let mut nonce_input = Secret::new(self.prefix.expose().to_vec());
+        nonce_input.expose_mut().extend_from_slice(message);
+        let nonce_hash = Secret::new(sha512::hash(nonce_input.expose()));
+        let r = Secret::new(scalar::reduce(nonce_hash.expose()));
+        let big_r = Point::base().scalar_multiply(r.expose()).compress();
+
+        // S = (r + SHA-512(R || A || M) * s) mod L
+        let k = challenge(&big_r, &self.verifying_key.value, message);
+        let s = scalar::multiply_add(&k, self.scalar.expose(), r.expose());
+
+        let mut signature = [0u8; SIGNATURE_LENGTH];
+        signature[..32].copy_from_slice(&big_r);
+        signature[32..].copy_from_slice(&s);
+        signature
+    }
+}
+
+pub struct VerifyingKey {
+    value: [u8; 32],
+    point: Point
+}
+
+impl VerifyingKey {
+    pub fn from_bytes(bytes: &[u8; 32]) -> Result<Self, Ed25519Error> {
+        let point = Point::decompress(bytes).ok_or(Ed25519Error::InvalidPublicKey)?;
+
+        Ok(Self {
+            value: *bytes,
+            point
+        })
+    }
+
+    pub fn as_bytes(&self) -> &[u8; 32] {
+        &self.value
+    }
+
+    // Checks that [S]B = R + [k]A, by computing [S]B - [k]A and comparing its encoding with R.
+    pub fn verify(&self, message: &[u8], signature: &[u8; SIGNATURE_LENGTH]) -> bool {
+        let mut big_r = [0u8; 32];
+        big_r.copy_from_slice(&signature[..32]);
+        let mut s = [0u8; 32];
+        s.copy_from_slice(&signature[32..]);
+
+        if !scalar::is_canonical(&s) {
+            return false;
+        }
+
+        let k = challenge(&big_r, &self.value, message);
+        let check = Point::base().scalar_multiply(&s)
+            .add(&self.point.negate().scalar_multiply(&k));
+
+        check.compress() == big_r
+    }
+}
+
+// k = SHA-512(R || A || M) mod L
+fn challenge(big_r: &[u8; 32], public_key: &[u8; 32], message: &[u8]) -> [u8; 32] {
+    let mut input = Vec::with_capacity(64 + message.len());
+    input.extend_from_slice(big_r);
+    input.extend_from_slice(public_key);
+    input.extend_from_slice(message);
+
+    scalar::reduce(&sha512::hash(&input))
+}
+
+#[cfg(test)]
+use crate::utils::formatting::hex_to_bytes;
+
+#[test]
+fn rfc8032_test() {
+    // RFC 8032 section 7.1, tests 1, 2, 3 and SHA(abc)
+    let test_cases = [
+        // (secret key, public key, message, signature)
+        ("9d61b19deffd5a60ba844af492ec2cc44449c5697b326919703bac031cae7f60",
+         "d75a980182b10ab7d54bfed3c964073a0ee172f3daa62325af021a68f707511a",
+         "",
+         "e5564300c360ac729086e2cc806e828a84877f1eb8e5d974d873e06522490155\
+          5fb8821590a33bacc61e39701cf9b46bd25bf5f0595bbe24655141438e7a100b"),
+        ("4ccd089b28ff96da9db6c346ec114e0f5b8a319f35aba624da8cf6ed4fb8a6fb",
+         "3d4017c3e843895a92b70aa74d1b7ebc9c982ccf2ec4968cc0cd55f12af4660c",
+         "72",
+         "92a009a9f0d4cab8720e820b5f642540a2b27b5416503f8fb3762223ebdb69da\
+          085ac1e43e15996e458f3613d0f11d8c387b2eaeb4302aeeb00d291612bb0c00"),
+        ("c5aa8df43f9f837bedb7442f31dcb7b166d38535076f094b85ce3a2e0b4458f7",
+         "fc51cd8e6218a1a38da47ed00230f0580816ed13ba3303ac5deb911548908025",
+         "af82",
+         "6291d657deec24024827e69c3abe01a30ce548a284743a445e3680d7db5ac3ac\
+          18ff9b538d16f290ae67f760984dc6594a7c15e9716ed28dc027beceea1ec40a"),
+        ("833fe62409237b9d62ec77587520911e9a759cec1d19755b7da901b96dca3d42",
+         "ec172b93ad5e563bf4932c70e1245034c35467ef2efd4d64ebf819683467e2bf",
+         "ddaf35a193617abacc417349ae20413112e6fa4e89a97ea20a9eeee64b55d39a\
+          2192992a274fc1a836ba3c23a3feebbd454d4423643ce80e2a9ac94fa54ca49f",
+         "dc2a4459e7369633a52b1bf277839a00201009a3efbf3ecb69bea2186c26b589\
+          09351fc9ac90b3ecfdfbc7c66431e0303dca179c138ac17ad9bef1177331a704"),
+    ];
+
+    for (secret_key, public_key, message, signature) in test_cases {
+        let seed: [u8; 32] = hex_to_bytes(secret_key).unwrap().try_into().unwrap();
+        let public_key: [u8; 32] = hex_to_bytes(public_key).unwrap().try_into().unwrap();
+        let message = hex_to_bytes(message).unwrap();
+        let signature: [u8; SIGNATURE_LENGTH] = hex_to_bytes(signature).unwrap().try_into().unwrap();
+
+        let signing_key = SigningKey::from_seed(&seed);
+        assert_eq!(signing_key.verifying_key().as_bytes(), &public_key);
+        assert_eq!(signing_key.sign(&message), signature);
+
+        let verifying_key = VerifyingKey::from_bytes(&public_key).unwrap();
+        assert!(verifying_key.verify(&message, &signature));
+    }
+}
+
+#[test]
+fn verify_rejects_invalid_signatures_test() {
+    let signing_key = SigningKey::from_seed(&[0x11; 32]);
+    let verifying_key = signing_key.verifying_key();
+    let signature = signing_key.sign(b"backup-2026-10-18.vault");
+
+    assert!(verifying_key.verify(b"backup-2026-10-18.vault", &signature));
+    assert!(!verifying_key.verify(b"backup-2026-10-19.vault", &signature));
+
+    for i in [0, 31, 32, 63] {
+        let mut tampered = signature;
+        tampered[i] ^= 0x01;
+        assert!(!verifying_key.verify(b"backup-2026-10-18.vault", &tampered));
+    }
+
+    // S + L is a valid solution to the verification equation, but is not canonical
+    let l: [u8; 32] = hex_to_bytes("edd3f55c1a631258d69cf7a2def9de1400000000000000000000000000000010")
+        .unwrap()
+        .try_into()
+        .unwrap();
+    let mut malleated = signature;
+    let mut carry = 0u16;
+    for i in 0..32 {
+        let sum = malleated[32 + i] as u16 + l[i] as u16 + carry;
+        malleated[32 + i] = sum as u8;
+        carry = sum >> 8;
+    }
+    assert!(!verifying_key.verify(b"backup-2026-10-18.vault", &malleated));
+
+    // a different key
+    let other_key = SigningKey::from_seed(&[0x22; 32]);
+    assert!(!other_key.verifying_key().verify(b"backup-2026-10-18.vault", &signature));
+}
+
+#[test]
+fn invalid_public_key_test() {
+    // y = 2 has no matching x on the curve
+    let mut not_on_curve = [0u8; 32];
+    not_on_curve[0] = 2;
+    assert_eq!(VerifyingKey::from_bytes(&not_on_curve).err(), Some(Ed25519Error::InvalidPublicKey));
+
+    // y = p + 1 is not fully reduced
+    let unreduced: [u8; 32] = hex_to_bytes("eeffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff7f")
+        .unwrap()
+        .try_into()
+        .unwrap();
+    assert_eq!(VerifyingKey::from_bytes(&unreduced).err(), Some(Ed25519Error::InvalidPublicKey));
+
+    // y = 1 gives x = 0, which can't have the sign bit set
+    let mut negative_zero = [0u8; 32];
+    negative_zero[0] = 1;
+    negative_zero[31] = 0x80;
+    assert_eq!(VerifyingKey::from_bytes(&negative_zero).err(), Some(Ed25519Error::InvalidPublicKey));
+}
+
+#[cfg(test)]
+use crate::utils::test_vectors::wycheproof;
+
+#[test]
+fn wycheproof_test() {
+    for group in &wycheproof::load("ed25519_test.json") {
+        // every test in a group with an invalid key must be rejected
+        let verifying_key = <[u8; 32]>::try_from(group.hex("publicKey.pk")).ok()
+            .and_then(|public_key| VerifyingKey::from_bytes(&public_key).ok());
+        for test in &group.tests {
+            // signatures of the wrong length can't be passed in at all
+            let accepted = match (&verifying_key, <[u8; SIGNATURE_LENGTH]>::try_from(test.hex("sig"))) {
+                (Some(verifying_key), Ok(signature)) => verifying_key.verify(&test.hex("msg"), &signature),
+                _ => false
+            };
+            test.check(accepted);
+        }
+    }
+}
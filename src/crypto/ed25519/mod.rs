@@ -0,0 +1,5 @@
+mod point;
+mod scalar;
+mod ed25519;
+
+pub use ed25519::{SigningKey, VerifyingKey};
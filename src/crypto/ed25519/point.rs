@@ -0,0 +1,184 @@
+// Points on the twisted Edwards curve -x^2 + y^2 = 1 + d x^2 y^2 over GF(2^255 - 19), which is
+// birationally equivalent to Curve25519. The field operations are shared with x25519.
+//
+// Points are kept in extended coordinates (X : Y : Z : T) with x = X/Z, y = Y/Z and xy = T/Z,
+// which gives a single addition formula that also works for doubling and the identity, so
+// scalar multiplication doesn't need any secret-dependent branches.
+
+use crate::crypto::x25519::field::FieldElement;
+
+// d = -121665/121666
+const D: FieldElement = FieldElement::from_limbs([
+    0x34dca135978a3, 0x1a8283b156ebd, 0x5e7a26001c029, 0x739c663a03cbb, 0x52036cee2b6ff
+]);
+
+// 2 * d
+const D2: FieldElement = FieldElement::from_limbs([
+    0x69b9426b2f159, 0x35050762add7a, 0x3cf44c0038052, 0x6738cc7407977, 0x2406d9dc56dff
+]);
+
+// x coordinate of the base point
+const BASE_X: FieldElement = FieldElement::from_limbs([
+    0x62d608f25d51a, 0x412a4b4f6592a, 0x75b7171a4b31d, 0x1ff60527118fe, 0x216936d3cd6e5
+]);
+
+// y coordinate of the base point, 4/5
+const BASE_Y: FieldElement = FieldElement::from_limbs([
+    0x6666666666658, 0x4cccccccccccc, 0x1999999999999, 0x3333333333333, 0x6666666666666
+]);
+
+// sqrt(-1)
+const SQRT_M1: FieldElement = FieldElement::from_limbs([
+    0x61b274a0ea0b0, 0x0d5a5fc8f189d, 0x7ef5e9cbd0c60, 0x78595a6804c9e, 0x2b8324804fc1d
+]);
+
+#[derive(Clone, Copy)]
+pub struct Point {
+    x: FieldElement,
+    y: FieldElement,
+    z: FieldElement,
+    t: FieldElement
+}
+
+impl Point {
+    pub fn identity() -> Self {
+        Self {
+            x: FieldElement::ZERO,
+            y: FieldElement::ONE,
+            z: FieldElement::ONE,
+            t: FieldElement::ZERO
+        }
+    }
+
+    pub fn base() -> Self {
+        Self {
+            x: BASE_X,
+            y: BASE_Y,
+            z: FieldElement::ONE,
+            t: BASE_X.multiply(&BASE_Y)
+        }
+    }
+
+    // Unified addition in extended coordinates ("add-2008-hwcd-3")
+    pub fn add(&self, other: &Point) -> Point {
+        let a = self.y.sub(&self.x).multiply(&other.y.sub(&other.x));
+        let b = self.y.add(&self.x).multiply(&other.y.add(&other.x));
+        let c = self.t.multiply(&other.t).multiply(&D2);
+        let d = self.z.multiply(&other.z);
+        let d = d.add(&d);
+
+        let e = b.sub(&a);
+        let f = d.sub(&c);
+        let g = d.add(&c);
+        let h = b.add(&a);
+
+        Point {
+            x: e.multiply(&f),
+            y: h.multiply(&g),
+            z: g.multiply(&f),
+            t: e.multiply(&h)
+        }
+    }
+
+    pub fn negate(&self) -> Point {
+        Point {
+            x: self.x.negate(),
+            y: self.y,
+            z: self.z,
+            t: self.t.negate()
+        }
+    }
+
+    // Multiplies the point by a 256 bit little endian scalar using a double-and-add ladder
+    // with constant-time swaps, so the running time does not depend on the scalar.
+    pub fn scalar_multiply(&self, scalar: &[u8; 32]) -> Point {
+        let mut result = Point::identity();
+        let mut addend = *self;
+
+        for i in (0..256).rev() {
+            let bit = ((scalar[i >> 3] >> (i & 7)) & 1) as u64;
+            conditional_swap(&mut result, &mut addend, bit);
+            addend = addend.add(&result);
+            result = result.add(&result);
+            conditional_swap(&mut result, &mut addend, bit);
+        }
+
+        result
+    }
+
+    // Encodes the point as the y coordinate, with the lowest bit of x stored in the top bit.
+    pub fn compress(&self) -> [u8; 32] {
+        let z_inverse = self.z.invert();
+        let x = self.x.multiply(&z_inverse);
+        let y = self.y.multiply(&z_inverse);
+
+        let mut result = y.to_bytes();
+        result[31] ^= parity(&x) << 7;
+        result
+    }
+
+    // Decodes a point as described in RFC 8032 section 5.1.3. Fails if the y coordinate isn't
+    // fully reduced, or if there is no x coordinate for it on the curve.
+    pub fn decompress(bytes: &[u8; 32]) -> Option<Point> {
+        let sign = bytes[31] >> 7;
+        let y = FieldElement::from_bytes(bytes);
+
+        // from_bytes ignores the top bit, and doesn't reduce y, so check that y < p
+        let mut y_bytes = *bytes;
+        y_bytes[31] &= 0x7f;
+        if y.to_bytes() != y_bytes {
+            return None;
+        }
+
+        // x^2 = (y^2 - 1) / (d y^2 + 1)
+        let y_squared = y.square();
+        let u = y_squared.sub(&FieldElement::ONE);
+        let v = y_squared.multiply(&D).add(&FieldElement::ONE);
+
+        // candidate root x = u v^3 (u v^7)^((p - 5) / 8)
+        let v3 = v.square().multiply(&v);
+        let v7 = v3.square().multiply(&v);
+        let mut x = u.multiply(&v3).multiply(&u.multiply(&v7).pow_p58());
+
+        // the candidate is either a root, or a root multiplied by sqrt(-1)
+        let v_x_squared = v.multiply(&x.square());
+        if !field_equal(&v_x_squared, &u) {
+            if !field_equal(&v_x_squared, &u.negate()) {
+                return None;
+            }
+            x = x.multiply(&SQRT_M1);
+        }
+
+        // x = 0 has no negative, so a set sign bit is invalid
+        if field_equal(&x, &FieldElement::ZERO) && sign == 1 {
+            return None;
+        }
+
+        if parity(&x) != sign {
+            x = x.negate();
+        }
+
+        Some(Point {
+            x,
+            y,
+            z: FieldElement::ONE,
+            t: x.multiply(&y)
+        })
+    }
+}
+
+fn conditional_swap(a: &mut Point, b: &mut Point, bit: u64) {
+    FieldElement::conditional_swap(&mut a.x, &mut b.x, bit);
+    FieldElement::conditional_swap(&mut a.y, &mut b.y, bit);
+    FieldElement::conditional_swap(&mut a.z, &mut b.z, bit);
+    FieldElement::conditional_swap(&mut a.t, &mut b.t, bit);
+}
+
+fn field_equal(a: &FieldElement, b: &FieldElement) -> bool {
+    a.to_bytes() == b.to_bytes()
+}
+
+// The lowest bit of the fully reduced field element, used as the "sign" of x.
+fn parity(a: &FieldElement) -> u8 {
+    a.to_bytes()[0] & 1
+}
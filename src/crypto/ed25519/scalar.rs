@@ -0,0 +1,92 @@
+// Arithmetic on scalars modulo the order of the Ed25519 base point,
+// L = 2^252 + 27742317777372353535851937790883648493.
+//
+// Scalars are little endian byte strings. Intermediate values are kept as one signed byte-sized
+// limb per i64, so carries can be handled lazily.
+
+use crate::utils::zeroize::zeroize_words;
+
+// L as little endian bytes
+const L: [i64; 32] = [
+    0xed, 0xd3, 0xf5, 0x5c, 0x1a, 0x63, 0x12, 0x58, 0xd6, 0x9c, 0xf7, 0xa2, 0xde, 0xf9, 0xde, 0x14,
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x10
+];
+
+// Reduces a 512 bit little endian number (e.g. a SHA-512 hash) modulo L.
+pub fn reduce(input: &[u8; 64]) -> [u8; 32] {
+    let mut x = [0i64; 64];
+    for (limb, &byte) in x.iter_mut().zip(input.iter()) {
+        *limb = byte as i64;
+    }
+
+    mod_l(&mut x)
+}
+
+// Computes (a * b + c) mod L.
+pub fn multiply_add(a: &[u8; 32], b: &[u8; 32], c: &[u8; 32]) -> [u8; 32] {
+    let mut x = [0i64; 64];
+    for (i, &byte) in c.iter().enumerate() {
+        x[i] = byte as i64;
+    }
+
+    for (i, &a_byte) in a.iter().enumerate() {
+        for (j, &b_byte) in b.iter().enumerate() {
+            x[i + j] += a_byte as i64 * b_byte as i64;
+        }
+    }
+
+    mod_l(&mut x)
+}
+
+// Whether the scalar is fully reduced (less than L). RFC 8032 requires this of the S half of
+// a signature, otherwise signatures would be malleable.
+pub fn is_canonical(scalar: &[u8; 32]) -> bool {
+    for i in (0..32).rev() {
+        let limb = scalar[i] as i64;
+        if limb != L[i] {
+            return limb < L[i];
+        }
+    }
+
+    // equal to L
+    false
+}
+
+// Reduces a number of up to 64 byte-sized limbs modulo L, following TweetNaCl's modL.
+fn mod_l(x: &mut [i64; 64]) -> [u8; 32] {
+    // fold the top 32 limbs down, using 2^256 = -16 * (L - 2^252) mod L
+    for i in (32..64).rev() {
+        let mut carry = 0i64;
+        let mut j = i - 32;
+        while j < i - 12 {
+            x[j] += carry - 16 * x[i] * L[j - (i - 32)];
+            carry = (x[j] + 128) >> 8;
+            x[j] -= carry << 8;
+            j += 1;
+        }
+        x[j] += carry;
+        x[i] = 0;
+    }
+
+    // subtract multiples of L based on the top 4 bits
+    let mut carry = 0i64;
+    for j in 0..32 {
+        x[j] += carry - (x[31] >> 4) * L[j];
+        carry = x[j] >> 8;
+        x[j] &= 255;
+    }
+
+    for j in 0..32 {
+        x[j] -= carry * L[j];
+    }
+
+    let mut result = [0u8; 32];
+    for i in 0..32 {
+        x[i + 1] += x[i] >> 8;
+        result[i] = (x[i] & 255) as u8;
+    }
+
+    // the limbs hold nonces and the secret scalar while signing
+    zeroize_words(x);
+    result
+}
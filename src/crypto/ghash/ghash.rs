@@ -0,0 +1,278 @@
+// GHASH, the universal hash function from GCM, usable on its own as a one-time authenticator.
+//
+// Blocks are treated as elements of GF(2^128) defined by x^128 + x^7 + x^2 + x + 1, using
+// the "reflected" bit order from NIST SP 800-38D, where the first bit of the block is the
+// coefficient of x^0. Each block is XORed into the accumulator, which is then multiplied by
+// the hash key H.
+//
+// Like Poly1305, the 32 byte key is split in two: H, and a mask that is XORed onto the result
+// to give the tag. A key must only be used for one message, since tags from two messages under
+// the same H reveal it.
+//
+// Zero padding alone would give a message and the same message with zero bytes appended the
+// same tag, so `finalize` hashes a final block holding the message length in bits. That is
+// the layout of GMAC, which is GCM with the whole message as associated data and nothing to
+// encrypt, so with the encryption of GCM's first counter block as the mask the tag is GMAC's.
+// GCM itself hashes its own length block through `update_padded` and `finalize_raw`.
+
+// CPUs with carry-less multiplication instructions (PCLMULQDQ or PMULL) multiply with those
+// instead of the portable loop.
+
+use super::hardware::CarrylessMultiply;
+use crate::utils::constant_time;
+use crate::utils::secret::Secret;
+
+pub const KEY_LENGTH: usize = 32;
+pub const TAG_LENGTH: usize = 16;
+
+// The reduction polynomial x^7 + x^2 + x + 1, in the reflected bit order.
+const R: u128 = 0xe1 << 120;
+
+pub struct Ghash {
+    h: Secret<u128>,
+    mask: Secret<u128>,
+    accumulator: u128,
+    // a partial block waiting for more data
+    buffer: [u8; 16],
+    buffer_length: usize,
+    // the number of bytes passed to `update`, for the length block
+    length: u64,
+    hardware: Option<CarrylessMultiply>
+}
+
+impl Ghash {
+    pub fn new(key: &[u8; KEY_LENGTH]) -> Self {
+        Self {
+            h: Secret::new(block_to_u128(&key[..16])),
+            mask: Secret::new(block_to_u128(&key[16..])),
+            accumulator: 0,
+            buffer: [0u8; 16],
+            buffer_length: 0,
+            length: 0,
+            hardware: CarrylessMultiply::detect()
+        }
+    }
+
+    pub fn update(&mut self, mut data: &[u8]) {
+        self.length += data.len() as u64;
+
+        // top up a partial block left over from a previous call first
+        if self.buffer_length > 0 {
+            let needed = (16 - self.buffer_length).min(data.len());
+            self.buffer[self.buffer_length..self.buffer_length + needed].copy_from_slice(&data[..needed]);
+            self.buffer_length += needed;
+            data = &data[needed..];
+
+            if self.buffer_length < 16 {
+                return;
+            }
+
+            let block = self.buffer;
+            self.process_block(&block);
+            self.buffer_length = 0;
+        }
+
+        let mut blocks = data.chunks_exact(16);
+        for block in &mut blocks {
+            self.process_block(block);
+        }
+
+        let remainder = blocks.remainder();
+        self.buffer[..remainder.len()].copy_from_slice(remainder);
+        self.buffer_length = remainder.len();
+    }
+
+    // Like `update`, but zero pads the data to a whole number of blocks, so whatever comes next
+    // starts a new block. GCM pads the associated data and the ciphertext this way.
+    pub(crate) fn update_padded(&mut self, data: &[u8]) {
+        self.update(data);
+        self.pad();
+    }
+
+    pub fn finalize(mut self) -> [u8; TAG_LENGTH] {
+        self.pad();
+
+        let mut length_block = [0u8; 16];
+        length_block[..8].copy_from_slice(&(self.length * 8).to_be_bytes());
+        self.process_block(&length_block);
+
+        self.finalize_raw()
+    }
+
+    // The tag without the length block, for GCM, which hashes its own. Only safe when the
+    // data already encodes its length.
+    pub(crate) fn finalize_raw(mut self) -> [u8; TAG_LENGTH] {
+        self.pad();
+        (self.accumulator ^ *self.mask.expose()).to_be_bytes()
+    }
+
+    // Checks a received tag against the one computed over the data passed to `update`, without
+    // revealing how much of it matched.
+    pub fn verify(self, tag: &[u8]) -> bool {
+        constant_time::compare(&self.finalize(), tag)
+    }
+
+    fn pad(&mut self) {
+        if self.buffer_length > 0 {
+            let block = self.buffer;
+            self.process_block(&block[..self.buffer_length]);
+            self.buffer_length = 0;
+        }
+    }
+
+    fn process_block(&mut self, block: &[u8]) {
+        let x = self.accumulator ^ block_to_u128(block);
+        self.accumulator = match &self.hardware {
+            Some(hardware) => hardware.gf_multiply(x, *self.h.expose()),
+            None => gf_multiply(x, *self.h.expose())
+        };
+    }
+}
+
+// Converts up to 16 bytes into a field element, padding short blocks with zeros.
+fn block_to_u128(block: &[u8]) -> u128 {
+    let mut bytes = [0u8; 16];
+    bytes[..block.len()].copy_from_slice(block);
+    u128::from_be_bytes(bytes)
+}
+
+// Multiplication in GF(2^128), following algorithm 1 of NIST SP 800-38D.
+//
+// The bits of x are used to build masks instead of branches, so the running time does not
+// depend on the hash key or the data being authenticated.
+fn gf_multiply(x: u128, y: u128) -> u128 {
+    let mut z = 0u128;
+    let mut v = y;
+
+    for i in 0..128 {
+        let bit = (x >> (127 - i)) & 1;
+        z ^= v & 0u128.wrapping_sub(bit);
+
+        let lsb = v & 1;
+        v = (v >> 1) ^ (R & 0u128.wrapping_sub(lsb));
+    }
+
+    z
+}
+
+#[cfg(test)]
+use crate::utils::formatting::hex_to_bytes;
+
+#[test]
+fn hardware_comparison_test() {
+    // without the instructions there is nothing to compare against
+    let Some(hardware) = CarrylessMultiply::detect() else {
+        return;
+    };
+
+    let random_u128 = || u128::from_le_bytes(crate::utils::random::random_u8_32()[..16].try_into().unwrap());
+    for _ in 0..1000 {
+        let (x, y) = (random_u128(), random_u128());
+        assert_eq!(hardware.gf_multiply(x, y), gf_multiply(x, y));
+    }
+
+    for (x, y) in [(0, 0), (1 << 127, 1 << 127), (u128::MAX, u128::MAX), (1, 1), (u128::MAX, 1)] {
+        assert_eq!(hardware.gf_multiply(x, y), gf_multiply(x, y));
+    }
+}
+
+#[test]
+fn ghash_test() {
+    // Test cases 2 and 4 from the original GCM specification (McGrew & Viega), which list H,
+    // the associated data A, the ciphertext C and GHASH(H, A, C). With a zero mask the raw tag
+    // is GHASH itself, and with the encrypted counter block E(K, Y0) as the mask it is the GCM tag.
+    let test_cases = [
+        ("66e94bd4ef8a2c3b884cfa59ca342b2e",
+         "",
+         "0388dace60b6a392f328c2b971b2fe78",
+         "f38cbb1ad69223dcc3457ae5b6b0f885",
+         "58e2fccefa7e3061367f1d57a4e7455a",
+         "ab6e47d42cec13bdf53a67b21257bddf"),
+        ("b83b533708bf535d0aa6e52980d53b78",
+         "feedfacedeadbeeffeedfacedeadbeefabaddad2",
+         "42831ec2217774244b7221b784d0d49ce3aa212f2c02a4e035c17e2329aca12e\
+          21d514b25466931c7d8f6a5aac84aa051ba30b396a0aac973d58e091",
+         "698e57f70e6ecc7fd9463b7260a9ae5f",
+         "3247184b3c4f69a44dbcd22887bbb418",
+         "5bc94fbc3221a5db94fae95ae7121a47"),
+    ];
+
+    for (h, associated_data, ciphertext, hash, mask, tag) in test_cases {
+        let associated_data = hex_to_bytes(associated_data).unwrap();
+        let ciphertext = hex_to_bytes(ciphertext).unwrap();
+        let mut length_block = [0u8; 16];
+        length_block[..8].copy_from_slice(&(associated_data.len() as u64 * 8).to_be_bytes());
+        length_block[8..].copy_from_slice(&(ciphertext.len() as u64 * 8).to_be_bytes());
+
+        for (mask, expected) in [("00000000000000000000000000000000", hash), (mask, tag)] {
+            let key: [u8; KEY_LENGTH] = hex_to_bytes(&format!("{h}{mask}")).unwrap().try_into().unwrap();
+            let expected = hex_to_bytes(expected).unwrap();
+
+            let mut ghash = Ghash::new(&key);
+            ghash.update_padded(&associated_data);
+            ghash.update_padded(&ciphertext);
+            ghash.update(&length_block);
+            assert_eq!(ghash.finalize_raw().to_vec(), expected);
+
+            // the ciphertext fed in small pieces, which only get padded at the end
+            let mut ghash = Ghash::new(&key);
+            ghash.update_padded(&associated_data);
+            for chunk in ciphertext.chunks(5) {
+                ghash.update(chunk);
+            }
+            ghash.update_padded(&[]);
+            ghash.update(&length_block);
+            assert_eq!(ghash.finalize_raw().to_vec(), expected);
+        }
+    }
+}
+
+#[test]
+fn zero_extension_test() {
+    // Without the length block both messages are one zero padded block, and share a tag.
+    let key = crate::utils::random::random_u8_32();
+    let raw_tag = |message: &[u8]| {
+        let mut ghash = Ghash::new(&key);
+        ghash.update(message);
+        ghash.finalize_raw()
+    };
+    assert_eq!(raw_tag(b"pay alice 10"), raw_tag(b"pay alice 10\0\0"));
+
+    let mut ghash = Ghash::new(&key);
+    ghash.update(b"pay alice 10");
+    let tag = ghash.finalize();
+
+    // zeros within the last block, zeros that fill it, and zeros that run into another one
+    for zeros in [2, 4, 20] {
+        let mut ghash = Ghash::new(&key);
+        ghash.update(b"pay alice 10");
+        ghash.update(&vec![0u8; zeros]);
+        assert!(!ghash.verify(&tag));
+    }
+}
+
+#[cfg(test)]
+use crate::utils::test_vectors::wycheproof;
+
+#[test]
+fn wycheproof_test() {
+    // GMAC with AES, where H is the encryption of the zero block and the mask the encryption of
+    // the first counter block. Other nonce sizes derive that block with GHASH, which GCM here
+    // doesn't support either.
+    for group in wycheproof::load("aes_gmac_test.json").iter().filter(|group| group.usize("ivSize") == 96) {
+        for test in &group.tests {
+            let aes = crate::crypto::aes::AES::new(&test.hex("key")).unwrap();
+            let mut counter_block = [0u8; 16];
+            counter_block[..12].copy_from_slice(&test.hex("iv"));
+            counter_block[15] = 1;
+
+            let mut key = Secret::new([0u8; KEY_LENGTH]);
+            key.expose_mut()[..16].copy_from_slice(&aes.encrypt_block(&[0u8; 16]));
+            key.expose_mut()[16..].copy_from_slice(&aes.encrypt_block(&counter_block));
+
+            let mut ghash = Ghash::new(key.expose());
+            ghash.update(&test.hex("msg"));
+            test.check(ghash.verify(&test.hex("tag")));
+        }
+    }
+}
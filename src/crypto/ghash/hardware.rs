@@ -0,0 +1,84 @@
+// GF(2^128) multiplication using the CPU's carry-less multiply instruction, PCLMULQDQ on x86_64
+// and PMULL on aarch64. Detection and the reason the intrinsics are safe to call work the same
+// way as for the AES instructions in aes/hardware.rs.
+
+#[cfg(target_arch = "x86_64")]
+use std::arch::x86_64::*;
+#[cfg(target_arch = "aarch64")]
+use std::arch::aarch64::*;
+
+// Hardware carry-less multiplication, for GHASH.
+#[derive(Clone, Copy)]
+pub struct CarrylessMultiply {
+    _detected: ()
+}
+
+impl CarrylessMultiply {
+    pub fn detect() -> Option<Self> {
+        #[cfg(target_arch = "x86_64")]
+        if is_x86_feature_detected!("pclmulqdq") && is_x86_feature_detected!("sse2") {
+            return Some(Self { _detected: () });
+        }
+
+        // PMULL is part of the same "aes" feature as the AES instructions
+        #[cfg(target_arch = "aarch64")]
+        if std::arch::is_aarch64_feature_detected!("aes") {
+            return Some(Self { _detected: () });
+        }
+
+        None
+    }
+
+    // Multiplication in GF(2^128) with the same bit ordering as the portable GHASH, where the
+    // most significant bit of the u128 is the coefficient of x^0.
+    pub fn gf_multiply(&self, x: u128, y: u128) -> u128 {
+        let (x_high, x_low) = ((x >> 64) as u64, x as u64);
+        let (y_high, y_low) = ((y >> 64) as u64, y as u64);
+
+        // schoolbook multiplication of the 64 bit halves into a 256 bit product
+        let high = self.multiply_64(x_high, y_high);
+        let low = self.multiply_64(x_low, y_low);
+        let middle = self.multiply_64(x_high, y_low) ^ self.multiply_64(x_low, y_high);
+        let mut product_high = high ^ (middle >> 64);
+        let mut product_low = low ^ (middle << 64);
+
+        // The product of two reflected values is one bit short of a reflected 256 bit value,
+        // so shift it up. Then product_high holds x^0 to x^127, and product_low x^128 to x^255.
+        product_high = (product_high << 1) | (product_low >> 127);
+        product_low <<= 1;
+
+        // x^128 = x^7 + x^2 + x + 1. Multiplying by x^k is a right shift by k, and the bits
+        // shifted out are above x^127 again, so they are folded a second time.
+        let fold = |value: u128| value ^ (value >> 1) ^ (value >> 2) ^ (value >> 7);
+        let overflow = (product_low << 127) ^ (product_low << 126) ^ (product_low << 121);
+
+        product_high ^ fold(product_low) ^ fold(overflow)
+    }
+
+    fn multiply_64(&self, a: u64, b: u64) -> u128 {
+        #[cfg(target_arch = "x86_64")]
+        return unsafe { multiply_64_x86(a, b) };
+
+        #[cfg(target_arch = "aarch64")]
+        return unsafe { multiply_64_aarch64(a, b) };
+
+        #[cfg(not(any(target_arch = "x86_64", target_arch = "aarch64")))]
+        unreachable!("carry-less multiplication is never detected on this architecture")
+    }
+}
+
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "pclmulqdq,sse2")]
+fn multiply_64_x86(a: u64, b: u64) -> u128 {
+    let product = _mm_clmulepi64_si128(_mm_set_epi64x(0, a as i64), _mm_set_epi64x(0, b as i64), 0x00);
+
+    let low = _mm_cvtsi128_si64(product) as u64 as u128;
+    let high = _mm_cvtsi128_si64(_mm_unpackhi_epi64(product, product)) as u64 as u128;
+    (high << 64) | low
+}
+
+#[cfg(target_arch = "aarch64")]
+#[target_feature(enable = "aes,neon")]
+fn multiply_64_aarch64(a: u64, b: u64) -> u128 {
+    vmull_p64(a, b)
+}
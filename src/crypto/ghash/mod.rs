@@ -0,0 +1,4 @@
+mod ghash;
+mod hardware;
+
+pub use ghash::{Ghash, KEY_LENGTH};
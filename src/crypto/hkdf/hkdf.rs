@@ -0,0 +1,133 @@
+// HMAC-based Extract-and-Expand Key Derivation Function (HKDF), as specified in RFC 5869,
+// using HMAC-SHA256.
+//
+// Extract concentrates the entropy of some input keying material (e.g. a Diffie-Hellman shared
+// secret) into a fixed length pseudorandom key. Expand then stretches that key into as many
+// output bytes as needed, with the info parameter binding the output to a specific purpose.
+
+use std::fmt::{Display, Formatter};
+use crate::crypto::sha256::hmac_sha256::HmacSha256;
+use crate::utils::secret::Secret;
+
+pub const HASH_LENGTH: usize = 32;
+
+#[derive(Debug, PartialEq)]
+pub enum HkdfError {
+    // Expand can produce at most 255 blocks, 255 * HASH_LENGTH bytes.
+    OutputTooLong(usize),
+}
+
+impl Display for HkdfError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            HkdfError::OutputTooLong(length) => write!(f, "HKDF output of {} bytes is more than 255 times the hash length", length),
+        }
+    }
+}
+
+// HKDF-Extract(salt, IKM) = HMAC-Hash(salt, IKM)
+// An empty salt is treated as a string of HASH_LENGTH zeros, which HMAC does implicitly.
+pub fn extract(salt: &[u8], input_key_material: &[u8]) -> Secret<[u8; HASH_LENGTH]> {
+    let mut hmac = HmacSha256::new(salt);
+    hmac.update(input_key_material);
+    Secret::new(hmac.finalize())
+}
+
+// HKDF-Expand(PRK, info, L)
+// T(0) = empty, T(i) = HMAC-Hash(PRK, T(i - 1) | info | i), output is the first L bytes of T(1) | T(2) | ...
+pub fn expand(pseudorandom_key: &[u8], info: &[u8], length: usize) -> Result<Secret<Vec<u8>>, HkdfError> {
+    // the block counter is a single byte
+    if length > 255 * HASH_LENGTH {
+        return Err(HkdfError::OutputTooLong(length));
+    }
+
+    let keyed_hmac = HmacSha256::new(pseudorandom_key);
+    // allocated up front, so the output is never copied by a reallocation and left behind
+    let mut output = Secret::new(Vec::with_capacity(length));
+    let mut previous_block: Option<Secret<[u8; HASH_LENGTH]>> = None;
+    let mut counter = 1u8;
+
+    while output.expose().len() < length {
+        let mut hmac = keyed_hmac.clone();
+        if let Some(previous_block) = &previous_block {
+            hmac.update(previous_block.expose());
+        }
+        hmac.update(info);
+        hmac.update(&[counter]);
+
+        let block = Secret::new(hmac.finalize());
+        let needed = (length - output.expose().len()).min(HASH_LENGTH);
+        output.expose_mut().extend_from_slice(&block.expose()[..needed]);
+
+        previous_block = Some(block);
+        counter = counter.wrapping_add(1);
+    }
+
+    Ok(output)
+}
+
+#[cfg(test)]
+use crate::utils::formatting::hex_to_bytes;
+
+#[test]
+fn rfc5869_test() {
+    // RFC 5869 appendix A, test cases 1 to 3
+    let test_cases = [
+        // (IKM, salt, info, PRK, OKM)
+        ("0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b",
+         "000102030405060708090a0b0c",
+         "f0f1f2f3f4f5f6f7f8f9",
+         "077709362c2e32df0ddc3f0dc47bba6390b6c73bb50f9c3122ec844ad7c2b3e5",
+         "3cb25f25faacd57a90434f64d0362f2a2d2d0a90cf1a5a4c5db02d56ecc4c5bf34007208d5b887185865"),
+        ("000102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f\
+          202122232425262728292a2b2c2d2e2f303132333435363738393a3b3c3d3e3f\
+          404142434445464748494a4b4c4d4e4f",
+         "606162636465666768696a6b6c6d6e6f707172737475767778797a7b7c7d7e7f\
+          808182838485868788898a8b8c8d8e8f909192939495969798999a9b9c9d9e9f\
+          a0a1a2a3a4a5a6a7a8a9aaabacadaeaf",
+         "b0b1b2b3b4b5b6b7b8b9babbbcbdbebfc0c1c2c3c4c5c6c7c8c9cacbcccdcecf\
+          d0d1d2d3d4d5d6d7d8d9dadbdcdddedfe0e1e2e3e4e5e6e7e8e9eaebecedeeef\
+          f0f1f2f3f4f5f6f7f8f9fafbfcfdfeff",
+         "06a6b88c5853361a06104c9ceb35b45cef760014904671014a193f40c15fc244",
+         "b11e398dc80327a1c8e7f78c596a49344f012eda2d4efad8a050cc4c19afa97c\
+          59045a99cac7827271cb41c65e590e09da3275600c2f09b8367793a9aca3db71\
+          cc30c58179ec3e87c14c01d5c1f3434f1d87"),
+        ("0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b0b",
+         "",
+         "",
+         "19ef24a32c717b167f33a91d6f648bdf96596776afdb6377ac434c1c293ccb04",
+         "8da4e775a563c18f715f802a063c5a31b8a11f5c5ee1879ec3454e5f3c738d2d9d201395faa4b61a96c8"),
+    ];
+
+    for (input_key_material, salt, info, pseudorandom_key, output_key_material) in test_cases {
+        let output_key_material = hex_to_bytes(output_key_material).unwrap();
+
+        let prk = extract(&hex_to_bytes(salt).unwrap(), &hex_to_bytes(input_key_material).unwrap());
+        assert_eq!(prk.expose().to_vec(), hex_to_bytes(pseudorandom_key).unwrap());
+
+        let okm = expand(prk.expose(), &hex_to_bytes(info).unwrap(), output_key_material.len()).unwrap();
+        assert_eq!(okm.expose(), &output_key_material);
+    }
+}
+
+#[test]
+fn expand_length_limit_test() {
+    let prk = [0x01; HASH_LENGTH];
+
+    assert_eq!(expand(&prk, b"", 255 * HASH_LENGTH).unwrap().expose().len(), 255 * HASH_LENGTH);
+    assert_eq!(expand(&prk, b"", 255 * HASH_LENGTH + 1).err(), Some(HkdfError::OutputTooLong(255 * HASH_LENGTH + 1)));
+}
+
+#[cfg(test)]
+use crate::utils::test_vectors::wycheproof;
+
+#[test]
+fn wycheproof_test() {
+    for group in &wycheproof::load("hkdf_sha256_test.json") {
+        for test in &group.tests {
+            let prk = extract(&test.hex("salt"), &test.hex("ikm"));
+            let okm = expand(prk.expose(), &test.hex("info"), test.usize("size"));
+            test.check(okm.is_ok_and(|okm| okm.expose() == &test.hex("okm")));
+        }
+    }
+}
@@ -1,4 +1,17 @@
 pub mod aes;
 pub mod sha256;
+pub mod sha512;
+pub mod hkdf;
+pub mod ed25519;
 pub mod base64;
-pub mod x25519;
\ No newline at end of file
+pub mod x25519;
+pub mod chacha20poly1305;
+pub mod poly1305;
+pub mod ghash;
+pub mod p256;
+pub mod der;
+pub mod x509;
+pub mod pem;
+pub mod pbkdf2;
+pub mod blake2b;
+pub mod argon2;
@@ -0,0 +1,199 @@
+// Elliptic curve Diffie-Hellman over P-256 (secp256r1), as used by TLS 1.3 key shares.
+
+use super::error::P256Error;
+use super::point::{Point, UNCOMPRESSED_LENGTH};
+use super::scalar;
+use crate::utils::secret::Secret;
+
+pub struct KeyPair {
+    pub public: PublicKey,
+    pub private: PrivateKey
+}
+
+impl KeyPair {
+    pub fn generate() -> Self {
+        // rejection sampling, the chance of needing a second attempt is about 2^-32
+        loop {
+            if let Ok(private) = PrivateKey::from_bytes(&crate::utils::random::random_u8_32()) {
+                return Self::from_private(private);
+            }
+        }
+    }
+
+    pub fn from_private(private: PrivateKey) -> Self {
+        Self {
+            public: PublicKey::from_point(Point::base().scalar_multiply(private.value.expose())),
+            private
+        }
+    }
+}
+
+// A big-endian private scalar, wiped from memory when dropped.
+pub struct PrivateKey {
+    value: Secret<[u8; 32]>
+}
+
+impl PrivateKey {
+    pub fn from_bytes(bytes: &[u8; 32]) -> Result<Self, P256Error> {
+        if !scalar::is_valid(bytes) {
+            return Err(P256Error::InvalidPrivateKey);
+        }
+
+        Ok(Self {
+            value: Secret::new(*bytes)
+        })
+    }
+
+    pub fn as_bytes(&self) -> &[u8; 32] {
+        self.value.expose()
+    }
+
+    // Computes the shared secret, the big-endian x coordinate of [d]Q (SEC 1 section 3.3.1).
+    pub fn diffie_hellman(&self, public: &PublicKey) -> Secret<[u8; 32]> {
+        let (x, _) = public.point.scalar_multiply(self.value.expose()).to_affine()
+            .expect("the curve has prime order, so a valid scalar never gives the point at infinity");
+        Secret::new(x.to_bytes())
+    }
+}
+
+pub struct PublicKey {
+    point: Point,
+    encoded: [u8; UNCOMPRESSED_LENGTH]
+}
+
+impl PublicKey {
+    // Parses and validates an uncompressed point, 0x04 || x || y.
+    pub fn from_uncompressed(bytes: &[u8]) -> Result<Self, P256Error> {
+        let point = Point::from_uncompressed(bytes).ok_or(P256Error::InvalidPublicKey)?;
+        Ok(Self::from_point(point))
+    }
+
+    fn from_point(point: Point) -> Self {
+        Self {
+            encoded: point.to_uncompressed().expect("public keys are never the point at infinity"),
+            point
+        }
+    }
+
+    pub(super) fn point(&self) -> &Point {
+        &self.point
+    }
+
+    pub fn as_uncompressed(&self) -> &[u8; UNCOMPRESSED_LENGTH] {
+        &self.encoded
+    }
+
+    pub fn to_vec(&self) -> Vec<u8> {
+        self.encoded.to_vec()
+    }
+}
+
+#[cfg(test)]
+use crate::utils::formatting::hex_to_bytes;
+
+#[test]
+fn ecdh_test() {
+    // NIST CAVS ECC CDH primitive test vectors for P-256, COUNT = 0 and 1
+    // (peer public x, peer public y, private key, own public x, own public y, shared secret)
+    let test_cases = [
+        ("700c48f77f56584c5cc632ca65640db91b6bacce3a4df6b42ce7cc838833d287",
+         "db71e509e3fd9b060ddb20ba5c51dcc5948d46fbf640dfe0441782cab85fa4ac",
+         "7d7dc5f71eb29ddaf80d6214632eeae03d9058af1fb6d22ed80badb62bc1a534",
+         "ead218590119e8876b29146ff89ca61770c4edbbf97d38ce385ed281d8a6b230",
+         "28af61281fd35e2fa7002523acc85a429cb06ee6648325389f59edfce1405141",
+         "46fc62106420ff012e54a434fbdd2d25ccc5852060561e68040dd7778997bd7b"),
+        ("809f04289c64348c01515eb03d5ce7ac1a8cb9498f5caa50197e58d43a86a7ae",
+         "b29d84e811197f25eba8f5194092cb6ff440e26d4421011372461f579271cda3",
+         "38f65d6dce47676044d58ce5139582d568f64bb16098d179dbab07741dd5caf5",
+         "119f2f047902782ab0c9e27a54aff5eb9b964829ca99c06b02ddba95b0a3f6d0",
+         "8f52b726664cac366fc98ac7a012b2682cbd962e5acb544671d41b9445704d1d",
+         "057d636096cb80b67a8c038c890e887d1adfa4195e9b3ce241c8a778c59cda67"),
+    ];
+
+    for (peer_x, peer_y, private, own_x, own_y, shared) in test_cases {
+        let peer = PublicKey::from_uncompressed(&hex_to_bytes(&format!("04{}{}", peer_x, peer_y)).unwrap()).unwrap();
+        let private = PrivateKey::from_bytes(&hex_to_bytes(private).unwrap().try_into().unwrap()).unwrap();
+        let key_pair = KeyPair::from_private(private);
+
+        assert_eq!(key_pair.public.to_vec(), hex_to_bytes(&format!("04{}{}", own_x, own_y)).unwrap());
+        assert_eq!(key_pair.private.diffie_hellman(&peer).expose().to_vec(), hex_to_bytes(shared).unwrap());
+    }
+}
+
+#[test]
+fn key_agreement_test() {
+    let alice = KeyPair::generate();
+    let bob = KeyPair::generate();
+
+    assert!(alice.private.diffie_hellman(&bob.public).ct_eq(&bob.private.diffie_hellman(&alice.public)));
+}
+
+#[test]
+fn invalid_public_key_test() {
+    let valid = hex_to_bytes("04\
+        700c48f77f56584c5cc632ca65640db91b6bacce3a4df6b42ce7cc838833d287\
+        db71e509e3fd9b060ddb20ba5c51dcc5948d46fbf640dfe0441782cab85fa4ac").unwrap();
+    assert!(PublicKey::from_uncompressed(&valid).is_ok());
+
+    // wrong length, compressed form and the point at infinity
+    assert_eq!(PublicKey::from_uncompressed(&valid[..64]).err(), Some(P256Error::InvalidPublicKey));
+    let mut compressed = valid[..33].to_vec();
+    compressed[0] = 0x02;
+    assert_eq!(PublicKey::from_uncompressed(&compressed).err(), Some(P256Error::InvalidPublicKey));
+    assert_eq!(PublicKey::from_uncompressed(&[0x00]).err(), Some(P256Error::InvalidPublicKey));
+
+    // not on the curve
+    let mut off_curve = valid.clone();
+    off_curve[64] ^= 1;
+    assert_eq!(PublicKey::from_uncompressed(&off_curve).err(), Some(P256Error::InvalidPublicKey));
+
+    // x coordinate not below p
+    let mut non_canonical = valid.clone();
+    non_canonical[1..33].copy_from_slice(&[0xff; 32]);
+    assert_eq!(PublicKey::from_uncompressed(&non_canonical).err(), Some(P256Error::InvalidPublicKey));
+}
+
+#[test]
+fn invalid_private_key_test() {
+    let order = hex_to_bytes("ffffffff00000000ffffffffffffffffbce6faada7179e84f3b9cac2fc632551").unwrap();
+    let mut order_minus_one: [u8; 32] = order.clone().try_into().unwrap();
+    order_minus_one[31] -= 1;
+
+    assert_eq!(PrivateKey::from_bytes(&[0u8; 32]).err(), Some(P256Error::InvalidPrivateKey));
+    assert_eq!(PrivateKey::from_bytes(&order.try_into().unwrap()).err(), Some(P256Error::InvalidPrivateKey));
+    assert!(PrivateKey::from_bytes(&order_minus_one).is_ok());
+}
+
+#[cfg(test)]
+use crate::utils::test_vectors::wycheproof;
+
+// Wycheproof gives private keys as DER style integers, which can be shorter than 32 bytes or
+// have a leading zero byte.
+#[cfg(test)]
+fn wycheproof_private_key(integer: &[u8]) -> Option<PrivateKey> {
+    let start = integer.iter().position(|&byte| byte != 0).unwrap_or(integer.len());
+    let magnitude = &integer[start..];
+    if magnitude.len() > 32 {
+        return None;
+    }
+
+    let mut bytes = [0u8; 32];
+    bytes[32 - magnitude.len()..].copy_from_slice(magnitude);
+    PrivateKey::from_bytes(&bytes).ok()
+}
+
+#[test]
+fn wycheproof_test() {
+    // compressed points are "acceptable" there, and not supported here
+    for group in &wycheproof::load("ecdh_secp256r1_ecpoint_test.json") {
+        for test in &group.tests {
+            let Some(private) = wycheproof_private_key(&test.hex("private")) else {
+                test.check(false);
+                continue;
+            };
+            let shared = PublicKey::from_uncompressed(&test.hex("public"))
+                .map(|public| private.diffie_hellman(&public));
+            test.check(shared.is_ok_and(|shared| shared.expose().to_vec() == test.hex("shared")));
+        }
+    }
+}
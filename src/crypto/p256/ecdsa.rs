@@ -0,0 +1,397 @@
+// ECDSA over P-256 with SHA-256 (FIPS 186-4 section 6), with deterministic nonces from
+// RFC 6979 so signing doesn't depend on the quality of the random number generator.
+
+use super::ecdh::{PrivateKey, PublicKey};
+use super::error::P256Error;
+use super::point::Point;
+use super::scalar::Scalar;
+use crate::crypto::sha256::hmac_sha256::HmacSha256;
+use crate::crypto::sha256::sha256;
+use crate::utils::secret::Secret;
+
+// Length of the fixed-size r || s encoding, as used by JWS (ES256)
+pub const SIGNATURE_LENGTH: usize = 64;
+
+#[derive(Debug, PartialEq)]
+pub struct Signature {
+    r: [u8; 32],
+    s: [u8; 32]
+}
+
+impl Signature {
+    // Parses the fixed-size r || s encoding.
+    pub fn from_bytes(bytes: &[u8; SIGNATURE_LENGTH]) -> Self {
+        let mut r = [0u8; 32];
+        let mut s = [0u8; 32];
+        r.copy_from_slice(&bytes[..32]);
+        s.copy_from_slice(&bytes[32..]);
+
+        Self { r, s }
+    }
+
+    pub fn to_bytes(&self) -> [u8; SIGNATURE_LENGTH] {
+        let mut result = [0u8; SIGNATURE_LENGTH];
+        result[..32].copy_from_slice(&self.r);
+        result[32..].copy_from_slice(&self.s);
+        result
+    }
+
+    // Parses the DER encoding SEQUENCE { r INTEGER, s INTEGER } used by TLS and X.509.
+    // Only the minimal encoding is accepted.
+    pub fn from_der(bytes: &[u8]) -> Result<Self, P256Error> {
+        // the whole signature is at most 72 bytes, so every length uses the short form
+        if bytes.len() < 2 || bytes[0] != 0x30 || bytes[1] as usize != bytes.len() - 2 {
+            return Err(P256Error::MalformedSignature);
+        }
+
+        let (r, rest) = decode_der_integer(&bytes[2..])?;
+        let (s, rest) = decode_der_integer(rest)?;
+        if !rest.is_empty() {
+            return Err(P256Error::MalformedSignature);
+        }
+
+        Ok(Self { r, s })
+    }
+
+    pub fn to_der(&self) -> Vec<u8> {
+        let r = encode_der_integer(&self.r);
+        let s = encode_der_integer(&self.s);
+
+        let mut result = vec![0x30, (r.len() + s.len()) as u8];
+        result.extend_from_slice(&r);
+        result.extend_from_slice(&s);
+        result
+    }
+}
+
+// Reads one INTEGER from the front of the input as a 32 byte big-endian value, returning it
+// with the remaining input.
+fn decode_der_integer(bytes: &[u8]) -> Result<([u8; 32], &[u8]), P256Error> {
+    if bytes.len() < 2 || bytes[0] != 0x02 {
+        return Err(P256Error::MalformedSignature);
+    }
+
+    let length = bytes[1] as usize;
+    if length == 0 || length > 33 || bytes.len() < 2 + length {
+        return Err(P256Error::MalformedSignature);
+    }
+
+    let mut value = &bytes[2..2 + length];
+    // negative numbers aren't allowed
+    if value[0] & 0x80 != 0 {
+        return Err(P256Error::MalformedSignature);
+    }
+
+    // a leading zero is only allowed when needed to keep the number positive
+    if value[0] == 0 && (value.len() == 1 || value[1] & 0x80 == 0) {
+        return Err(P256Error::MalformedSignature);
+    }
+
+    if value[0] == 0 {
+        value = &value[1..];
+    }
+
+    if value.len() > 32 {
+        return Err(P256Error::MalformedSignature);
+    }
+
+    let mut result = [0u8; 32];
+    result[32 - value.len()..].copy_from_slice(value);
+    Ok((result, &bytes[2 + length..]))
+}
+
+fn encode_der_integer(value: &[u8; 32]) -> Vec<u8> {
+    let leading_zeros = value.iter().take_while(|&&b| b == 0).count().min(31);
+    let value = &value[leading_zeros..];
+
+    let mut result = vec![0x02];
+    if value[0] & 0x80 != 0 {
+        result.push(value.len() as u8 + 1);
+        result.push(0x00);
+    } else {
+        result.push(value.len() as u8);
+    }
+
+    result.extend_from_slice(value);
+    result
+}
+
+pub struct SigningKey {
+    private: PrivateKey,
+    verifying_key: VerifyingKey
+}
+
+impl SigningKey {
+    pub fn generate() -> Self {
+        let key_pair = super::ecdh::KeyPair::generate();
+        Self {
+            private: key_pair.private,
+            verifying_key: VerifyingKey {
+                public: key_pair.public
+            }
+        }
+    }
+
+    // Creates the key from a big-endian private scalar.
+    pub fn from_bytes(bytes: &[u8; 32]) -> Result<Self, P256Error> {
+        let key_pair = super::ecdh::KeyPair::from_private(PrivateKey::from_bytes(bytes)?);
+        Ok(Self {
+            private: key_pair.private,
+            verifying_key: VerifyingKey {
+                public: key_pair.public
+            }
+        })
+    }
+
+    pub fn as_bytes(&self) -> &[u8; 32] {
+        self.private.as_bytes()
+    }
+
+    pub fn verifying_key(&self) -> &VerifyingKey {
+        &self.verifying_key
+    }
+
+    pub fn sign(&self, message: &[u8]) -> Signature {
+        let hash = sha256::hash(message);
+        let z = Scalar::from_bytes_reduced(&hash);
+        // the private key is already validated, so this can't fail
+        let d = Secret::new(Scalar::from_bytes(self.private.as_bytes()).unwrap());
+
+        let mut nonces = NonceGenerator::new(self.private.as_bytes(), &z.to_bytes());
+        loop {
+            let k_bytes = Secret::new(nonces.next());
+            let Some(k) = Scalar::from_bytes(k_bytes.expose()).map(Secret::new) else {
+                continue;
+            };
+
+            // r = x(kG) mod n
+            let (x, _) = Point::base().scalar_multiply(k_bytes.expose()).to_affine()
+                .expect("k is never zero, so kG is never the point at infinity");
+            let r = Scalar::from_bytes_reduced(&x.to_bytes());
+            if r.is_zero() {
+                continue;
+            }
+
+            // s = k^-1 (z + r d) mod n, where k^-1 and z + r d would each give away d
+            let k_inverse = Secret::new(k.expose().invert());
+            let z_plus_r_d = Secret::new(z.add(&r.multiply(d.expose())));
+            let s = k_inverse.expose().multiply(z_plus_r_d.expose());
+            if s.is_zero() {
+                continue;
+            }
+
+            return Signature {
+                r: r.to_bytes(),
+                s: s.to_bytes()
+            };
+        }
+    }
+}
+
+pub struct VerifyingKey {
+    public: PublicKey
+}
+
+impl VerifyingKey {
+    // Parses and validates an uncompressed point, 0x04 || x || y.
+    pub fn from_uncompressed(bytes: &[u8]) -> Result<Self, P256Error> {
+        Ok(Self {
+            public: PublicKey::from_uncompressed(bytes)?
+        })
+    }
+
+    pub fn as_uncompressed(&self) -> &[u8] {
+        self.public.as_uncompressed()
+    }
+
+    pub fn verify(&self, message: &[u8], signature: &Signature) -> bool {
+        // r and s must both be in 1..n-1
+        let (Some(r), Some(s)) = (Scalar::from_bytes(&signature.r), Scalar::from_bytes(&signature.s)) else {
+            return false;
+        };
+
+        let z = Scalar::from_bytes_reduced(&sha256::hash(message));
+
+        // R = (z s^-1) G + (r s^-1) Q, valid if x(R) mod n == r
+        let s_inverse = s.invert();
+        let u1 = z.multiply(&s_inverse);
+        let u2 = r.multiply(&s_inverse);
+        let point = Point::base().scalar_multiply(&u1.to_bytes())
+            .add(&self.public.point().scalar_multiply(&u2.to_bytes()));
+
+        match point.to_affine() {
+            Some((x, _)) => Scalar::from_bytes_reduced(&x.to_bytes()).to_bytes() == signature.r,
+            None => false
+        }
+    }
+}
+
+// The HMAC_DRBG based nonce generation from RFC 6979 section 3.2, with qlen = hlen = 256 bits
+// so every candidate is a single HMAC output.
+struct NonceGenerator {
+    k: Secret<[u8; 32]>,
+    v: Secret<[u8; 32]>,
+    first: bool
+}
+
+impl NonceGenerator {
+    fn new(private_key: &[u8; 32], hash: &[u8; 32]) -> Self {
+        let mut generator = Self {
+            k: Secret::new([0x00; 32]),
+            v: Secret::new([0x01; 32]),
+            first: true
+        };
+
+        for separator in [0x00, 0x01] {
+            let mut hmac = HmacSha256::new(generator.k.expose());
+            hmac.update(generator.v.expose());
+            hmac.update(&[separator]);
+            hmac.update(private_key);
+            hmac.update(hash);
+            *generator.k.expose_mut() = hmac.finalize();
+            *generator.v.expose_mut() = hmac_sha256(generator.k.expose(), generator.v.expose());
+        }
+
+        generator
+    }
+
+    fn next(&mut self) -> [u8; 32] {
+        // after a rejected candidate, K = HMAC_K(V || 0x00) and V = HMAC_K(V)
+        if !self.first {
+            let mut hmac = HmacSha256::new(self.k.expose());
+            hmac.update(self.v.expose());
+            hmac.update(&[0x00]);
+            *self.k.expose_mut() = hmac.finalize();
+            *self.v.expose_mut() = hmac_sha256(self.k.expose(), self.v.expose());
+        }
+        self.first = false;
+
+        *self.v.expose_mut() = hmac_sha256(self.k.expose(), self.v.expose());
+        *self.v.expose()
+    }
+}
+
+fn hmac_sha256(key: &[u8], data: &[u8]) -> [u8; 32] {
+    let mut hmac = HmacSha256::new(key);
+    hmac.update(data);
+    hmac.finalize()
+}
+
+#[cfg(test)]
+use crate::utils::formatting::hex_to_bytes;
+
+#[cfg(test)]
+fn rfc6979_key() -> SigningKey {
+    // RFC 6979 appendix A.2.5
+    let private = hex_to_bytes("c9afa9d845ba75166b5c215767b1d6934e50c3db36e89b127b8a622b120f6721").unwrap();
+    SigningKey::from_bytes(&private.try_into().unwrap()).unwrap()
+}
+
+#[test]
+fn rfc6979_test() {
+    let signing_key = rfc6979_key();
+    assert_eq!(signing_key.verifying_key().as_uncompressed(), hex_to_bytes("04\
+        60fed4ba255a9d31c961eb74c6356d68c049b8923b61fa6ce669622e60f29fb6\
+        7903fe1008b8bc99a41ae9e95628bc64f2f1b20c2d7e9f5177a3c294d4462299").unwrap());
+
+    let test_cases = [
+        ("sample",
+         "efd48b2aacb6a8fd1140dd9cd45e81d69d2c877b56aaf991c34d0ea84eaf3716\
+          f7cb1c942d657c41d436c7a1b6e29f65f3e900dbb9aff4064dc4ab2f843acda8"),
+        ("test",
+         "f1abb023518351cd71d881567b1ea663ed3efcf6c5132b354f28d3b0b7d38367\
+          019f4113742a2b14bd25926b49c649155f267e60d3814b4c0cc84250e46f0083"),
+    ];
+
+    for (message, expected_signature) in test_cases {
+        let signature = signing_key.sign(message.as_bytes());
+        assert_eq!(signature.to_bytes().to_vec(), hex_to_bytes(expected_signature).unwrap());
+        assert!(signing_key.verifying_key().verify(message.as_bytes(), &signature));
+    }
+}
+
+#[test]
+fn verify_rejection_test() {
+    let signing_key = rfc6979_key();
+    let verifying_key = signing_key.verifying_key();
+    let signature = signing_key.sign(b"sample");
+
+    assert!(!verifying_key.verify(b"samples", &signature));
+
+    let mut tampered = signature.to_bytes();
+    tampered[63] ^= 1;
+    assert!(!verifying_key.verify(b"sample", &Signature::from_bytes(&tampered)));
+
+    // r = 0 and s = n are outside the allowed range
+    let mut zero_r = signature.to_bytes();
+    zero_r[..32].copy_from_slice(&[0; 32]);
+    assert!(!verifying_key.verify(b"sample", &Signature::from_bytes(&zero_r)));
+
+    let mut order_s = signature.to_bytes();
+    order_s[32..].copy_from_slice(&hex_to_bytes("ffffffff00000000ffffffffffffffffbce6faada7179e84f3b9cac2fc632551").unwrap());
+    assert!(!verifying_key.verify(b"sample", &Signature::from_bytes(&order_s)));
+
+    // a different key
+    assert!(!SigningKey::generate().verifying_key().verify(b"sample", &signature));
+}
+
+#[test]
+fn der_test() {
+    let signing_key = rfc6979_key();
+
+    // r has its top bit set and needs a leading zero, s doesn't
+    let der = hex_to_bytes("3045022100\
+        f1abb023518351cd71d881567b1ea663ed3efcf6c5132b354f28d3b0b7d38367\
+        0220019f4113742a2b14bd25926b49c649155f267e60d3814b4c0cc84250e46f0083").unwrap();
+    let signature = signing_key.sign(b"test");
+    assert_eq!(signature.to_der(), der);
+    assert_eq!(Signature::from_der(&der).unwrap(), signature);
+
+    // small values are encoded without leading zeros
+    let mut small = [0u8; SIGNATURE_LENGTH];
+    small[31] = 0x01;
+    small[63] = 0x80;
+    let small = Signature::from_bytes(&small);
+    assert_eq!(small.to_der(), vec![0x30, 0x07, 0x02, 0x01, 0x01, 0x02, 0x02, 0x00, 0x80]);
+    assert_eq!(Signature::from_der(&small.to_der()).unwrap(), small);
+}
+
+#[test]
+fn der_invalid_test() {
+    let invalid_encodings: [&[u8]; 7] = [
+        // wrong outer length
+        &[0x30, 0x07, 0x02, 0x01, 0x01, 0x02, 0x01, 0x01],
+        // non-minimal leading zero
+        &[0x30, 0x07, 0x02, 0x02, 0x00, 0x01, 0x02, 0x01, 0x01],
+        // negative integer
+        &[0x30, 0x06, 0x02, 0x01, 0x81, 0x02, 0x01, 0x01],
+        // empty integer
+        &[0x30, 0x05, 0x02, 0x00, 0x02, 0x01, 0x01],
+        // wrong tag
+        &[0x31, 0x06, 0x02, 0x01, 0x01, 0x02, 0x01, 0x01],
+        // trailing data inside the sequence
+        &[0x30, 0x08, 0x02, 0x01, 0x01, 0x02, 0x01, 0x01, 0x00, 0x00],
+        // only one integer
+        &[0x30, 0x03, 0x02, 0x01, 0x01],
+    ];
+
+    for encoding in invalid_encodings {
+        assert_eq!(Signature::from_der(encoding).err(), Some(P256Error::MalformedSignature));
+    }
+}
+
+#[cfg(test)]
+use crate::utils::test_vectors::wycheproof;
+
+#[test]
+fn wycheproof_test() {
+    for group in &wycheproof::load("ecdsa_secp256r1_sha256_test.json") {
+        // every test in a group with an invalid key must be rejected
+        let verifying_key = VerifyingKey::from_uncompressed(&group.hex("publicKey.uncompressed")).ok();
+        for test in &group.tests {
+            let accepted = verifying_key.as_ref().is_some_and(|verifying_key| Signature::from_der(&test.hex("sig"))
+                .is_ok_and(|signature| verifying_key.verify(&test.hex("msg"), &signature)));
+            test.check(accepted);
+        }
+    }
+}
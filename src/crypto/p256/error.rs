@@ -0,0 +1,21 @@
+use std::fmt::{Display, Formatter};
+
+#[derive(Debug, PartialEq)]
+pub enum P256Error {
+    // The bytes are not an uncompressed encoding of a point on the curve.
+    InvalidPublicKey,
+    // The scalar is zero or not below the group order.
+    InvalidPrivateKey,
+    // The signature is not a DER sequence of two integers in the range 1..n-1.
+    MalformedSignature,
+}
+
+impl Display for P256Error {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            P256Error::InvalidPublicKey => write!(f, "invalid P-256 public key"),
+            P256Error::InvalidPrivateKey => write!(f, "invalid P-256 private key"),
+            P256Error::MalformedSignature => write!(f, "invalid ECDSA signature encoding"),
+        }
+    }
+}
@@ -0,0 +1,73 @@
+// Elements of the P-256 base field GF(p), p = 2^256 - 2^224 + 2^192 + 2^96 - 1.
+
+use super::montgomery::{self, Modulus};
+
+const P: Modulus = Modulus {
+    value: [0xffffffffffffffff, 0x00000000ffffffff, 0x0000000000000000, 0xffffffff00000001],
+    inverse: 0x0000000000000001,
+    r_squared: [0x0000000000000003, 0xfffffffbffffffff, 0xfffffffffffffffe, 0x00000004fffffffd]
+};
+
+#[derive(Clone, Copy)]
+pub struct FieldElement([u64; 4]);
+
+impl FieldElement {
+    pub const ZERO: Self = Self([0; 4]);
+
+    // 2^256 mod p, which is 1 in Montgomery form
+    pub const ONE: Self = Self([0x0000000000000001, 0xffffffff00000000, 0xffffffffffffffff, 0x00000000fffffffe]);
+
+    // Parses a big-endian field element, returning None if it is not below p.
+    pub fn from_bytes(bytes: &[u8; 32]) -> Option<Self> {
+        let (limbs, is_canonical) = montgomery::from_bytes(bytes, &P);
+        if is_canonical == 0 {
+            return None;
+        }
+
+        Some(Self(montgomery::to_montgomery(&limbs, &P)))
+    }
+
+    // Builds a field element from limbs that are already in Montgomery form.
+    pub const fn from_montgomery(limbs: [u64; 4]) -> Self {
+        Self(limbs)
+    }
+
+    pub fn to_bytes(self) -> [u8; 32] {
+        montgomery::to_bytes(&montgomery::from_montgomery(&self.0, &P))
+    }
+
+    pub fn add(&self, other: &Self) -> Self {
+        Self(montgomery::add(&self.0, &other.0, &P))
+    }
+
+    pub fn sub(&self, other: &Self) -> Self {
+        Self(montgomery::sub(&self.0, &other.0, &P))
+    }
+
+    pub fn multiply(&self, other: &Self) -> Self {
+        Self(montgomery::multiply(&self.0, &other.0, &P))
+    }
+
+    pub fn square(&self) -> Self {
+        self.multiply(self)
+    }
+
+    // The multiplicative inverse, or zero for zero.
+    pub fn invert(&self) -> Self {
+        Self(montgomery::invert(&self.0, &Self::ONE.0, &P))
+    }
+
+    // 1 if the element is zero, 0 otherwise
+    pub fn is_zero(&self) -> u64 {
+        montgomery::is_zero(&self.0)
+    }
+
+    pub fn equals(&self, other: &Self) -> u64 {
+        self.sub(other).is_zero()
+    }
+
+    // Returns a when choice is 0 and b when choice is 1, in constant time.
+    pub fn select(a: &Self, b: &Self, choice: u64) -> Self {
+        Self(montgomery::select(&a.0, &b.0, choice))
+    }
+}
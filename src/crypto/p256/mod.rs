@@ -0,0 +1,10 @@
+mod montgomery;
+mod field;
+mod point;
+mod scalar;
+mod error;
+mod ecdh;
+mod ecdsa;
+
+pub use ecdh::{KeyPair, PrivateKey, PublicKey};
+pub use ecdsa::{Signature, SigningKey, VerifyingKey};
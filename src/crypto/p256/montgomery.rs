@@ -0,0 +1,163 @@
+// Constant-time arithmetic modulo a 256 bit odd number, with values kept in Montgomery form
+// (a * 2^256 mod m) as four little-endian 64 bit limbs. Used for both the P-256 base field
+// and the group order.
+
+pub struct Modulus {
+    pub value: [u64; 4],
+    // -m^-1 mod 2^64
+    pub inverse: u64,
+    // 2^512 mod m, for converting into Montgomery form
+    pub r_squared: [u64; 4]
+}
+
+// a + b + carry, returning the low 64 bits and the carry out
+fn add_with_carry(a: u64, b: u64, carry: u64) -> (u64, u64) {
+    let sum = a as u128 + b as u128 + carry as u128;
+    (sum as u64, (sum >> 64) as u64)
+}
+
+// a - b - borrow, returning the low 64 bits and the borrow out (0 or 1)
+fn sub_with_borrow(a: u64, b: u64, borrow: u64) -> (u64, u64) {
+    let difference = (a as u128).wrapping_sub(b as u128 + borrow as u128);
+    (difference as u64, (difference >> 127) as u64)
+}
+
+// a + b * c + carry, returning the low 64 bits and the high 64 bits
+fn multiply_add(a: u64, b: u64, c: u64, carry: u64) -> (u64, u64) {
+    let product = a as u128 + b as u128 * c as u128 + carry as u128;
+    (product as u64, (product >> 64) as u64)
+}
+
+// Subtracts the modulus from the 257 bit value (carry, value) if the result is not negative.
+// The value must be below 2m.
+fn reduce_once(value: &[u64; 4], carry: u64, modulus: &Modulus) -> [u64; 4] {
+    let mut reduced = [0u64; 4];
+    let mut borrow = 0;
+    for i in 0..4 {
+        (reduced[i], borrow) = sub_with_borrow(value[i], modulus.value[i], borrow);
+    }
+
+    // keep the unreduced value only when the subtraction borrowed past the carry bit
+    let (_, borrow) = sub_with_borrow(carry, 0, borrow);
+    select(&reduced, value, borrow)
+}
+
+// Reduces any 256 bit value modulo m, which only takes one subtraction because m > 2^255.
+pub fn reduce(value: &[u64; 4], modulus: &Modulus) -> [u64; 4] {
+    reduce_once(value, 0, modulus)
+}
+
+pub fn add(a: &[u64; 4], b: &[u64; 4], modulus: &Modulus) -> [u64; 4] {
+    let mut sum = [0u64; 4];
+    let mut carry = 0;
+    for i in 0..4 {
+        (sum[i], carry) = add_with_carry(a[i], b[i], carry);
+    }
+
+    reduce_once(&sum, carry, modulus)
+}
+
+pub fn sub(a: &[u64; 4], b: &[u64; 4], modulus: &Modulus) -> [u64; 4] {
+    let mut difference = [0u64; 4];
+    let mut borrow = 0;
+    for i in 0..4 {
+        (difference[i], borrow) = sub_with_borrow(a[i], b[i], borrow);
+    }
+
+    // add the modulus back if the subtraction went negative
+    let mask = 0u64.wrapping_sub(borrow);
+    let mut carry = 0;
+    for (limb, modulus_limb) in difference.iter_mut().zip(modulus.value) {
+        (*limb, carry) = add_with_carry(*limb, modulus_limb & mask, carry);
+    }
+
+    difference
+}
+
+// Montgomery multiplication: a * b * 2^-256 mod m (CIOS method)
+pub fn multiply(a: &[u64; 4], b: &[u64; 4], modulus: &Modulus) -> [u64; 4] {
+    let mut t = [0u64; 6];
+
+    for &b_limb in b {
+        let mut carry = 0;
+        for j in 0..4 {
+            (t[j], carry) = multiply_add(t[j], a[j], b_limb, carry);
+        }
+        (t[4], t[5]) = add_with_carry(t[4], carry, 0);
+
+        // add a multiple of m that clears the lowest limb, then shift down by one limb
+        let q = t[0].wrapping_mul(modulus.inverse);
+        let (_, mut carry) = multiply_add(t[0], q, modulus.value[0], 0);
+        for j in 1..4 {
+            (t[j - 1], carry) = multiply_add(t[j], q, modulus.value[j], carry);
+        }
+        (t[3], carry) = add_with_carry(t[4], carry, 0);
+        t[4] = t[5] + carry;
+    }
+
+    reduce_once(&[t[0], t[1], t[2], t[3]], t[4], modulus)
+}
+
+pub fn to_montgomery(a: &[u64; 4], modulus: &Modulus) -> [u64; 4] {
+    multiply(a, &modulus.r_squared, modulus)
+}
+
+pub fn from_montgomery(a: &[u64; 4], modulus: &Modulus) -> [u64; 4] {
+    multiply(a, &[1, 0, 0, 0], modulus)
+}
+
+// a^(m - 2) mod m, the inverse of a when m is prime. The exponent is public, so branching on
+// its bits doesn't leak anything about a.
+pub fn invert(a: &[u64; 4], one: &[u64; 4], modulus: &Modulus) -> [u64; 4] {
+    let mut exponent = modulus.value;
+    exponent[0] -= 2;
+
+    let mut result = *one;
+    for i in (0..256).rev() {
+        result = multiply(&result, &result, modulus);
+        if (exponent[i / 64] >> (i % 64)) & 1 == 1 {
+            result = multiply(&result, a, modulus);
+        }
+    }
+
+    result
+}
+
+// Returns a when choice is 0 and b when choice is 1, without branching.
+pub fn select(a: &[u64; 4], b: &[u64; 4], choice: u64) -> [u64; 4] {
+    let mask = 0u64.wrapping_sub(choice);
+    let mut result = [0u64; 4];
+    for i in 0..4 {
+        result[i] = a[i] ^ ((a[i] ^ b[i]) & mask);
+    }
+    result
+}
+
+pub fn is_zero(a: &[u64; 4]) -> u64 {
+    let combined = a[0] | a[1] | a[2] | a[3];
+    // the top bit of (x | -x) is set for every non-zero x
+    1 ^ ((combined | combined.wrapping_neg()) >> 63)
+}
+
+// Big-endian bytes to limbs, returning the limbs and 1 if the value is below the modulus.
+pub fn from_bytes(bytes: &[u8; 32], modulus: &Modulus) -> ([u64; 4], u64) {
+    let mut limbs = [0u64; 4];
+    for (i, chunk) in bytes.chunks_exact(8).enumerate() {
+        limbs[3 - i] = u64::from_be_bytes(chunk.try_into().unwrap());
+    }
+
+    let mut borrow = 0;
+    for (limb, modulus_limb) in limbs.iter().zip(modulus.value) {
+        (_, borrow) = sub_with_borrow(*limb, modulus_limb, borrow);
+    }
+
+    (limbs, borrow)
+}
+
+pub fn to_bytes(limbs: &[u64; 4]) -> [u8; 32] {
+    let mut bytes = [0u8; 32];
+    for (i, chunk) in bytes.chunks_exact_mut(8).enumerate() {
+        chunk.copy_from_slice(&limbs[3 - i].to_be_bytes());
+    }
+    bytes
+}
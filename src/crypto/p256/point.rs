@@ -0,0 +1,189 @@
+// Points on the NIST P-256 curve y^2 = x^3 - 3x + b.
+//
+// Points are kept in projective coordinates (X : Y : Z) with x = X/Z and y = Y/Z, using the
+// complete addition formulas from Renes, Costello and Batina ("Complete addition formulas for
+// prime order elliptic curves", 2016). They work for doubling and the point at infinity too,
+// so scalar multiplication doesn't need any secret-dependent branches.
+
+use super::field::FieldElement;
+
+// b in Montgomery form
+const B: FieldElement = FieldElement::from_montgomery(
+    [0xd89cdf6229c4bddf, 0xacf005cd78843090, 0xe5a220abf7212ed6, 0xdc30061d04874834]
+);
+
+// the base point G in Montgomery form
+const BASE_X: FieldElement = FieldElement::from_montgomery(
+    [0x79e730d418a9143c, 0x75ba95fc5fedb601, 0x79fb732b77622510, 0x18905f76a53755c6]
+);
+const BASE_Y: FieldElement = FieldElement::from_montgomery(
+    [0xddf25357ce95560a, 0x8b4ab8e4ba19e45c, 0xd2e88688dd21f325, 0x8571ff1825885d85]
+);
+
+pub const UNCOMPRESSED_LENGTH: usize = 65;
+
+#[derive(Clone, Copy)]
+pub struct Point {
+    x: FieldElement,
+    y: FieldElement,
+    z: FieldElement
+}
+
+impl Point {
+    pub fn identity() -> Self {
+        Self {
+            x: FieldElement::ZERO,
+            y: FieldElement::ONE,
+            z: FieldElement::ZERO
+        }
+    }
+
+    pub fn base() -> Self {
+        Self {
+            x: BASE_X,
+            y: BASE_Y,
+            z: FieldElement::ONE
+        }
+    }
+
+    // Builds a point from affine coordinates, returning None if it is not on the curve.
+    pub fn from_affine(x: FieldElement, y: FieldElement) -> Option<Self> {
+        // y^2 = x^3 - 3x + b
+        let left = y.square();
+        let three_x = x.add(&x).add(&x);
+        let right = x.square().multiply(&x).sub(&three_x).add(&B);
+
+        if left.equals(&right) == 0 {
+            return None;
+        }
+
+        Some(Self {
+            x,
+            y,
+            z: FieldElement::ONE
+        })
+    }
+
+    // Parses the SEC 1 uncompressed encoding 0x04 || x || y. The point at infinity and
+    // compressed points are rejected, as TLS only allows uncompressed points.
+    pub fn from_uncompressed(bytes: &[u8]) -> Option<Self> {
+        if bytes.len() != UNCOMPRESSED_LENGTH || bytes[0] != 0x04 {
+            return None;
+        }
+
+        let x = FieldElement::from_bytes(bytes[1..33].try_into().unwrap())?;
+        let y = FieldElement::from_bytes(bytes[33..65].try_into().unwrap())?;
+        Self::from_affine(x, y)
+    }
+
+    // Returns the affine coordinates, or None for the point at infinity.
+    pub fn to_affine(self) -> Option<(FieldElement, FieldElement)> {
+        if self.z.is_zero() == 1 {
+            return None;
+        }
+
+        let z_inverse = self.z.invert();
+        Some((self.x.multiply(&z_inverse), self.y.multiply(&z_inverse)))
+    }
+
+    pub fn to_uncompressed(self) -> Option<[u8; UNCOMPRESSED_LENGTH]> {
+        let (x, y) = self.to_affine()?;
+
+        let mut result = [0u8; UNCOMPRESSED_LENGTH];
+        result[0] = 0x04;
+        result[1..33].copy_from_slice(&x.to_bytes());
+        result[33..65].copy_from_slice(&y.to_bytes());
+        Some(result)
+    }
+
+    // Algorithm 4 of the paper, for curves with a = -3.
+    pub fn add(&self, other: &Self) -> Self {
+        let (x1, y1, z1) = (&self.x, &self.y, &self.z);
+        let (x2, y2, z2) = (&other.x, &other.y, &other.z);
+
+        let mut t0 = x1.multiply(x2);
+        let mut t1 = y1.multiply(y2);
+        let mut t2 = z1.multiply(z2);
+        let mut t3 = x1.add(y1);
+        let mut t4 = x2.add(y2);
+        t3 = t3.multiply(&t4);
+        t4 = t0.add(&t1);
+        t3 = t3.sub(&t4);
+        t4 = y1.add(z1);
+        let mut x3 = y2.add(z2);
+        t4 = t4.multiply(&x3);
+        x3 = t1.add(&t2);
+        t4 = t4.sub(&x3);
+        x3 = x1.add(z1);
+        let mut y3 = x2.add(z2);
+        x3 = x3.multiply(&y3);
+        y3 = t0.add(&t2);
+        y3 = x3.sub(&y3);
+        let mut z3 = B.multiply(&t2);
+        x3 = y3.sub(&z3);
+        z3 = x3.add(&x3);
+        x3 = x3.add(&z3);
+        z3 = t1.sub(&x3);
+        x3 = t1.add(&x3);
+        y3 = B.multiply(&y3);
+        t1 = t2.add(&t2);
+        t2 = t1.add(&t2);
+        y3 = y3.sub(&t2);
+        y3 = y3.sub(&t0);
+        t1 = y3.add(&y3);
+        y3 = t1.add(&y3);
+        t1 = t0.add(&t0);
+        t0 = t1.add(&t0);
+        t0 = t0.sub(&t2);
+        t1 = t4.multiply(&y3);
+        t2 = t0.multiply(&y3);
+        y3 = x3.multiply(&z3);
+        y3 = y3.add(&t2);
+        x3 = t3.multiply(&x3);
+        x3 = x3.sub(&t1);
+        z3 = t4.multiply(&z3);
+        t1 = t3.multiply(&t0);
+        z3 = z3.add(&t1);
+
+        Self {
+            x: x3,
+            y: y3,
+            z: z3
+        }
+    }
+
+    // Swaps a and b when choice is 1, in constant time.
+    fn conditional_swap(a: &mut Self, b: &mut Self, choice: u64) {
+        let new_a = Self {
+            x: FieldElement::select(&a.x, &b.x, choice),
+            y: FieldElement::select(&a.y, &b.y, choice),
+            z: FieldElement::select(&a.z, &b.z, choice)
+        };
+        let new_b = Self {
+            x: FieldElement::select(&b.x, &a.x, choice),
+            y: FieldElement::select(&b.y, &a.y, choice),
+            z: FieldElement::select(&b.z, &a.z, choice)
+        };
+
+        *a = new_a;
+        *b = new_b;
+    }
+
+    // Multiplies the point by a big-endian scalar with a Montgomery ladder, which performs the
+    // same operations for every scalar of the same length.
+    pub fn scalar_multiply(&self, scalar: &[u8; 32]) -> Self {
+        let mut r0 = Self::identity();
+        let mut r1 = *self;
+
+        for i in 0..256 {
+            let bit = ((scalar[i / 8] >> (7 - i % 8)) & 1) as u64;
+
+            Self::conditional_swap(&mut r0, &mut r1, bit);
+            r1 = r0.add(&r1);
+            r0 = r0.add(&r0);
+            Self::conditional_swap(&mut r0, &mut r1, bit);
+        }
+
+        r0
+    }
+}
@@ -0,0 +1,67 @@
+// Integers modulo the order n of the P-256 base point.
+
+use super::montgomery::{self, Modulus};
+use crate::utils::zeroize::Zeroize;
+
+const N: Modulus = Modulus {
+    value: [0xf3b9cac2fc632551, 0xbce6faada7179e84, 0xffffffffffffffff, 0xffffffff00000000],
+    inverse: 0xccd1c8aaee00bc4f,
+    r_squared: [0x83244c95be79eea2, 0x4699799c49bd6fa6, 0x2845b2392b6bec59, 0x66e12d94f3d95620]
+};
+
+// 2^256 mod n, which is 1 in Montgomery form
+const ONE: [u64; 4] = [0x0c46353d039cdaaf, 0x4319055258e8617b, 0x0000000000000000, 0x00000000ffffffff];
+
+// Not Copy, since private keys and nonces are scalars and every copy would need wiping.
+#[derive(Clone)]
+pub struct Scalar([u64; 4]);
+
+impl Scalar {
+    // Parses a big-endian scalar, returning None unless 1 <= value < n.
+    pub fn from_bytes(bytes: &[u8; 32]) -> Option<Self> {
+        if !is_valid(bytes) {
+            return None;
+        }
+
+        let (limbs, _) = montgomery::from_bytes(bytes, &N);
+        Some(Self(montgomery::to_montgomery(&limbs, &N)))
+    }
+
+    // Interprets the big-endian bytes as an integer and reduces it modulo n.
+    pub fn from_bytes_reduced(bytes: &[u8; 32]) -> Self {
+        let (limbs, _) = montgomery::from_bytes(bytes, &N);
+        Self(montgomery::to_montgomery(&montgomery::reduce(&limbs, &N), &N))
+    }
+
+    pub fn to_bytes(&self) -> [u8; 32] {
+        montgomery::to_bytes(&montgomery::from_montgomery(&self.0, &N))
+    }
+
+    pub fn add(&self, other: &Self) -> Self {
+        Self(montgomery::add(&self.0, &other.0, &N))
+    }
+
+    pub fn multiply(&self, other: &Self) -> Self {
+        Self(montgomery::multiply(&self.0, &other.0, &N))
+    }
+
+    pub fn invert(&self) -> Self {
+        Self(montgomery::invert(&self.0, &ONE, &N))
+    }
+
+    pub fn is_zero(&self) -> bool {
+        montgomery::is_zero(&self.0) == 1
+    }
+}
+
+impl Zeroize for Scalar {
+    fn zeroize(&mut self) {
+        self.0.zeroize();
+    }
+}
+
+// Whether the big-endian bytes are a valid private scalar, 1 <= d < n.
+pub fn is_valid(bytes: &[u8; 32]) -> bool {
+    let (limbs, is_below_order) = montgomery::from_bytes(bytes, &N);
+    (is_below_order & (1 ^ montgomery::is_zero(&limbs))) == 1
+}
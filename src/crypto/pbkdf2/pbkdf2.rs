@@ -0,0 +1,120 @@
+// Password-Based Key Derivation Function 2 (PBKDF2), as specified in RFC 8018 section 5.2,
+// using HMAC-SHA256 as the pseudorandom function.
+//
+// Every output block is the XOR of `iterations` chained HMAC outputs, so the cost of guessing a
+// password grows linearly with the iteration count. The salt makes precomputed tables useless.
+
+use std::fmt::{Display, Formatter};
+use crate::crypto::sha256::hmac_sha256::HmacSha256;
+use crate::utils::secret::Secret;
+use crate::utils::zeroize::zeroize;
+
+pub const HASH_LENGTH: usize = 32;
+
+#[derive(Debug, PartialEq)]
+pub enum Pbkdf2Error {
+    InvalidIterations(u32),
+    // The output can be at most 2^32 - 1 blocks, since the block index is 32 bits.
+    OutputTooLong(usize),
+}
+
+impl Display for Pbkdf2Error {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Pbkdf2Error::InvalidIterations(iterations) => write!(f, "PBKDF2 needs at least one iteration, got {}", iterations),
+            Pbkdf2Error::OutputTooLong(length) => write!(f, "PBKDF2 output of {} bytes is more than 2^32 - 1 times the hash length", length),
+        }
+    }
+}
+
+// T(i) = U(1) ^ U(2) ^ ... ^ U(c), with U(1) = PRF(P, S | INT(i)) and U(j) = PRF(P, U(j - 1))
+// The output is the first dkLen bytes of T(1) | T(2) | ...
+pub fn derive(password: &[u8], salt: &[u8], iterations: u32, length: usize) -> Result<Secret<Vec<u8>>, Pbkdf2Error> {
+    if iterations == 0 {
+        return Err(Pbkdf2Error::InvalidIterations(iterations));
+    }
+
+    // the block index is a 32 bit counter
+    if length as u64 > u32::MAX as u64 * HASH_LENGTH as u64 {
+        return Err(Pbkdf2Error::OutputTooLong(length));
+    }
+    let block_count = length.div_ceil(HASH_LENGTH) as u32;
+
+    // key the HMAC once, and clone the keyed state for every PRF call
+    let keyed_hmac = HmacSha256::new(password);
+    let mut output = Secret::new(Vec::with_capacity(length));
+
+    for block_index in 1..=block_count {
+        let mut hmac = keyed_hmac.clone();
+        hmac.update(salt);
+        hmac.update(&block_index.to_be_bytes());
+        let mut u = hmac.finalize();
+        let mut block = u;
+
+        for _ in 1..iterations {
+            let mut hmac = keyed_hmac.clone();
+            hmac.update(&u);
+            u = hmac.finalize();
+
+            for (block_byte, u_byte) in block.iter_mut().zip(u.iter()) {
+                *block_byte ^= u_byte;
+            }
+        }
+
+        let needed = (length - output.expose().len()).min(HASH_LENGTH);
+        output.expose_mut().extend_from_slice(&block[..needed]);
+
+        zeroize(&mut u);
+        zeroize(&mut block);
+    }
+
+    Ok(output)
+}
+
+#[cfg(test)]
+use crate::utils::formatting::hex_to_bytes;
+
+#[test]
+fn rfc7914_test() {
+    // RFC 7914 section 11
+    let test_cases = [
+        ("passwd", "salt", 1,
+         "55ac046e56e3089fec1691c22544b605f94185216dde0465e68b9d57c20dacbc\
+          49ca9cccf179b645991664b39d77ef317c71b845b1e30bd509112041d3a19783"),
+        ("Password", "NaCl", 80000,
+         "4ddcd8f60b98be21830cee5ef22701f9641a4418d04c0414aeff08876b34ab56\
+          a1d425a1225833549adb841b51c9b3176a272bdebba1d078478f62b397f33c8d"),
+    ];
+
+    for (password, salt, iterations, expected_output) in test_cases {
+        let output = derive(password.as_bytes(), salt.as_bytes(), iterations, 64).unwrap();
+        assert_eq!(output.expose(), &hex_to_bytes(expected_output).unwrap());
+    }
+}
+
+#[test]
+fn rfc6070_style_test() {
+    // the RFC 6070 inputs, with HMAC-SHA256 instead of HMAC-SHA1
+    let test_cases: [(&[u8], &[u8], u32, &str); 5] = [
+        (b"password", b"salt", 1, "120fb6cffcf8b32c43e7225256c4f837a86548c92ccc35480805987cb70be17b"),
+        (b"password", b"salt", 2, "ae4d0c95af6b46d32d0adff928f06dd02a303f8ef3c251dfd6e2d85a95474c43"),
+        (b"password", b"salt", 4096, "c5e478d59288c841aa530db6845c4c8d962893a001ce4e11a4963873aa98134a"),
+        (b"passwordPASSWORDpassword", b"saltSALTsaltSALTsaltSALTsaltSALTsalt", 4096,
+         "348c89dbcbd32b2f32d814b8116e84cf2b17347ebc1800181c4e2a1fb8dd53e1c635518c7dac47e9"),
+        (b"pass\0word", b"sa\0lt", 4096, "89b69d0516f829893c696226650a8687"),
+    ];
+
+    for (password, salt, iterations, expected_output) in test_cases {
+        let expected_output = hex_to_bytes(expected_output).unwrap();
+        let output = derive(password, salt, iterations, expected_output.len()).unwrap();
+        assert_eq!(output.expose(), &expected_output);
+    }
+}
+
+#[test]
+fn invalid_parameters_test() {
+    assert_eq!(derive(b"password", b"salt", 0, 32).err(), Some(Pbkdf2Error::InvalidIterations(0)));
+    let too_long = u32::MAX as usize * HASH_LENGTH + 1;
+    assert_eq!(derive(b"password", b"salt", 1, too_long).err(), Some(Pbkdf2Error::OutputTooLong(too_long)));
+    assert!(derive(b"password", b"salt", 1, 0).unwrap().expose().is_empty());
+}
@@ -0,0 +1,71 @@
+// The textual encoding from RFC 7468: base64 between "-----BEGIN label-----" and
+// "-----END label-----" lines. Text outside the blocks is ignored, as tools often put a
+// human-readable description of a certificate before it.
+
+use crate::crypto::base64::base64::{decode_b64, encode_b64_bytes};
+use super::error::{PemError, PemErrorKind};
+
+// RFC 7468 section 2 asks generators to wrap the base64 at 64 characters
+const LINE_LENGTH: usize = 64;
+
+pub struct Block {
+    pub label: String,
+    pub data: Vec<u8>
+}
+
+fn boundary_label<'a>(line: &'a str, kind: &str) -> Option<&'a str> {
+    line.strip_prefix("-----")?
+        .strip_prefix(kind)?
+        .strip_prefix(' ')?
+        .strip_suffix("-----")
+}
+
+// Decodes every block in the text. The file name only goes into error messages.
+pub fn decode(text: &str, file: &str) -> Result<Vec<Block>, PemError> {
+    let mut blocks = vec![];
+    let mut lines = text.lines().map(str::trim);
+
+    while let Some(line) = lines.next() {
+        let Some(label) = boundary_label(line, "BEGIN") else {
+            continue;
+        };
+
+        let error = |kind| PemError {
+            file: file.to_string(),
+            block: Some((blocks.len() + 1, label.to_string())),
+            kind
+        };
+
+        let mut body = String::new();
+        loop {
+            let line = lines.next().ok_or_else(|| error(PemErrorKind::MissingEnd))?;
+            match boundary_label(line, "END") {
+                Some(end_label) if end_label == label => break,
+                Some(end_label) => return Err(error(PemErrorKind::MismatchedEnd(end_label.to_string()))),
+                None => {}
+            }
+            body.push_str(line);
+        }
+
+        let data = decode_b64(body.as_bytes()).map_err(|e| error(PemErrorKind::InvalidBase64(e)))?;
+        blocks.push(Block {
+            label: label.to_string(),
+            data
+        });
+    }
+
+    Ok(blocks)
+}
+
+pub fn encode(label: &str, data: &[u8]) -> String {
+    let body = encode_b64_bytes(data);
+
+    let mut result = format!("-----BEGIN {}-----\n", label);
+    for line in body.as_bytes().chunks(LINE_LENGTH) {
+        // base64 output is ASCII, so every chunk is valid UTF-8
+        result.push_str(std::str::from_utf8(line).unwrap());
+        result.push('\n');
+    }
+    result.push_str(&format!("-----END {}-----\n", label));
+    result
+}
@@ -0,0 +1,59 @@
+use std::fmt::{Display, Formatter};
+use crate::crypto::base64::base64::Base64Error;
+use crate::crypto::der::DerError;
+use crate::crypto::x509::X509Error;
+
+#[derive(Debug, PartialEq)]
+pub enum PemErrorKind {
+    // The file couldn't be read or written, with the operating system's message.
+    Io(String),
+    // A BEGIN line without a matching END line.
+    MissingEnd,
+    // An END line for a different label than the BEGIN line.
+    MismatchedEnd(String),
+    // Text after a BEGIN line that isn't base64, e.g. an encrypted key's headers.
+    InvalidBase64(Base64Error),
+    // The file holds no block with the label we need.
+    NotFound(&'static str),
+    InvalidCertificate(X509Error),
+    // The private key isn't valid PKCS#8 (or SEC 1 for EC keys).
+    MalformedPrivateKey(DerError),
+    // A private key algorithm other than Ed25519, P-256 or X25519, with the dotted object identifier.
+    UnsupportedKeyAlgorithm(String),
+    // The key is well formed but its value isn't usable, e.g. a P-256 scalar outside the group.
+    InvalidPrivateKey,
+}
+
+impl Display for PemErrorKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PemErrorKind::Io(message) => write!(f, "{}", message),
+            PemErrorKind::MissingEnd => write!(f, "missing END line"),
+            PemErrorKind::MismatchedEnd(label) => write!(f, "block ends with END {}", label),
+            PemErrorKind::InvalidBase64(error) => write!(f, "invalid base64: {}", error),
+            PemErrorKind::NotFound(label) => write!(f, "no {} block found", label),
+            PemErrorKind::InvalidCertificate(error) => write!(f, "{}", error),
+            PemErrorKind::MalformedPrivateKey(error) => write!(f, "malformed private key: {}", error),
+            PemErrorKind::UnsupportedKeyAlgorithm(oid) => write!(f, "unsupported private key algorithm {}", oid),
+            PemErrorKind::InvalidPrivateKey => write!(f, "invalid private key"),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct PemError {
+    // the file name, as given by the caller
+    pub file: String,
+    // the 1-based position and the label of the block that failed, if the error is about one
+    pub block: Option<(usize, String)>,
+    pub kind: PemErrorKind
+}
+
+impl Display for PemError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match &self.block {
+            Some((index, label)) => write!(f, "{}: block {} ({}): {}", self.file, index, label, self.kind),
+            None => write!(f, "{}: {}", self.file, self.kind),
+        }
+    }
+}
@@ -0,0 +1,16 @@
+mod error;
+mod armor;
+mod pkcs8;
+mod files;
+
+// Nothing loads keys or certificates from disk yet, so the interface below has
+// no callers until the TLS server is given its certificate.
+#[allow(unused_imports)]
+pub use error::{PemError, PemErrorKind};
+#[allow(unused_imports)]
+pub use pkcs8::PrivateKey;
+#[allow(unused_imports)]
+pub use files::{
+    decode_certificate_chain, decode_private_key, encode_certificate_chain, encode_private_key,
+    load_certificate_chain, load_private_key, save_certificate_chain, save_private_key
+};
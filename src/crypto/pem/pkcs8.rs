@@ -0,0 +1,149 @@
+// Private keys in PKCS#8 (RFC 5208, with the key formats of RFC 8410 for Ed25519 and X25519)
+// and in the SEC 1 ECPrivateKey format (RFC 5915) that OpenSSL writes for "EC PRIVATE KEY".
+
+use crate::crypto::der::{oid, tag, DerError, DerReader, DerWriter};
+use crate::crypto::{ed25519, p256, x25519};
+use crate::utils::secret::Secret;
+use super::error::PemErrorKind;
+
+pub enum PrivateKey {
+    // boxed because the key holds a decompressed point, much larger than the other variants
+    Ed25519(Box<ed25519::SigningKey>),
+    P256(p256::SigningKey),
+    X25519(x25519::Key)
+}
+
+impl From<DerError> for PemErrorKind {
+    fn from(error: DerError) -> Self {
+        PemErrorKind::MalformedPrivateKey(error)
+    }
+}
+
+fn to_key_bytes(bytes: &[u8]) -> Result<&[u8; 32], PemErrorKind> {
+    bytes.try_into().map_err(|_| PemErrorKind::InvalidPrivateKey)
+}
+
+pub fn decode_pkcs8(der: &[u8]) -> Result<PrivateKey, PemErrorKind> {
+    let mut reader = DerReader::new(der);
+    let mut private_key_info = reader.read_sequence()?;
+    reader.finish()?;
+
+    // version 0, or 1 for the RFC 5958 form that may carry the public key
+    if private_key_info.read_small_integer()? > 1 {
+        return Err(PemErrorKind::InvalidPrivateKey);
+    }
+
+    let mut algorithm_identifier = private_key_info.read_sequence()?;
+    let algorithm = algorithm_identifier.read_oid()?;
+    let parameters = if algorithm_identifier.is_empty() {
+        None
+    } else {
+        Some(algorithm_identifier.read_oid()?)
+    };
+    algorithm_identifier.finish()?;
+
+    let private_key = private_key_info.read_octet_string()?;
+    // the optional attributes and public key aren't needed
+    while !private_key_info.is_empty() {
+        private_key_info.read_element()?;
+    }
+
+    match (algorithm, parameters) {
+        (oid::ED25519, None) => {
+            let seed = read_curve_private_key(private_key)?;
+            Ok(PrivateKey::Ed25519(Box::new(ed25519::SigningKey::from_seed(seed))))
+        },
+        (oid::X25519, None) => {
+            let key = read_curve_private_key(private_key)?;
+            Ok(PrivateKey::X25519(x25519::Key::from_bytes(key)))
+        },
+        (oid::EC_PUBLIC_KEY, Some(oid::PRIME256V1)) => decode_ec_private_key(private_key),
+        (oid::EC_PUBLIC_KEY, Some(curve)) => Err(PemErrorKind::UnsupportedKeyAlgorithm(oid::to_string(curve))),
+        (other, _) => Err(PemErrorKind::UnsupportedKeyAlgorithm(oid::to_string(other)))
+    }
+}
+
+// The CurvePrivateKey from RFC 8410, an OCTET STRING inside the PKCS#8 OCTET STRING.
+fn read_curve_private_key(der: &[u8]) -> Result<&[u8; 32], PemErrorKind> {
+    let mut reader = DerReader::new(der);
+    let key = reader.read_octet_string()?;
+    reader.finish()?;
+    to_key_bytes(key)
+}
+
+// Decodes a SEC 1 ECPrivateKey for P-256, checking the curve and the public key if present.
+pub fn decode_ec_private_key(der: &[u8]) -> Result<PrivateKey, PemErrorKind> {
+    let mut reader = DerReader::new(der);
+    let mut ec_private_key = reader.read_sequence()?;
+    reader.finish()?;
+
+    if ec_private_key.read_small_integer()? != 1 {
+        return Err(PemErrorKind::InvalidPrivateKey);
+    }
+
+    let scalar = to_key_bytes(ec_private_key.read_octet_string()?)?;
+    let signing_key = p256::SigningKey::from_bytes(scalar).map_err(|_| PemErrorKind::InvalidPrivateKey)?;
+
+    if let Some(parameters) = ec_private_key.read_optional(tag::context_specific(0, true))? {
+        let mut parameters = DerReader::new(parameters);
+        let curve = parameters.read_oid()?;
+        parameters.finish()?;
+        if curve != oid::PRIME256V1 {
+            return Err(PemErrorKind::UnsupportedKeyAlgorithm(oid::to_string(curve)));
+        }
+    }
+
+    if let Some(public_key) = ec_private_key.read_optional(tag::context_specific(1, true))? {
+        let mut public_key = DerReader::new(public_key);
+        let point = public_key.read_bit_string()?;
+        public_key.finish()?;
+        if point != signing_key.verifying_key().as_uncompressed() {
+            return Err(PemErrorKind::InvalidPrivateKey);
+        }
+    }
+    ec_private_key.finish()?;
+
+    Ok(PrivateKey::P256(signing_key))
+}
+
+pub fn encode_pkcs8(key: &PrivateKey) -> Secret<Vec<u8>> {
+    let mut writer = DerWriter::new();
+    writer.write_sequence(|private_key_info| {
+        private_key_info.write_small_integer(0);
+
+        match key {
+            PrivateKey::Ed25519(signing_key) => {
+                private_key_info.write_sequence(|algorithm| algorithm.write_oid(oid::ED25519));
+                write_curve_private_key(private_key_info, signing_key.seed());
+            },
+            PrivateKey::X25519(private_key) => {
+                private_key_info.write_sequence(|algorithm| algorithm.write_oid(oid::X25519));
+                write_curve_private_key(private_key_info, private_key.as_bytes());
+            },
+            PrivateKey::P256(signing_key) => {
+                private_key_info.write_sequence(|algorithm| {
+                    algorithm.write_oid(oid::EC_PUBLIC_KEY);
+                    algorithm.write_oid(oid::PRIME256V1);
+                });
+
+                // the curve is already in the algorithm identifier, so only the public key is added
+                let mut ec_private_key = DerWriter::new();
+                ec_private_key.write_sequence(|sequence| {
+                    sequence.write_small_integer(1);
+                    sequence.write_octet_string(signing_key.as_bytes());
+                    sequence.write_constructed(tag::context_specific(1, true), |public_key| {
+                        public_key.write_bit_string(signing_key.verifying_key().as_uncompressed());
+                    });
+                });
+                private_key_info.write_octet_string(Secret::new(ec_private_key.finish()).expose());
+            }
+        }
+    });
+    Secret::new(writer.finish())
+}
+
+fn write_curve_private_key(writer: &mut DerWriter, key: &[u8; 32]) {
+    let mut curve_private_key = DerWriter::new();
+    curve_private_key.write_octet_string(key);
+    writer.write_octet_string(Secret::new(curve_private_key.finish()).expose());
+}
@@ -0,0 +1,3 @@
+mod poly1305;
+
+pub use poly1305::{Poly1305, TAG_LENGTH};
@@ -0,0 +1,286 @@
+// The Poly1305 one-time authenticator, as specified in RFC 8439 section 2.5.
+//
+// The message is split into 16 byte blocks, each read as a little endian number with an extra
+// high bit set. The blocks are evaluated as a polynomial in r modulo the prime 2^130 - 5, and
+// the secret s is added to the result to give the tag. A key must only be used for one message.
+//
+// ChaCha20-Poly1305 derives a fresh key for every message from its own key and nonce. Other
+// callers have to do the same, for example by taking the key from a stream cipher's keystream.
+//
+// The accumulator and r are stored as five 26 bit limbs, so every product of two limbs fits
+// into a u64, and no branches or table lookups depend on the key or message.
+
+use crate::utils::constant_time;
+use crate::utils::secret::Secret;
+
+pub const KEY_LENGTH: usize = 32;
+pub const TAG_LENGTH: usize = 16;
+
+const LIMB_MASK: u32 = 0x3ffffff;
+
+pub struct Poly1305 {
+    r: Secret<[u32; 5]>,
+    s: Secret<[u32; 4]>,
+    accumulator: [u32; 5],
+    // a partial block waiting for more data
+    buffer: [u8; 16],
+    buffer_length: usize
+}
+
+impl Poly1305 {
+    pub fn new(key: &[u8; KEY_LENGTH]) -> Self {
+        // r is "clamped" by clearing some of its bits, as required by the RFC
+        let r = [
+            read_u32_le(&key[0..]) & 0x3ffffff,
+            (read_u32_le(&key[3..]) >> 2) & 0x3ffff03,
+            (read_u32_le(&key[6..]) >> 4) & 0x3ffc0ff,
+            (read_u32_le(&key[9..]) >> 6) & 0x3f03fff,
+            (read_u32_le(&key[12..]) >> 8) & 0x00fffff,
+        ];
+
+        let s = [
+            read_u32_le(&key[16..]),
+            read_u32_le(&key[20..]),
+            read_u32_le(&key[24..]),
+            read_u32_le(&key[28..]),
+        ];
+
+        Self {
+            r: Secret::new(r),
+            s: Secret::new(s),
+            accumulator: [0u32; 5],
+            buffer: [0u8; 16],
+            buffer_length: 0
+        }
+    }
+
+    pub fn update(&mut self, mut data: &[u8]) {
+        // top up a partial block left over from a previous call first
+        if self.buffer_length > 0 {
+            let needed = (16 - self.buffer_length).min(data.len());
+            self.buffer[self.buffer_length..self.buffer_length + needed].copy_from_slice(&data[..needed]);
+            self.buffer_length += needed;
+            data = &data[needed..];
+
+            if self.buffer_length < 16 {
+                return;
+            }
+
+            let block = self.buffer;
+            self.process_block(&block, 1 << 24);
+            self.buffer_length = 0;
+        }
+
+        let mut blocks = data.chunks_exact(16);
+        for block in &mut blocks {
+            self.process_block(block, 1 << 24);
+        }
+
+        let remainder = blocks.remainder();
+        self.buffer[..remainder.len()].copy_from_slice(remainder);
+        self.buffer_length = remainder.len();
+    }
+
+    pub fn finalize(mut self) -> [u8; TAG_LENGTH] {
+        // the final partial block gets its high bit as a 0x01 byte right after the data
+        if self.buffer_length > 0 {
+            let mut block = [0u8; 16];
+            block[..self.buffer_length].copy_from_slice(&self.buffer[..self.buffer_length]);
+            block[self.buffer_length] = 0x01;
+            self.process_block(&block, 0);
+        }
+
+        let [mut h0, mut h1, mut h2, mut h3, mut h4] = self.accumulator;
+
+        // fully carry the accumulator
+        let mut carry;
+        carry = h1 >> 26; h1 &= LIMB_MASK; h2 += carry;
+        carry = h2 >> 26; h2 &= LIMB_MASK; h3 += carry;
+        carry = h3 >> 26; h3 &= LIMB_MASK; h4 += carry;
+        carry = h4 >> 26; h4 &= LIMB_MASK; h0 += carry * 5;
+        carry = h0 >> 26; h0 &= LIMB_MASK; h1 += carry;
+
+        // compute g = h - p = h + 5 - 2^130
+        let mut g0 = h0.wrapping_add(5);
+        carry = g0 >> 26; g0 &= LIMB_MASK;
+        let mut g1 = h1.wrapping_add(carry);
+        carry = g1 >> 26; g1 &= LIMB_MASK;
+        let mut g2 = h2.wrapping_add(carry);
+        carry = g2 >> 26; g2 &= LIMB_MASK;
+        let mut g3 = h3.wrapping_add(carry);
+        carry = g3 >> 26; g3 &= LIMB_MASK;
+        let g4 = h4.wrapping_add(carry).wrapping_sub(1 << 26);
+
+        // select h if h < p (g went negative), otherwise g, without branching
+        let select_g = (g4 >> 31).wrapping_sub(1);
+        let select_h = !select_g;
+        h0 = (h0 & select_h) | (g0 & select_g);
+        h1 = (h1 & select_h) | (g1 & select_g);
+        h2 = (h2 & select_h) | (g2 & select_g);
+        h3 = (h3 & select_h) | (g3 & select_g);
+        h4 = (h4 & select_h) | (g4 & select_g);
+
+        // repack the limbs into four 32 bit words, dropping everything above 2^128
+        let words = [
+            h0 | (h1 << 26),
+            (h1 >> 6) | (h2 << 20),
+            (h2 >> 12) | (h3 << 14),
+            (h3 >> 18) | (h4 << 8),
+        ];
+
+        // add s modulo 2^128
+        let mut tag = [0u8; TAG_LENGTH];
+        let mut sum = 0u64;
+        for i in 0..4 {
+            sum = words[i] as u64 + self.s.expose()[i] as u64 + (sum >> 32);
+            tag[i * 4..(i + 1) * 4].copy_from_slice(&(sum as u32).to_le_bytes());
+        }
+
+        tag
+    }
+
+    // Checks a received tag against the one computed over the data passed to `update`, without
+    // revealing how much of it matched.
+    pub fn verify(self, tag: &[u8]) -> bool {
+        constant_time::compare(&self.finalize(), tag)
+    }
+
+    // Adds one block to the accumulator and multiplies it by r, modulo 2^130 - 5.
+    fn process_block(&mut self, block: &[u8], high_bit: u32) {
+        let [r0, r1, r2, r3, r4] = self.r.expose().map(|limb| limb as u64);
+
+        // 2^130 is congruent to 5, so the parts of the product that overflow the top limb
+        // wrap around to the bottom multiplied by 5
+        let s1 = r1 * 5;
+        let s2 = r2 * 5;
+        let s3 = r3 * 5;
+        let s4 = r4 * 5;
+
+        let h0 = (self.accumulator[0] + (read_u32_le(&block[0..]) & LIMB_MASK)) as u64;
+        let h1 = (self.accumulator[1] + ((read_u32_le(&block[3..]) >> 2) & LIMB_MASK)) as u64;
+        let h2 = (self.accumulator[2] + ((read_u32_le(&block[6..]) >> 4) & LIMB_MASK)) as u64;
+        let h3 = (self.accumulator[3] + ((read_u32_le(&block[9..]) >> 6) & LIMB_MASK)) as u64;
+        let h4 = (self.accumulator[4] + ((read_u32_le(&block[12..]) >> 8) | high_bit)) as u64;
+
+        let d0 = h0 * r0 + h1 * s4 + h2 * s3 + h3 * s2 + h4 * s1;
+        let mut d1 = h0 * r1 + h1 * r0 + h2 * s4 + h3 * s3 + h4 * s2;
+        let mut d2 = h0 * r2 + h1 * r1 + h2 * r0 + h3 * s4 + h4 * s3;
+        let mut d3 = h0 * r3 + h1 * r2 + h2 * r1 + h3 * r0 + h4 * s4;
+        let mut d4 = h0 * r4 + h1 * r3 + h2 * r2 + h3 * r1 + h4 * r0;
+
+        // partially carry back down to 26 bit limbs
+        let mut carry;
+        carry = d0 >> 26; self.accumulator[0] = (d0 as u32) & LIMB_MASK; d1 += carry;
+        carry = d1 >> 26; self.accumulator[1] = (d1 as u32) & LIMB_MASK; d2 += carry;
+        carry = d2 >> 26; self.accumulator[2] = (d2 as u32) & LIMB_MASK; d3 += carry;
+        carry = d3 >> 26; self.accumulator[3] = (d3 as u32) & LIMB_MASK; d4 += carry;
+        carry = d4 >> 26; self.accumulator[4] = (d4 as u32) & LIMB_MASK;
+        self.accumulator[0] += (carry * 5) as u32;
+        carry = (self.accumulator[0] >> 26) as u64;
+        self.accumulator[0] &= LIMB_MASK;
+        self.accumulator[1] += carry as u32;
+    }
+}
+
+fn read_u32_le(bytes: &[u8]) -> u32 {
+    u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]])
+}
+
+#[cfg(test)]
+use crate::utils::formatting::hex_to_bytes;
+
+#[test]
+fn mac_test() {
+    // RFC 8439 section 2.5.2
+    let key: [u8; KEY_LENGTH] = hex_to_bytes("85d6be7857556d337f4452fe42d506a80103808afb0db2fd4abff6af4149f51b")
+        .unwrap()
+        .try_into()
+        .unwrap();
+    let expected = hex_to_bytes("a8061dc1305136c6c22b8baf0c0127a9").unwrap();
+
+    let mut poly1305 = Poly1305::new(&key);
+    poly1305.update(b"Cryptographic Forum Research Group");
+    assert_eq!(poly1305.finalize().to_vec(), expected);
+
+    // the same message fed in small pieces
+    let mut poly1305 = Poly1305::new(&key);
+    for chunk in b"Cryptographic Forum Research Group".chunks(3) {
+        poly1305.update(chunk);
+    }
+    assert_eq!(poly1305.finalize().to_vec(), expected);
+}
+
+#[test]
+fn reduction_edge_cases_test() {
+    // RFC 8439 appendix A.3, test vectors #5, #6, #8 and #9. These exercise the carries and
+    // the final reduction modulo 2^130 - 5.
+    let test_cases = [
+        ("0200000000000000000000000000000000000000000000000000000000000000",
+         "ffffffffffffffffffffffffffffffff",
+         "03000000000000000000000000000000"),
+        ("02000000000000000000000000000000ffffffffffffffffffffffffffffffff",
+         "02000000000000000000000000000000",
+         "03000000000000000000000000000000"),
+        ("0100000000000000000000000000000000000000000000000000000000000000",
+         "fffffffffffffffffffffffffffffffff0ffffffffffffffffffffffffffffff11000000000000000000000000000000",
+         "05000000000000000000000000000000"),
+        ("0100000000000000000000000000000000000000000000000000000000000000",
+         "fffffffffffffffffffffffffffffffffbfefefefefefefefefefefefefefefe01010101010101010101010101010101",
+         "00000000000000000000000000000000"),
+    ];
+
+    for (key, message, tag) in test_cases {
+        let key: [u8; KEY_LENGTH] = hex_to_bytes(key).unwrap().try_into().unwrap();
+
+        let mut poly1305 = Poly1305::new(&key);
+        poly1305.update(&hex_to_bytes(message).unwrap());
+        assert_eq!(poly1305.finalize().to_vec(), hex_to_bytes(tag).unwrap());
+    }
+}
+
+#[test]
+fn rfc_vectors_test() {
+    // RFC 8439 appendix A.3, test vectors #1 to #4
+    let ietf_text: &[u8] = b"Any submission to the IETF intended by the Contributor for publication as all or part of \
+        an IETF Internet-Draft or RFC and any statement made within the context of an IETF activity is considered an \
+        \"IETF Contribution\". Such statements include oral statements in IETF sessions, as well as written and \
+        electronic communications made at any time or place, which are addressed to";
+    let jabberwocky: &[u8] = b"'Twas brillig, and the slithy toves\nDid gyre and gimble in the wabe:\n\
+        All mimsy were the borogoves,\nAnd the mome raths outgrabe.";
+
+    let test_cases = [
+        ("0000000000000000000000000000000000000000000000000000000000000000", &[0u8; 64][..],
+         "00000000000000000000000000000000"),
+        ("0000000000000000000000000000000036e5f6b5c5e06070f0efca96227a863e", ietf_text,
+         "36e5f6b5c5e06070f0efca96227a863e"),
+        ("36e5f6b5c5e06070f0efca96227a863e00000000000000000000000000000000", ietf_text,
+         "f3477e7cd95417af89a6b8794c310cf0"),
+        ("1c9240a5eb55d38af333888604f6b5f0473917c1402b80099dca5cbc207075c0", jabberwocky,
+         "4541669a7eaaee61e708dc7cbcc5eb62"),
+    ];
+
+    for (key, message, tag) in test_cases {
+        let key: [u8; KEY_LENGTH] = hex_to_bytes(key).unwrap().try_into().unwrap();
+        let tag = hex_to_bytes(tag).unwrap();
+
+        let mut poly1305 = Poly1305::new(&key);
+        poly1305.update(message);
+        assert_eq!(poly1305.finalize().to_vec(), tag);
+
+        // split at an awkward point, so the buffered partial block is used
+        let mut poly1305 = Poly1305::new(&key);
+        let (first, second) = message.split_at(message.len() / 3);
+        poly1305.update(first);
+        poly1305.update(second);
+        assert!(poly1305.verify(&tag));
+
+        // the top bit of the tag, and a tag cut short, are rejected too
+        let mut wrong_tag = tag.clone();
+        wrong_tag[15] ^= 0x80;
+        for wrong_tag in [&wrong_tag[..], &tag[..8]] {
+            let mut poly1305 = Poly1305::new(&key);
+            poly1305.update(message);
+            assert!(!poly1305.verify(wrong_tag));
+        }
+    }
+}
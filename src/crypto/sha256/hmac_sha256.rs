@@ -1,57 +1,86 @@
 use super::sha256;
+use super::sha256::Sha256;
+use crate::utils::constant_time;
+use crate::utils::secret::Secret;
 
 const OPAD: [u8; 64] = [0x5C; 64];
 const IPAD: [u8; 64] = [0x36; 64];
 
 pub fn hash(input: &str, secret_key: &str) -> String {
-    let processed_key = process_key(secret_key);
-    let message_bytes = input.as_bytes();
-
-    let inner_hash = create_inner_hash(&processed_key, message_bytes);
-    let final_bytes = create_outer_hash(&processed_key, &inner_hash);
+    let final_bytes = hash_bytes(input.as_bytes(), secret_key.as_bytes());
 
     bytes_to_hex_string(&final_bytes)
 }
 
-fn create_outer_hash(key: &[u8], inner_hash: &[u8]) -> [u8; 32] {
-    let mut key_buffer = key.to_vec();
-    for i in 0..key_buffer.len() {
-        key_buffer[i] = key_buffer[i] ^ OPAD[i];
-    }
-
-    let mut final_message = key_buffer;
-    final_message.extend_from_slice(inner_hash);
+// Same as `hash`, but for binary keys and messages, returning the raw MAC.
+pub fn hash_bytes(input: &[u8], secret_key: &[u8]) -> [u8; 32] {
+    let mut hmac = HmacSha256::new(secret_key);
+    hmac.update(input);
+    hmac.finalize()
+}
 
-    sha256::hash(&final_message)
+// An incremental HMAC-SHA256 over binary keys and messages.
+// HMAC(K, m) = H((K' ^ opad) || H((K' ^ ipad) || m))
+//
+// Both hashers are keyed up front, so the message can be fed in with any number of `update`
+// calls, and a keyed instance can be cloned to MAC several messages with the same key.
+#[derive(Clone)]
+pub struct HmacSha256 {
+    inner: Sha256,
+    outer: Sha256
 }
 
-fn create_inner_hash(key: &[u8], message: &[u8]) -> [u8; 32] {
-    let mut key_buffer = key.to_vec();
-    for i in 0..key_buffer.len() {
-        key_buffer[i] = key_buffer[i] ^ IPAD[i];
+impl HmacSha256 {
+    pub fn new(secret_key: &[u8]) -> Self {
+        let processed_key = process_key(secret_key);
+
+        let mut inner = Sha256::new();
+        let inner_key = Secret::new(processed_key.expose().iter().zip(IPAD.iter()).map(|(k, p)| k ^ p).collect::<Vec<u8>>());
+        inner.update(inner_key.expose());
+
+        let mut outer = Sha256::new();
+        let outer_key = Secret::new(processed_key.expose().iter().zip(OPAD.iter()).map(|(k, p)| k ^ p).collect::<Vec<u8>>());
+        outer.update(outer_key.expose());
+
+        Self {
+            inner,
+            outer
+        }
     }
 
-    let mut final_message = key_buffer;
-    final_message.extend_from_slice(message);
+    pub fn update(&mut self, data: &[u8]) {
+        self.inner.update(data);
+    }
 
-    sha256::hash(&final_message)
+    pub fn finalize(self) -> [u8; 32] {
+        let inner_hash = self.inner.finalize();
+
+        let mut outer = self.outer;
+        outer.update(&inner_hash);
+        outer.finalize()
+    }
+
+    // Checks the MAC of everything passed to `update` against the given tag, without leaking
+    // through timing how much of the tag was correct.
+    pub fn verify(self, tag: &[u8]) -> bool {
+        constant_time::compare(&self.finalize(), tag)
+    }
 }
 
-fn process_key(key: &str) -> Vec<u8> {
-    let key_bytes = key.as_bytes();
+fn process_key(key_bytes: &[u8]) -> Secret<Vec<u8>> {
     let mut processed = Vec::with_capacity(64);
 
     if key_bytes.len() > 64 {
         // Hash the key if it's too long
-        let hashed = sha256::hash(key_bytes);
-        processed.extend_from_slice(&hashed);
+        let hashed = Secret::new(sha256::hash(key_bytes));
+        processed.extend_from_slice(hashed.expose());
         processed.resize(64, 0);
     } else {
         processed.extend_from_slice(key_bytes);
         processed.resize(64, 0);
     }
 
-    processed
+    Secret::new(processed)
 }
 
 fn bytes_to_hex_string(bytes: &[u8]) -> String {
@@ -69,4 +98,67 @@ pub fn hash_test() {
     let hashed_output = hash(input, secret);
 
     assert_eq!(hashed_output, expected_output);
-}
\ No newline at end of file
+}
+
+#[test]
+pub fn incremental_hash_test() {
+    // RFC 4231 test cases 2 and 6
+    let test_cases: [(&[u8], &[u8], [u8; 32]); 2] = [
+        (b"Jefe", b"what do ya want for nothing?", [
+            0x5b, 0xdc, 0xc1, 0x46, 0xbf, 0x60, 0x75, 0x4e, 0x6a, 0x04, 0x24, 0x26, 0x08, 0x95, 0x75, 0xc7,
+            0x5a, 0x00, 0x3f, 0x08, 0x9d, 0x27, 0x39, 0x83, 0x9d, 0xec, 0x58, 0xb9, 0x64, 0xec, 0x38, 0x43
+        ]),
+        (&[0xaa; 131], b"Test Using Larger Than Block-Size Key - Hash Key First", [
+            0x60, 0xe4, 0x31, 0x59, 0x1e, 0xe0, 0xb6, 0x7f, 0x0d, 0x8a, 0x26, 0xaa, 0xcb, 0xf5, 0xb7, 0x7f,
+            0x8e, 0x0b, 0xc6, 0x21, 0x37, 0x28, 0xc5, 0x14, 0x05, 0x46, 0x04, 0x0f, 0x0e, 0xe3, 0x7f, 0x54
+        ]),
+    ];
+
+    for (key, input, expected_output) in test_cases {
+        assert_eq!(hash_bytes(input, key), expected_output);
+
+        let mut hmac = HmacSha256::new(key);
+        for chunk in input.chunks(7) {
+            hmac.update(chunk);
+        }
+        assert_eq!(hmac.finalize(), expected_output);
+    }
+}
+
+#[test]
+pub fn verify_test() {
+    let mut hmac = HmacSha256::new(b"key");
+    hmac.update(b"The quick brown fox jumps over the lazy dog");
+    let tag = hmac.clone().finalize();
+
+    assert!(hmac.clone().verify(&tag));
+
+    let mut wrong_tag = tag;
+    wrong_tag[31] ^= 0x01;
+    assert!(!hmac.clone().verify(&wrong_tag));
+
+    // truncated tags are rejected
+    assert!(!hmac.verify(&tag[..16]));
+}
+
+#[cfg(test)]
+use crate::utils::test_vectors::wycheproof;
+
+
+#[test]
+pub fn wycheproof_test() {
+    for group in &wycheproof::load("hmac_sha256_test.json") {
+        let tag_length = group.usize("tagSize") / 8;
+        for test in &group.tests {
+            let mut hmac = HmacSha256::new(&test.hex("key"));
+            hmac.update(&test.hex("msg"));
+
+            // truncated tags are checked against the start of the full MAC
+            let accepted = match tag_length {
+                32 => hmac.verify(&test.hex("tag")),
+                _ => constant_time::compare(&hmac.finalize()[..tag_length], &test.hex("tag"))
+            };
+            test.check(accepted);
+        }
+    }
+}
@@ -1,69 +1,146 @@
 pub fn hash(input: &[u8]) -> [u8; 32] {
-    let message = preprocess_message(input);
-
-    // Message scheduling on all 512-bit blocks in the message
-    let mut hash_values = INITIAL_HASH;
-    for chunk in message.chunks(64) {
-        let mut words: [u32; 64] = [0;64];
-        // Get W0..W15
-        for i in 0..16 {
-            let start = i * 4;
-            let bytes = [chunk[start], chunk[start+1], chunk[start+2], chunk[start+3]];
-            words[i] = u32::from_be_bytes(bytes);
+    let mut hasher = Sha256::new();
+    hasher.update(input);
+    hasher.finalize()
+}
+
+// An incremental SHA-256 hasher. Data can be fed in with any number of `update` calls, and
+// the hasher can be cloned at any point to get the hash of everything so far while carrying on
+// with the original, e.g. for the TLS transcript hash.
+#[derive(Clone)]
+pub struct Sha256 {
+    hash_values: [u32; 8],
+    // a partial block waiting for more data
+    buffer: [u8; 64],
+    buffer_length: usize,
+    // total number of bytes passed to update
+    message_length: u64
+}
+
+impl Sha256 {
+    pub fn new() -> Self {
+        Self {
+            hash_values: INITIAL_HASH,
+            buffer: [0u8; 64],
+            buffer_length: 0,
+            message_length: 0
         }
+    }
+
+    pub fn update(&mut self, mut data: &[u8]) {
+        self.message_length += data.len() as u64;
 
-        for i in 16..64 {
-            let w = small_sigma1(words[i-2])
-                .wrapping_add(words[i-7])
-                .wrapping_add(small_sigma0(words[i-15]))
-                .wrapping_add(words[i-16]);
+        // top up a partial block left over from a previous call first
+        if self.buffer_length > 0 {
+            let needed = (64 - self.buffer_length).min(data.len());
+            self.buffer[self.buffer_length..self.buffer_length + needed].copy_from_slice(&data[..needed]);
+            self.buffer_length += needed;
+            data = &data[needed..];
 
-            words[i] = w;
+            if self.buffer_length < 64 {
+                return;
+            }
+
+            let block = self.buffer;
+            compress(&mut self.hash_values, &block);
+            self.buffer_length = 0;
         }
 
-        let mut a = hash_values[0];
-        let mut b = hash_values[1];
-        let mut c = hash_values[2];
-        let mut d = hash_values[3];
-        let mut e = hash_values[4];
-        let mut f = hash_values[5];
-        let mut g = hash_values[6];
-        let mut h = hash_values[7];
-
-        for i in 0..64 {
-            let round = K[i];
-            let t1 = h.wrapping_add(large_sigma1(e))
-                .wrapping_add(calculate_choose(e, f, g))
-                .wrapping_add(round)
-                .wrapping_add(words[i]);
-            let t2 = large_sigma0(a).wrapping_add(calculate_majority(a, b, c));
-            h = g;
-            g = f;
-            f = e;
-            e = d.wrapping_add(t1);
-            d = c;
-            c = b;
-            b = a;
-            a = t1.wrapping_add(t2);
+        let mut chunks = data.chunks_exact(64);
+        for chunk in &mut chunks {
+            compress(&mut self.hash_values, chunk);
         }
 
-        hash_values[0] = hash_values[0].wrapping_add(a);
-        hash_values[1] = hash_values[1].wrapping_add(b);
-        hash_values[2] = hash_values[2].wrapping_add(c);
-        hash_values[3] = hash_values[3].wrapping_add(d);
-        hash_values[4] = hash_values[4].wrapping_add(e);
-        hash_values[5] = hash_values[5].wrapping_add(f);
-        hash_values[6] = hash_values[6].wrapping_add(g);
-        hash_values[7] = hash_values[7].wrapping_add(h);
-    };
-    let mut final_hash = [0u8; 32];
-    for (i, &value) in hash_values.iter().enumerate() {
-        let bytes = value.to_be_bytes();
-        // Copy these 4 bytes into the appropriate position in our final array
-        final_hash[i*4..(i+1)*4].copy_from_slice(&bytes);
+        let remainder = chunks.remainder();
+        self.buffer[..remainder.len()].copy_from_slice(remainder);
+        self.buffer_length = remainder.len();
     }
 
-    final_hash
+    pub fn finalize(mut self) -> [u8; 32] {
+        let message_length = self.message_length * 8;
+
+        // Start by adding the '1' bit after the message.
+        // Adding 0x80 is the same as adding 1000000 in binary
+        self.update(&[0x80]);
+
+        // Now, we have to keep adding '0' bits until the total length of the message is a multiple
+        // of 512 bits, accounting for the 8 bytes at the end needed for the message size (64 bits)
+        let padding_length = (64 + 56 - self.buffer_length) % 64;
+        self.update(&[0x00; 64][..padding_length]);
+
+        // Now we add the total length of the original message to the end.
+        self.update(&message_length.to_be_bytes());
+
+        let mut final_hash = [0u8; 32];
+        for (i, &value) in self.hash_values.iter().enumerate() {
+            let bytes = value.to_be_bytes();
+            // Copy these 4 bytes into the appropriate position in our final array
+            final_hash[i*4..(i+1)*4].copy_from_slice(&bytes);
+        }
+
+        final_hash
+    }
+}
+
+impl Default for Sha256 {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// Message scheduling and compression of a single 512-bit block
+fn compress(hash_values: &mut [u32; 8], chunk: &[u8]) {
+    let mut words: [u32; 64] = [0;64];
+    // Get W0..W15
+    for (i, word) in words.iter_mut().take(16).enumerate() {
+        let start = i * 4;
+        let bytes = [chunk[start], chunk[start+1], chunk[start+2], chunk[start+3]];
+        *word = u32::from_be_bytes(bytes);
+    }
+
+    for i in 16..64 {
+        let w = small_sigma1(words[i-2])
+            .wrapping_add(words[i-7])
+            .wrapping_add(small_sigma0(words[i-15]))
+            .wrapping_add(words[i-16]);
+
+        words[i] = w;
+    }
+
+    let mut a = hash_values[0];
+    let mut b = hash_values[1];
+    let mut c = hash_values[2];
+    let mut d = hash_values[3];
+    let mut e = hash_values[4];
+    let mut f = hash_values[5];
+    let mut g = hash_values[6];
+    let mut h = hash_values[7];
+
+    for i in 0..64 {
+        let round = K[i];
+        let t1 = h.wrapping_add(large_sigma1(e))
+            .wrapping_add(calculate_choose(e, f, g))
+            .wrapping_add(round)
+            .wrapping_add(words[i]);
+        let t2 = large_sigma0(a).wrapping_add(calculate_majority(a, b, c));
+        h = g;
+        g = f;
+        f = e;
+        e = d.wrapping_add(t1);
+        d = c;
+        c = b;
+        b = a;
+        a = t1.wrapping_add(t2);
+    }
+
+    hash_values[0] = hash_values[0].wrapping_add(a);
+    hash_values[1] = hash_values[1].wrapping_add(b);
+    hash_values[2] = hash_values[2].wrapping_add(c);
+    hash_values[3] = hash_values[3].wrapping_add(d);
+    hash_values[4] = hash_values[4].wrapping_add(e);
+    hash_values[5] = hash_values[5].wrapping_add(f);
+    hash_values[6] = hash_values[6].wrapping_add(g);
+    hash_values[7] = hash_values[7].wrapping_add(h);
 }
 
 // The initialization values (IV) for the SHA-256 algorithm. Consists of the fractional
@@ -135,27 +212,6 @@ fn small_sigma1(i1: u32) -> u32 {
     r1 ^ r2 ^ s
 }
 
-fn preprocess_message(input_message: &[u8]) -> Vec<u8> {
-    let message_length: u64 = (input_message.len() as u64) * 8;
-
-    let mut message: Vec<u8> = input_message.to_vec();
-    // Start by adding the '1' bit after the message.
-    // Adding 0x80 is the same as adding 1000000 in binary
-    message.push(0x80);
-
-    // Now, we have to keep adding '0' bits until the total length of the message is a multiple
-    // of 512 bits, accounting for the 8 bytes at the end needed for the message size (64 bits)
-    while (message.len() + 8) % 64 != 0 {
-        message.push(0x00);
-    }
-
-    // Now we add the total length of the original message to the end.
-    let length_bits = message_length.to_be_bytes();
-    message.extend_from_slice(&length_bits);
-
-    message
-}
-
 #[test]
 pub fn hash_test() {
     let input = "the quick brown fox jumps over the lazy dog";
@@ -174,4 +230,76 @@ pub fn hash_test() {
 
     let hashed_output = hash(input.as_bytes());
     assert_eq!(hashed_output, expected_output);
-}
\ No newline at end of file
+}
+
+#[test]
+pub fn incremental_hash_test() {
+    // NIST FIPS 180-4 two-block example
+    let input = b"abcdbcdecdefdefgefghfghighijhijkijkljklmklmnlmnomnopnopq";
+    let expected_output = [
+        0x24, 0x8d, 0x6a, 0x61, 0xd2, 0x06, 0x38, 0xb8, 0xe5, 0xc0, 0x26, 0x93, 0x0c, 0x3e, 0x60, 0x39,
+        0xa3, 0x3c, 0xe4, 0x59, 0x64, 0xff, 0x21, 0x67, 0xf6, 0xec, 0xed, 0xd4, 0x19, 0xdb, 0x06, 0xc1
+    ];
+
+    assert_eq!(hash(input), expected_output);
+
+    // feeding the same message in chunks of every size must give the same hash
+    for chunk_size in 1..=input.len() {
+        let mut hasher = Sha256::new();
+        for chunk in input.chunks(chunk_size) {
+            hasher.update(chunk);
+        }
+        assert_eq!(hasher.finalize(), expected_output);
+    }
+}
+
+#[test]
+pub fn clone_mid_stream_test() {
+    let mut hasher = Sha256::new();
+    hasher.update(b"abc");
+
+    // a clone can be finalized without affecting the original
+    let intermediate = hasher.clone().finalize();
+    assert_eq!(intermediate, hash(b"abc"));
+
+    hasher.update(b"dbcdecdefdefgefghfghighijhijkijkljklmklmnlmnomnopnopq");
+    assert_eq!(hasher.finalize(), hash(b"abcdbcdecdefdefgefghfghighijhijkijkljklmklmnlmnomnopnopq"));
+}
+
+#[cfg(test)]
+use crate::utils::formatting::hex_to_bytes;
+
+#[test]
+pub fn padding_boundary_test() {
+    // lengths around the 55/56 byte boundary need an extra block for the length
+    let expected = [
+        (55, "9f4390f8d30c2dd92ec9f095b65e2b9ae9b0a925a5258e241c9f1e910f734318"),
+        (56, "b35439a4ac6f0948b6d6f9e3c6af0f5f590ce20f1bde7090ef7970686ec6738a"),
+        (64, "ffe054fe7ae0cb6dc65c3af9b61d5209f439851db43d0ba5997337df154668eb"),
+    ];
+
+    for (length, expected_output) in expected {
+        let input = vec![b'a'; length];
+        let expected_output = hex_to_bytes(expected_output).unwrap();
+        assert_eq!(hash(&input).to_vec(), expected_output);
+    }
+}
+
+#[cfg(test)]
+use crate::utils::test_vectors::cavp;
+
+#[test]
+pub fn cavp_test() {
+    cavp::check_message_digests("SHA256ShortMsg.rsp", |message| hash(message).to_vec());
+    cavp::check_message_digests("SHA256LongMsg.rsp", |message| hash(message).to_vec());
+    cavp::check_monte_carlo("SHA256Monte.rsp", |message| hash(message).to_vec());
+
+    // the long messages again, fed in uneven pieces
+    cavp::check_message_digests("SHA256LongMsg.rsp", |message| {
+        let mut hasher = Sha256::new();
+        for chunk in message.chunks(37) {
+            hasher.update(chunk);
+        }
+        hasher.finalize().to_vec()
+    });
+}
@@ -0,0 +1,40 @@
+use super::hmac_sha512::hmac;
+use super::sha384;
+
+pub fn hash(input: &[u8], secret_key: &[u8]) -> [u8; 48] {
+    hmac(sha384::hash, input, secret_key)
+}
+
+#[cfg(test)]
+use crate::utils::formatting::hex_to_bytes;
+
+#[test]
+pub fn hash_test() {
+    // RFC 4231 test cases 1, 2 and 6
+    let test_cases: [(&[u8], &[u8], &str); 3] = [
+        (&[0x0b; 20], b"Hi There",
+         "afd03944d84895626b0825f4ab46907f15f9dadbe4101ec682aa034c7cebc59cfaea9ea9076ede7f4af152e8b2fa9cb6"),
+        (b"Jefe", b"what do ya want for nothing?",
+         "af45d2e376484031617f78d2b58a6b1b9c7ef464f5a01b47e42ec3736322445e8e2240ca5e69e2c78b3239ecfab21649"),
+        (&[0xaa; 131], b"Test Using Larger Than Block-Size Key - Hash Key First",
+         "4ece084485813e9088d2c63a041bc5b44f9ef1012a2b588f3cd11f05033ac4c60c2ef6ab4030fe8296248df163f44952"),
+    ];
+
+    for (key, input, expected_output) in test_cases {
+        assert_eq!(hash(input, key).to_vec(), hex_to_bytes(expected_output).unwrap());
+    }
+}
+
+#[cfg(test)]
+use crate::utils::test_vectors::wycheproof;
+
+#[test]
+pub fn wycheproof_test() {
+    for group in &wycheproof::load("hmac_sha384_test.json") {
+        let tag_length = group.usize("tagSize") / 8;
+        for test in &group.tests {
+            let mac = hash(&test.hex("msg"), &test.hex("key"));
+            test.check(crate::utils::constant_time::compare(&mac[..tag_length], &test.hex("tag")));
+        }
+    }
+}
@@ -0,0 +1,82 @@
+use super::sha512;
+use crate::utils::secret::Secret;
+
+// SHA-512 and SHA-384 both work on 128 byte blocks
+pub const BLOCK_SIZE: usize = 128;
+
+const OPAD: [u8; BLOCK_SIZE] = [0x5C; BLOCK_SIZE];
+const IPAD: [u8; BLOCK_SIZE] = [0x36; BLOCK_SIZE];
+
+pub fn hash(input: &[u8], secret_key: &[u8]) -> [u8; 64] {
+    hmac(sha512::hash, input, secret_key)
+}
+
+// HMAC over any hash function with a 128 byte block size.
+// HMAC(K, m) = H((K' ^ opad) || H((K' ^ ipad) || m))
+pub fn hmac<const N: usize>(hash_function: fn(&[u8]) -> [u8; N], input: &[u8], secret_key: &[u8]) -> [u8; N] {
+    let processed_key = process_key(hash_function, secret_key);
+
+    // both messages start with the key, so they are wiped too
+    let mut inner_message = Secret::new(Vec::with_capacity(BLOCK_SIZE + input.len()));
+    inner_message.expose_mut().extend(processed_key.expose().iter().zip(IPAD.iter()).map(|(k, p)| k ^ p));
+    inner_message.expose_mut().extend_from_slice(input);
+    let inner_hash = hash_function(inner_message.expose());
+
+    let mut outer_message = Secret::new(Vec::with_capacity(BLOCK_SIZE + N));
+    outer_message.expose_mut().extend(processed_key.expose().iter().zip(OPAD.iter()).map(|(k, p)| k ^ p));
+    outer_message.expose_mut().extend_from_slice(&inner_hash);
+
+    hash_function(outer_message.expose())
+}
+
+fn process_key<const N: usize>(hash_function: fn(&[u8]) -> [u8; N], key: &[u8]) -> Secret<[u8; BLOCK_SIZE]> {
+    let mut processed = Secret::new([0u8; BLOCK_SIZE]);
+
+    if key.len() > BLOCK_SIZE {
+        // Hash the key if it's too long
+        let hashed = Secret::new(hash_function(key));
+        processed.expose_mut()[..N].copy_from_slice(hashed.expose());
+    } else {
+        processed.expose_mut()[..key.len()].copy_from_slice(key);
+    }
+
+    processed
+}
+
+#[cfg(test)]
+use crate::utils::formatting::hex_to_bytes;
+
+#[test]
+pub fn hash_test() {
+    // RFC 4231 test cases 1, 2 and 6
+    let test_cases: [(&[u8], &[u8], &str); 3] = [
+        (&[0x0b; 20], b"Hi There",
+         "87aa7cdea5ef619d4ff0b4241a1d6cb02379f4e2ce4ec2787ad0b30545e17cde\
+          daa833b7d6b8a702038b274eaea3f4e4be9d914eeb61f1702e696c203a126854"),
+        (b"Jefe", b"what do ya want for nothing?",
+         "164b7a7bfcf819e2e395fbe73b56e0a387bd64222e831fd610270cd7ea250554\
+          9758bf75c05a994a6d034f65f8f0e6fdcaeab1a34d4a6b4b636e070a38bce737"),
+        (&[0xaa; 131], b"Test Using Larger Than Block-Size Key - Hash Key First",
+         "80b24263c7c1a3ebb71493c1dd7be8b49b46d1f41b4aeec1121b013783f8f352\
+          6b56d037e05f2598bd0fd2215d6a1e5295e64f73f63f0aec8b915a985d786598"),
+    ];
+
+    for (key, input, expected_output) in test_cases {
+        assert_eq!(hash(input, key).to_vec(), hex_to_bytes(expected_output).unwrap());
+    }
+}
+
+#[cfg(test)]
+use crate::utils::test_vectors::wycheproof;
+
+
+#[test]
+pub fn wycheproof_test() {
+    for group in &wycheproof::load("hmac_sha512_test.json") {
+        let tag_length = group.usize("tagSize") / 8;
+        for test in &group.tests {
+            let mac = hash(&test.hex("msg"), &test.hex("key"));
+            test.check(crate::utils::constant_time::compare(&mac[..tag_length], &test.hex("tag")));
+        }
+    }
+}
@@ -0,0 +1,5 @@
+pub mod hmac_sha384;
+pub mod hmac_sha512;
+pub mod sha384;
+pub mod sha512;
+pub mod sha512_256;
@@ -0,0 +1,56 @@
+use super::sha512;
+
+// SHA-384 is SHA-512 with a different initial hash, truncated to the first 384 bits.
+pub fn hash(input: &[u8]) -> [u8; 48] {
+    let hash_values = sha512::digest(input, &INITIAL_HASH);
+
+    let mut final_hash = [0u8; 48];
+    for (i, &value) in hash_values.iter().take(6).enumerate() {
+        final_hash[i*8..(i+1)*8].copy_from_slice(&value.to_be_bytes());
+    }
+
+    final_hash
+}
+
+// The first 64 bits of the fractional parts of the square roots of the 9th through 16th
+// primes (23..53)
+const INITIAL_HASH: [u64; 8] = [
+    0xcbbb9d5dc1059ed8,
+    0x629a292a367cd507,
+    0x9159015a3070dd17,
+    0x152fecd8f70e5939,
+    0x67332667ffc00b31,
+    0x8eb44a8768581511,
+    0xdb0c2e0d64f98fa7,
+    0x47b5481dbefa4fa4,
+];
+
+#[cfg(test)]
+use crate::utils::formatting::hex_to_bytes;
+
+#[test]
+pub fn hash_test() {
+    // NIST FIPS 180-4 examples
+    let test_cases = [
+        ("",
+         "38b060a751ac96384cd9327eb1b1e36a21fdb71114be07434c0cc7bf63f6e1da274edebfe76f65fbd51ad2f14898b95b"),
+        ("abc",
+         "cb00753f45a35e8bb5a03d699ac65007272c32ab0eded1631a8b605a43ff5bed8086072ba1e7cc2358baeca134c825a7"),
+        (sha512::TWO_BLOCK_MESSAGE,
+         "09330c33f71147e83d192fc782cd1b4753111b173b3b05d22fa08086e3b0f712fcc7c71a557e2db966c3e9fa91746039"),
+    ];
+
+    for (input, expected_output) in test_cases {
+        assert_eq!(hash(input.as_bytes()).to_vec(), hex_to_bytes(expected_output).unwrap());
+    }
+}
+
+#[cfg(test)]
+use crate::utils::test_vectors::cavp;
+
+#[test]
+pub fn cavp_test() {
+    cavp::check_message_digests("SHA384ShortMsg.rsp", |message| hash(message).to_vec());
+    cavp::check_message_digests("SHA384LongMsg.rsp", |message| hash(message).to_vec());
+    cavp::check_monte_carlo("SHA384Monte.rsp", |message| hash(message).to_vec());
+}
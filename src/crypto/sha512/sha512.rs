@@ -0,0 +1,217 @@
+pub fn hash(input: &[u8]) -> [u8; 64] {
+    let hash_values = digest(input, &INITIAL_HASH);
+
+    let mut final_hash = [0u8; 64];
+    for (i, &value) in hash_values.iter().enumerate() {
+        let bytes = value.to_be_bytes();
+        // Copy these 8 bytes into the appropriate position in our final array
+        final_hash[i*8..(i+1)*8].copy_from_slice(&bytes);
+    }
+
+    final_hash
+}
+
+// Runs the SHA-512 compression over the whole message, starting from the given initial hash.
+// SHA-384 and SHA-512/256 are the same algorithm with a different initial hash and a
+// truncated output.
+pub fn digest(input: &[u8], initial_hash: &[u64; 8]) -> [u64; 8] {
+    let message = preprocess_message(input);
+
+    // Message scheduling on all 1024-bit blocks in the message
+    let mut hash_values = *initial_hash;
+    for chunk in message.chunks(128) {
+        let mut words: [u64; 80] = [0; 80];
+        // Get W0..W15
+        for (i, word) in words.iter_mut().take(16).enumerate() {
+            let start = i * 8;
+            let mut bytes = [0u8; 8];
+            bytes.copy_from_slice(&chunk[start..start + 8]);
+            *word = u64::from_be_bytes(bytes);
+        }
+
+        for i in 16..80 {
+            let w = small_sigma1(words[i-2])
+                .wrapping_add(words[i-7])
+                .wrapping_add(small_sigma0(words[i-15]))
+                .wrapping_add(words[i-16]);
+
+            words[i] = w;
+        }
+
+        let mut a = hash_values[0];
+        let mut b = hash_values[1];
+        let mut c = hash_values[2];
+        let mut d = hash_values[3];
+        let mut e = hash_values[4];
+        let mut f = hash_values[5];
+        let mut g = hash_values[6];
+        let mut h = hash_values[7];
+
+        for i in 0..80 {
+            let round = K[i];
+            let t1 = h.wrapping_add(large_sigma1(e))
+                .wrapping_add(calculate_choose(e, f, g))
+                .wrapping_add(round)
+                .wrapping_add(words[i]);
+            let t2 = large_sigma0(a).wrapping_add(calculate_majority(a, b, c));
+            h = g;
+            g = f;
+            f = e;
+            e = d.wrapping_add(t1);
+            d = c;
+            c = b;
+            b = a;
+            a = t1.wrapping_add(t2);
+        }
+
+        hash_values[0] = hash_values[0].wrapping_add(a);
+        hash_values[1] = hash_values[1].wrapping_add(b);
+        hash_values[2] = hash_values[2].wrapping_add(c);
+        hash_values[3] = hash_values[3].wrapping_add(d);
+        hash_values[4] = hash_values[4].wrapping_add(e);
+        hash_values[5] = hash_values[5].wrapping_add(f);
+        hash_values[6] = hash_values[6].wrapping_add(g);
+        hash_values[7] = hash_values[7].wrapping_add(h);
+    };
+
+    hash_values
+}
+
+// The initialization values (IV) for the SHA-512 algorithm. Consists of the fractional
+// parts of the square roots of the first 8 primes (2..19)
+const INITIAL_HASH: [u64; 8] = [
+    0x6a09e667f3bcc908,
+    0xbb67ae8584caa73b,
+    0x3c6ef372fe94f82b,
+    0xa54ff53a5f1d36f1,
+    0x510e527fade682d1,
+    0x9b05688c2b3e6c1f,
+    0x1f83d9abfb41bd6b,
+    0x5be0cd19137e2179,
+];
+
+// The first 64 bits of the cube roots of the first 80 primes (2..409)
+const K: [u64; 80] = [
+    0x428a2f98d728ae22, 0x7137449123ef65cd, 0xb5c0fbcfec4d3b2f, 0xe9b5dba58189dbbc,
+    0x3956c25bf348b538, 0x59f111f1b605d019, 0x923f82a4af194f9b, 0xab1c5ed5da6d8118,
+    0xd807aa98a3030242, 0x12835b0145706fbe, 0x243185be4ee4b28c, 0x550c7dc3d5ffb4e2,
+    0x72be5d74f27b896f, 0x80deb1fe3b1696b1, 0x9bdc06a725c71235, 0xc19bf174cf692694,
+    0xe49b69c19ef14ad2, 0xefbe4786384f25e3, 0x0fc19dc68b8cd5b5, 0x240ca1cc77ac9c65,
+    0x2de92c6f592b0275, 0x4a7484aa6ea6e483, 0x5cb0a9dcbd41fbd4, 0x76f988da831153b5,
+    0x983e5152ee66dfab, 0xa831c66d2db43210, 0xb00327c898fb213f, 0xbf597fc7beef0ee4,
+    0xc6e00bf33da88fc2, 0xd5a79147930aa725, 0x06ca6351e003826f, 0x142929670a0e6e70,
+    0x27b70a8546d22ffc, 0x2e1b21385c26c926, 0x4d2c6dfc5ac42aed, 0x53380d139d95b3df,
+    0x650a73548baf63de, 0x766a0abb3c77b2a8, 0x81c2c92e47edaee6, 0x92722c851482353b,
+    0xa2bfe8a14cf10364, 0xa81a664bbc423001, 0xc24b8b70d0f89791, 0xc76c51a30654be30,
+    0xd192e819d6ef5218, 0xd69906245565a910, 0xf40e35855771202a, 0x106aa07032bbd1b8,
+    0x19a4c116b8d2d0c8, 0x1e376c085141ab53, 0x2748774cdf8eeb99, 0x34b0bcb5e19b48a8,
+    0x391c0cb3c5c95a63, 0x4ed8aa4ae3418acb, 0x5b9cca4f7763e373, 0x682e6ff3d6b2b8a3,
+    0x748f82ee5defb2fc, 0x78a5636f43172f60, 0x84c87814a1f0ab72, 0x8cc702081a6439ec,
+    0x90befffa23631e28, 0xa4506cebde82bde9, 0xbef9a3f7b2c67915, 0xc67178f2e372532b,
+    0xca273eceea26619c, 0xd186b8c721c0c207, 0xeada7dd6cde0eb1e, 0xf57d4f7fee6ed178,
+    0x06f067aa72176fba, 0x0a637dc5a2c898a6, 0x113f9804bef90dae, 0x1b710b35131c471b,
+    0x28db77f523047d84, 0x32caab7b40c72493, 0x3c9ebe0a15c9bebc, 0x431d67c49c100d4c,
+    0x4cc5d4becb3e42b6, 0x597f299cfc657e2a, 0x5fcb6fab3ad6faec, 0x6c44198c4a475817,
+];
+
+fn calculate_choose(e: u64, f: u64, g: u64) -> u64 {
+    // for each bit position, use e to determine if the bit from f or g should be kept.
+    // if e = 1, use f bit
+    // else use g bit
+    (e & f) ^ ((!e) & g)
+}
+
+fn calculate_majority(a: u64, b: u64, c: u64) -> u64 {
+    // converts bits that are 1 across 2 of 3 words into 1, else it becomes 0
+    (a & b) ^ (a & c) ^ (b & c)
+}
+
+fn large_sigma0(a: u64) -> u64 {
+    let r1 = a.rotate_right(28);
+    let r2 = a.rotate_right(34);
+    let r3 = a.rotate_right(39);
+
+    r1 ^ r2 ^ r3
+}
+
+fn large_sigma1(e: u64) -> u64 {
+    let r1 = e.rotate_right(14);
+    let r2 = e.rotate_right(18);
+    let r3 = e.rotate_right(41);
+
+    r1 ^ r2 ^ r3
+}
+
+fn small_sigma0(i1: u64) -> u64 {
+    let r1 = i1.rotate_right(1);
+    let r2 = i1.rotate_right(8);
+    let s = i1 >> 7;
+
+    r1 ^ r2 ^ s
+}
+
+fn small_sigma1(i1: u64) -> u64 {
+    let r1 = i1.rotate_right(19);
+    let r2 = i1.rotate_right(61);
+    let s = i1 >> 6;
+
+    r1 ^ r2 ^ s
+}
+
+fn preprocess_message(input_message: &[u8]) -> Vec<u8> {
+    let message_length: u128 = (input_message.len() as u128) * 8;
+
+    let mut message: Vec<u8> = input_message.to_vec();
+    // Start by adding the '1' bit after the message.
+    message.push(0x80);
+
+    // Keep adding '0' bits until the total length of the message is a multiple of 1024 bits,
+    // accounting for the 16 bytes at the end needed for the message size (128 bits)
+    while !(message.len() + 16).is_multiple_of(128) {
+        message.push(0x00);
+    }
+
+    // Now we add the total length of the original message to the end.
+    let length_bits = message_length.to_be_bytes();
+    message.extend_from_slice(&length_bits);
+
+    message
+}
+
+#[cfg(test)]
+use crate::utils::formatting::hex_to_bytes;
+
+// Two-block message from the NIST examples, shared with the SHA-384 and SHA-512/256 tests
+#[cfg(test)]
+pub const TWO_BLOCK_MESSAGE: &str = "abcdefghbcdefghicdefghijdefghijkefghijklfghijklmghijklmnhijklmno\
+                                     ijklmnopjklmnopqklmnopqrlmnopqrsmnopqrstnopqrstu";
+
+#[test]
+pub fn hash_test() {
+    // NIST FIPS 180-4 examples
+    let test_cases = [
+        ("",
+         "cf83e1357eefb8bdf1542850d66d8007d620e4050b5715dc83f4a921d36ce9ce\
+          47d0d13c5d85f2b0ff8318d2877eec2f63b931bd47417a81a538327af927da3e"),
+        ("abc",
+         "ddaf35a193617abacc417349ae20413112e6fa4e89a97ea20a9eeee64b55d39a\
+          2192992a274fc1a836ba3c23a3feebbd454d4423643ce80e2a9ac94fa54ca49f"),
+        (TWO_BLOCK_MESSAGE,
+         "8e959b75dae313da8cf4f72814fc143f8f7779c6eb9f7fa17299aeadb6889018\
+          501d289e4900f7e4331b99dec4b5433ac7d329eeb6dd26545e96e55b874be909"),
+    ];
+
+    for (input, expected_output) in test_cases {
+        assert_eq!(hash(input.as_bytes()).to_vec(), hex_to_bytes(expected_output).unwrap());
+    }
+}
+
+#[cfg(test)]
+use crate::utils::test_vectors::cavp;
+
+#[test]
+pub fn cavp_test() {
+    cavp::check_message_digests("SHA512ShortMsg.rsp", |message| hash(message).to_vec());
+    cavp::check_message_digests("SHA512LongMsg.rsp", |message| hash(message).to_vec());
+    cavp::check_monte_carlo("SHA512Monte.rsp", |message| hash(message).to_vec());
+}
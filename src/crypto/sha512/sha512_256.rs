@@ -0,0 +1,43 @@
+use super::sha512;
+
+// SHA-512/256 is SHA-512 with a different initial hash, truncated to the first 256 bits.
+// It has the same output size as SHA-256, but runs faster on 64 bit machines.
+pub fn hash(input: &[u8]) -> [u8; 32] {
+    let hash_values = sha512::digest(input, &INITIAL_HASH);
+
+    let mut final_hash = [0u8; 32];
+    for (i, &value) in hash_values.iter().take(4).enumerate() {
+        final_hash[i*8..(i+1)*8].copy_from_slice(&value.to_be_bytes());
+    }
+
+    final_hash
+}
+
+// Generated with the SHA-512/t IV generation function from FIPS 180-4 section 5.3.6, with t = 256
+const INITIAL_HASH: [u64; 8] = [
+    0x22312194fc2bf72c,
+    0x9f555fa3c84c64c2,
+    0x2393b86b6f53b151,
+    0x963877195940eabd,
+    0x96283ee2a88effe3,
+    0xbe5e1e2553863992,
+    0x2b0199fc2c85b8aa,
+    0x0eb72ddc81c52ca2,
+];
+
+#[cfg(test)]
+use crate::utils::formatting::hex_to_bytes;
+
+#[test]
+pub fn hash_test() {
+    // NIST FIPS 180-4 examples
+    let test_cases = [
+        ("", "c672b8d1ef56ed28ab87c3622c5114069bdd3ad7b8f9737498d0c01ecef0967a"),
+        ("abc", "53048e2681941ef99b2e29b76b4c7dabe4c2d0c634fc6d46e0e2f13107e7af23"),
+        (sha512::TWO_BLOCK_MESSAGE, "3928e184fb8690f840da3988121d31be65cb9d3ef83ee6146feac861e19b563a"),
+    ];
+
+    for (input, expected_output) in test_cases {
+        assert_eq!(hash(input.as_bytes()).to_vec(), hex_to_bytes(expected_output).unwrap());
+    }
+}
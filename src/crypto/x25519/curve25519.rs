@@ -1,4 +1,21 @@
-﻿use super::field_arithmetic::*;
+﻿use std::fmt::{Display, Formatter};
+use super::field::FieldElement;
+use crate::utils::secret::Secret;
+
+#[derive(Debug, PartialEq)]
+pub enum X25519Error {
+    // The peer's public key is a low order point, so the shared secret carries no
+    // contribution from our private key (RFC 7748 section 6.1).
+    AllZeroSharedSecret,
+}
+
+impl Display for X25519Error {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            X25519Error::AllZeroSharedSecret => write!(f, "X25519 shared secret is all zeros"),
+        }
+    }
+}
 
 pub struct KeyPair {
     pub public: Key,
@@ -7,105 +24,226 @@ pub struct KeyPair {
 
 impl KeyPair {
     pub fn generate() -> Self {
-        let keypair = generate_keypair();
+        Self::from_private(Key::from_bytes(&crate::utils::random::random_u8_32()))
+    }
+
+    // Builds the key pair for an existing private key, e.g. one loaded from a file.
+    pub fn from_private(private: Key) -> Self {
         Self {
-            public: Key {
-                value: keypair.1
-            },
-            private: Key {
-                value: keypair.0
-            }
+            public: Key::from_bytes(&scalar_multiply(private.value.expose(), &BASE_POINT)),
+            private
         }
     }
 }
 
+// A 32 byte X25519 key, either a private scalar or a public u coordinate.
+// The value is wiped from memory when the key is dropped.
 pub struct Key {
-    value: [u8; 32]
+    value: Secret<[u8; 32]>
 }
 
 impl Key {
-    pub fn create_shared(public: &Key, private: &Key) -> Self {
+    pub fn from_bytes(bytes: &[u8; 32]) -> Self {
         Self {
-            value: x25519(&private.value, &public.value)
+            value: Secret::new(*bytes)
+        }
+    }
+
+    // Computes the shared secret from our private key and the peer's public key.
+    pub fn create_shared(public: &Key, private: &Key) -> Result<Self, X25519Error> {
+        let shared = Self {
+            value: Secret::new(x25519(private.value.expose(), public.value.expose()))
+        };
+
+        // compare without branching on individual bytes of the secret
+        let combined = shared.value.expose().iter().fold(0u8, |acc, &b| acc | b);
+        if std::hint::black_box(combined) == 0 {
+            return Err(X25519Error::AllZeroSharedSecret);
         }
+
+        Ok(shared)
     }
 
     pub fn compare(&self, other: &Key) -> bool {
-        self.value == other.value
+        self.value.ct_eq(&other.value)
+    }
+
+    pub fn as_bytes(&self) -> &[u8; 32] {
+        self.value.expose()
     }
 
     pub fn to_vec(&self) -> Vec<u8> {
-        self.value.to_vec()
+        self.value.expose().to_vec()
     }
 }
 
-static NINE: [u8; 32] = [9; 32];
-
-fn generate_keypair() -> ([u8; 32], [u8; 32]) {
-    let mut private_key = crate::utils::random::random_u8_32();
-    let mut public_key = scalar_multiply(&private_key, &NINE);
-    (private_key, public_key)
-}
+// The u coordinate of the Curve25519 base point
+static BASE_POINT: [u8; 32] = {
+    let mut arr = [0; 32];
+    arr[0] = 9;
+    arr
+};
 
 fn x25519(private_key: &[u8; 32], public_key: &[u8; 32]) -> [u8; 32] {
     scalar_multiply(private_key, public_key)
 }
 
-const _121665: [i64; 16] = {
-    let mut arr = [0; 16];
-    arr[0] = 0xDB41;
-    arr[1] = 1;
-    arr
-};
-
+// Montgomery ladder from RFC 7748 section 5, with a24 = 121665 and z_2 = E * (AA + a24 * E).
 fn scalar_multiply(scalar: &[u8; 32], point: &[u8; 32]) -> [u8; 32] {
-    let mut bit = 0i64;
-    let mut a = [0i64; 16];
-    let mut b = [0i64; 16];
-    let mut c = [0i64; 16];
-    let mut d = [0i64; 16];
-    let mut e = [0i64; 16];
-    let mut f = [0i64; 16];
-    let mut x = [0i64; 16];
-
-    let mut clamped = scalar.clone();
+    let mut clamped = *scalar;
     clamped[0] &= 0xf8;
     clamped[31] = (clamped[31] & 0x7f) | 0x40;
 
-    x = unpack25519(point);
-    for i in 0..16 {
-        b[i] = x[i];
-    };
-    a[0] = 1;
-    d[0] = 1;
+    let x = FieldElement::from_bytes(point);
+    let mut a = FieldElement::ONE;
+    let mut b = x;
+    let mut c = FieldElement::ZERO;
+    let mut d = FieldElement::ONE;
 
     for i in (0..255).rev() {
-        bit = ((clamped[i >> 3] >> (i & 7)) & 1) as i64;
-        swap25519(&mut a, &mut b, bit);
-        swap25519(&mut c, &mut d, bit);
-        e = field_add(&a, &c);
-        a = field_sub(&a, &c);
-        c = field_add(&b, &d);
-        b = field_sub(&b, &d);
-        d = field_mul(&e, &e);
-        f = field_mul(&a, &a);
-        a = field_mul(&c, &a);
-        c = field_mul(&b, &e);
-        e = field_add(&a, &c);
-        a = field_sub(&a, &c);
-        b = field_mul(&a, &a);
-        c = field_sub(&d, &f);
-        a = field_mul(&c, &_121665);
-        a = field_add(&a, &d);
-        c = field_mul(&c, &a);
-        a = field_mul(&d, &f);
-        d = field_mul(&b, &x);
-        b = field_mul(&e, &e);
-        swap25519(&mut a, &mut b, bit);
-        swap25519(&mut c, &mut d, bit);
+        let bit = ((clamped[i >> 3] >> (i & 7)) & 1) as u64;
+        FieldElement::conditional_swap(&mut a, &mut b, bit);
+        FieldElement::conditional_swap(&mut c, &mut d, bit);
+        let e = a.add(&c);
+        a = a.sub(&c);
+        c = b.add(&d);
+        b = b.sub(&d);
+        d = e.square();
+        let f = a.square();
+        a = c.multiply(&a);
+        c = b.multiply(&e);
+        let e = a.add(&c);
+        a = a.sub(&c);
+        b = a.square();
+        c = d.sub(&f);
+        a = c.multiply_small(121665);
+        a = a.add(&d);
+        c = c.multiply(&a);
+        a = d.multiply(&f);
+        d = b.multiply(&x);
+        b = e.square();
+        FieldElement::conditional_swap(&mut a, &mut b, bit);
+        FieldElement::conditional_swap(&mut c, &mut d, bit);
     };
 
-    c = field_inverse(&c);
-    a = field_mul(&a, &c);
-    pack25519(&a)
-}
\ No newline at end of file
+    a.multiply(&c.invert()).to_bytes()
+}
+
+#[cfg(test)]
+use crate::utils::formatting::hex_to_bytes;
+
+#[cfg(test)]
+fn hex_to_key(hex: &str) -> [u8; 32] {
+    hex_to_bytes(hex).unwrap().try_into().unwrap()
+}
+
+#[test]
+fn rfc7748_test() {
+    // RFC 7748 section 5.2
+    let test_cases = [
+        ("a546e36bf0527c9d3b16154b82465edd62144c0ac1fc5a18506a2244ba449ac4",
+         "e6db6867583030db3594c1a424b15f7c726624ec26b3353b10a903a6d0ab1c4c",
+         "c3da55379de9c6908e94ea4df28d084f32eccf03491c71f754b4075577a28552"),
+        ("4b66e9d4d1b4673c5ad22691957d6af5c11b6421e0ea01d42ca4169e7918ba0d",
+         "e5210f12786811d3f4b7959d0538ae2c31dbe7106fc03c3efc4cd549c715a493",
+         "95cbde9476e8907d7aade45cb4b873f88b595a68799fa152e6f8f7647aac7957"),
+    ];
+
+    for (scalar, u_coordinate, expected_output) in test_cases {
+        let output = x25519(&hex_to_key(scalar), &hex_to_key(u_coordinate));
+        assert_eq!(output, hex_to_key(expected_output));
+    }
+}
+
+#[test]
+fn rfc7748_iterated_test() {
+    // RFC 7748 section 5.2, starting with k = u = 9 and repeatedly setting k = X25519(k, u), u = old k
+    let mut k = BASE_POINT;
+    let mut u = BASE_POINT;
+
+    for i in 1..=1000 {
+        let result = x25519(&k, &u);
+        u = k;
+        k = result;
+
+        if i == 1 {
+            assert_eq!(k, hex_to_key("422c8e7a6227d7bca1350b3e2bb7279f7897b87bb6854b783c60e80311ae3079"));
+        }
+    }
+
+    assert_eq!(k, hex_to_key("684cf59ba83309552800ef566f2f4d3c1c3887c49360e3875f2eb94d99532c51"));
+}
+
+#[test]
+fn diffie_hellman_test() {
+    // RFC 7748 section 6.1
+    let alice = KeyPair::from_private(Key::from_bytes(&hex_to_key("77076d0a7318a57d3c16c17251b26645df4c2f87ebc0992ab177fba51db92c2a")));
+    let bob = KeyPair::from_private(Key::from_bytes(&hex_to_key("5dab087e624a8a4b79e17f8b83800ee66f3bb1292618b6fd1c2f8b27ff88e0eb")));
+
+    assert_eq!(alice.public.as_bytes(), &hex_to_key("8520f0098930a754748b7ddcb43ef75a0dbf3a0d26381af4eba4a98eaa9b4e6a"));
+    assert_eq!(bob.public.as_bytes(), &hex_to_key("de9edb7d7b7dc1b4d35b61c2ece435373f8343c85b78674dadfc7e146f882b4f"));
+
+    let expected_shared = hex_to_key("4a5d9d5ba4ce2de1728e3bf480350f25e07e21c947d19e3376f09b3c1e161742");
+    let alice_shared = Key::create_shared(&bob.public, &alice.private).unwrap();
+    let bob_shared = Key::create_shared(&alice.public, &bob.private).unwrap();
+
+    assert_eq!(alice_shared.as_bytes(), &expected_shared);
+    assert!(alice_shared.compare(&bob_shared));
+}
+
+#[test]
+fn low_order_point_test() {
+    let private = KeyPair::generate().private;
+
+    // u = 0 and u = 1 are low order points, which always give an all-zero shared secret
+    let zero = Key::from_bytes(&[0u8; 32]);
+    assert_eq!(Key::create_shared(&zero, &private).err(), Some(X25519Error::AllZeroSharedSecret));
+
+    let mut one_bytes = [0u8; 32];
+    one_bytes[0] = 1;
+    let one = Key::from_bytes(&one_bytes);
+    assert_eq!(Key::create_shared(&one, &private).err(), Some(X25519Error::AllZeroSharedSecret));
+}
+
+// Measures handshakes per second. A handshake is generating the server key pair and computing
+// the shared secret. Run it with `cargo test --release handshake_benchmark -- --ignored --nocapture`.
+#[test]
+#[ignore]
+fn handshake_benchmark() {
+    use std::time::Instant;
+
+    let client = KeyPair::generate();
+    let handshakes = 2000;
+
+    let start = Instant::now();
+    for _ in 0..handshakes {
+        let server = KeyPair::generate();
+        let shared = Key::create_shared(&client.public, &server.private).unwrap();
+        std::hint::black_box((server, shared));
+    }
+    let time = start.elapsed();
+
+    println!("{:.0} handshakes/s", handshakes as f64 / time.as_secs_f64());
+}
+
+#[cfg(test)]
+use crate::utils::test_vectors::wycheproof;
+
+#[test]
+fn wycheproof_test() {
+    // low order public keys are "acceptable" there, and rejected here as RFC 7748 allows
+    for group in &wycheproof::load("x25519_test.json") {
+        for test in &group.tests {
+            let (Ok(public), Ok(private)) = (test.hex("public").try_into(), test.hex("private").try_into()) else {
+                test.check(false);
+                continue;
+            };
+            let shared = Key::create_shared(&Key::from_bytes(&public), &Key::from_bytes(&private));
+
+            if test.flags.iter().any(|flag| flag == "ZeroSharedSecret") {
+                assert_eq!(shared.as_ref().err(), Some(&X25519Error::AllZeroSharedSecret), "{test}");
+            }
+            test.check(shared.is_ok_and(|shared| shared.to_vec() == test.hex("shared")));
+        }
+    }
+}
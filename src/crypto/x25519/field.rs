@@ -0,0 +1,227 @@
+// Elements of GF(2^255 - 19) as five 51 bit limbs, value = sum of limb[i] * 2^(51 * i).
+//
+// Products of two limbs fit in a u128, and since 2^255 = 19 mod p, the parts of a product above
+// 2^255 fold back into the low limbs multiplied by 19. Limbs are allowed to grow a few bits past
+// 51 between operations; multiply and square accept limbs up to 2^54 and return them below 2^52.
+
+const MASK: u64 = (1 << 51) - 1;
+
+// 4p, added before subtracting so no limb goes negative
+const FOUR_P: [u64; 5] = [
+    0x1fffffffffffb4, 0x1ffffffffffffc, 0x1ffffffffffffc, 0x1ffffffffffffc, 0x1ffffffffffffc
+];
+
+#[derive(Clone, Copy)]
+pub struct FieldElement([u64; 5]);
+
+impl FieldElement {
+    pub const ZERO: FieldElement = FieldElement([0; 5]);
+    pub const ONE: FieldElement = FieldElement([1, 0, 0, 0, 0]);
+
+    // For constants, with each limb below 2^51.
+    pub const fn from_limbs(limbs: [u64; 5]) -> Self {
+        Self(limbs)
+    }
+
+    // Decodes 32 little endian bytes, ignoring the top bit as RFC 7748 requires. Values from
+    // p to 2^255 - 1 are accepted and reduced later.
+    pub fn from_bytes(bytes: &[u8; 32]) -> Self {
+        let load = |start: usize| {
+            let mut word = [0u8; 8];
+            word.copy_from_slice(&bytes[start..start + 8]);
+            u64::from_le_bytes(word)
+        };
+
+        Self([
+            load(0) & MASK,
+            (load(6) >> 3) & MASK,
+            (load(12) >> 6) & MASK,
+            (load(19) >> 1) & MASK,
+            (load(24) >> 12) & MASK
+        ])
+    }
+
+    // Encodes the fully reduced value as 32 little endian bytes.
+    pub fn to_bytes(self) -> [u8; 32] {
+        let mut limbs = carry(self.0.map(|limb| limb as u128));
+
+        // q is 1 if the value is p or more, found by checking whether value + 19 carries out of
+        // 2^255. Subtracting p is then adding 19 and dropping bit 255.
+        let mut q = (limbs[0] + 19) >> 51;
+        for limb in &limbs[1..] {
+            q = (limb + q) >> 51;
+        }
+        limbs[0] += 19 * q;
+        for i in 0..4 {
+            limbs[i + 1] += limbs[i] >> 51;
+            limbs[i] &= MASK;
+        }
+        limbs[4] &= MASK;
+
+        let mut result = [0u8; 32];
+        let mut accumulator = 0u128;
+        let mut bits = 0;
+        let mut position = 0;
+        for limb in limbs {
+            accumulator |= (limb as u128) << bits;
+            bits += 51;
+            while bits >= 8 {
+                result[position] = accumulator as u8;
+                accumulator >>= 8;
+                bits -= 8;
+                position += 1;
+            }
+        }
+        // 255 bits fill 31 bytes, with the last 7 bits left over
+        result[31] = accumulator as u8;
+        result
+    }
+
+    pub fn add(&self, other: &FieldElement) -> FieldElement {
+        let mut result = [0u64; 5];
+        for (i, limb) in result.iter_mut().enumerate() {
+            *limb = self.0[i] + other.0[i];
+        }
+        FieldElement(result)
+    }
+
+    pub fn sub(&self, other: &FieldElement) -> FieldElement {
+        let mut result = [0u128; 5];
+        for (i, limb) in result.iter_mut().enumerate() {
+            *limb = (self.0[i] + FOUR_P[i] - other.0[i]) as u128;
+        }
+        FieldElement(carry(result))
+    }
+
+    pub fn multiply(&self, other: &FieldElement) -> FieldElement {
+        let a = self.0.map(|limb| limb as u128);
+        let b = other.0.map(|limb| limb as u128);
+        let b19 = b.map(|limb| limb * 19);
+
+        FieldElement(carry([
+            a[0] * b[0] + a[1] * b19[4] + a[2] * b19[3] + a[3] * b19[2] + a[4] * b19[1],
+            a[0] * b[1] + a[1] * b[0] + a[2] * b19[4] + a[3] * b19[3] + a[4] * b19[2],
+            a[0] * b[2] + a[1] * b[1] + a[2] * b[0] + a[3] * b19[4] + a[4] * b19[3],
+            a[0] * b[3] + a[1] * b[2] + a[2] * b[1] + a[3] * b[0] + a[4] * b19[4],
+            a[0] * b[4] + a[1] * b[3] + a[2] * b[2] + a[3] * b[1] + a[4] * b[0]
+        ]))
+    }
+
+    // Squaring needs 15 limb products instead of 25, since a[i] * a[j] and a[j] * a[i] are the
+    // same product counted twice.
+    pub fn square(&self) -> FieldElement {
+        let a = self.0.map(|limb| limb as u128);
+        let a2 = a.map(|limb| limb * 2);
+        let a19 = a.map(|limb| limb * 19);
+        let a38 = a.map(|limb| limb * 38);
+
+        FieldElement(carry([
+            a[0] * a[0] + a38[1] * a[4] + a38[2] * a[3],
+            a2[0] * a[1] + a38[2] * a[4] + a19[3] * a[3],
+            a2[0] * a[2] + a[1] * a[1] + a38[3] * a[4],
+            a2[0] * a[3] + a2[1] * a[2] + a19[4] * a[4],
+            a2[0] * a[4] + a2[1] * a[3] + a[2] * a[2]
+        ]))
+    }
+
+    pub fn multiply_small(&self, factor: u32) -> FieldElement {
+        FieldElement(carry(self.0.map(|limb| limb as u128 * factor as u128)))
+    }
+
+    pub fn negate(&self) -> FieldElement {
+        FieldElement::ZERO.sub(self)
+    }
+
+    // a^(p - 2) = a^(2^255 - 21), using the addition chain from the curve25519 paper.
+    pub fn invert(&self) -> FieldElement {
+        let (z_250_0, z11) = self.pow_2_250_minus_1();
+        z_250_0.square_times(5).multiply(&z11)
+    }
+
+    // a^((p - 5) / 8) = a^(2^252 - 3), used for square roots when decoding Ed25519 points.
+    pub fn pow_p58(&self) -> FieldElement {
+        let (z_250_0, _) = self.pow_2_250_minus_1();
+        z_250_0.square_times(2).multiply(self)
+    }
+
+    // Returns a^(2^250 - 1), and a^11 which invert needs to finish its chain.
+    fn pow_2_250_minus_1(&self) -> (FieldElement, FieldElement) {
+        let z2 = self.square();
+        let z9 = self.multiply(&z2.square().square());
+        let z11 = z2.multiply(&z9);
+        let z_5_0 = z9.multiply(&z11.square());
+        let z_10_0 = z_5_0.square_times(5).multiply(&z_5_0);
+        let z_20_0 = z_10_0.square_times(10).multiply(&z_10_0);
+        let z_40_0 = z_20_0.square_times(20).multiply(&z_20_0);
+        let z_50_0 = z_40_0.square_times(10).multiply(&z_10_0);
+        let z_100_0 = z_50_0.square_times(50).multiply(&z_50_0);
+        let z_200_0 = z_100_0.square_times(100).multiply(&z_100_0);
+        let z_250_0 = z_200_0.square_times(50).multiply(&z_50_0);
+
+        (z_250_0, z11)
+    }
+
+    fn square_times(&self, count: usize) -> FieldElement {
+        let mut result = *self;
+        for _ in 0..count {
+            result = result.square();
+        }
+        result
+    }
+
+    // Swaps a and b when bit is 1, without branching on it.
+    pub fn conditional_swap(a: &mut FieldElement, b: &mut FieldElement, bit: u64) {
+        let mask = bit.wrapping_neg();
+        for i in 0..5 {
+            let t = mask & (a.0[i] ^ b.0[i]);
+            a.0[i] ^= t;
+            b.0[i] ^= t;
+        }
+    }
+}
+
+// Carries each limb into the next, with the carry out of the top limb multiplied by 19 and
+// added back to the bottom one.
+fn carry(mut limbs: [u128; 5]) -> [u64; 5] {
+    for i in 0..4 {
+        limbs[i + 1] += limbs[i] >> 51;
+        limbs[i] &= MASK as u128;
+    }
+    limbs[0] += (limbs[4] >> 51) * 19;
+    limbs[4] &= MASK as u128;
+    limbs[1] += limbs[0] >> 51;
+    limbs[0] &= MASK as u128;
+
+    limbs.map(|limb| limb as u64)
+}
+
+#[test]
+fn invert_test() {
+    for _ in 0..20 {
+        let a = FieldElement::from_bytes(&crate::utils::random::random_u8_32());
+        assert_eq!(a.multiply(&a.invert()).to_bytes(), FieldElement::ONE.to_bytes());
+    }
+    assert_eq!(FieldElement::ZERO.invert().to_bytes(), [0u8; 32]);
+}
+
+#[test]
+fn reduction_test() {
+    // p - 1, p and 2^255 - 1 encode as p - 1, 0 and 18
+    let mut p_minus_one = [0xffu8; 32];
+    p_minus_one[0] = 0xec;
+    p_minus_one[31] = 0x7f;
+    let mut p = p_minus_one;
+    p[0] = 0xed;
+    let mut eighteen = [0u8; 32];
+    eighteen[0] = 18;
+
+    assert_eq!(FieldElement::from_bytes(&p_minus_one).to_bytes(), p_minus_one);
+    assert_eq!(FieldElement::from_bytes(&p).to_bytes(), [0u8; 32]);
+    assert_eq!(FieldElement::from_bytes(&[0xff; 32]).to_bytes(), eighteen);
+
+    // (p - 1)^2 = 1 and (p - 1) + 1 = 0
+    let minus_one = FieldElement::from_bytes(&p_minus_one);
+    assert_eq!(minus_one.square().to_bytes(), FieldElement::ONE.to_bytes());
+    assert_eq!(minus_one.add(&FieldElement::ONE).to_bytes(), [0u8; 32]);
+    assert_eq!(FieldElement::ONE.negate().to_bytes(), p_minus_one);
+}
@@ -1,4 +1,4 @@
-﻿mod field_arithmetic;
+﻿pub mod field;
 mod curve25519;
 
 pub use curve25519::{KeyPair, Key};
\ No newline at end of file
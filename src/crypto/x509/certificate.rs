@@ -0,0 +1,453 @@
+// X.509 v3 certificates (RFC 5280). Parsing extracts what the TLS server needs and checks the
+// structure, but doesn't do path validation.
+
+use crate::crypto::der::{oid, tag, DerError, DerReader, DerWriter};
+use crate::crypto::{ed25519, p256};
+use super::error::X509Error;
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum SignatureAlgorithm {
+    Ed25519,
+    EcdsaSha256,
+    EcdsaSha384,
+    RsaSha256,
+    // Any other algorithm, with the encoded object identifier
+    Other(Vec<u8>)
+}
+
+impl SignatureAlgorithm {
+    fn from_oid(algorithm: &[u8]) -> Self {
+        match algorithm {
+            oid::ED25519 => Self::Ed25519,
+            oid::ECDSA_WITH_SHA256 => Self::EcdsaSha256,
+            oid::ECDSA_WITH_SHA384 => Self::EcdsaSha384,
+            oid::SHA256_WITH_RSA_ENCRYPTION => Self::RsaSha256,
+            other => Self::Other(other.to_vec())
+        }
+    }
+
+    fn oid(&self) -> &[u8] {
+        match self {
+            Self::Ed25519 => oid::ED25519,
+            Self::EcdsaSha256 => oid::ECDSA_WITH_SHA256,
+            Self::EcdsaSha384 => oid::ECDSA_WITH_SHA384,
+            Self::RsaSha256 => oid::SHA256_WITH_RSA_ENCRYPTION,
+            Self::Other(algorithm) => algorithm
+        }
+    }
+
+    // Reads an AlgorithmIdentifier. Parameters are skipped: they are absent for ECDSA and
+    // Ed25519, and NULL for RSA.
+    fn read(reader: &mut DerReader) -> Result<Self, X509Error> {
+        let mut sequence = reader.read_sequence()?;
+        let algorithm = Self::from_oid(sequence.read_oid()?);
+        if !sequence.is_empty() {
+            sequence.read_element()?;
+        }
+        sequence.finish()?;
+
+        Ok(algorithm)
+    }
+
+    pub(super) fn write(&self, writer: &mut DerWriter) {
+        writer.write_sequence(|sequence| {
+            sequence.write_oid(self.oid());
+            if *self == Self::RsaSha256 {
+                sequence.write_null();
+            }
+        });
+    }
+}
+
+pub enum PublicKey {
+    // boxed because the decompressed point is much larger than the other variants
+    Ed25519(Box<ed25519::VerifyingKey>),
+    P256(p256::VerifyingKey),
+    // Any other key type, with the encoded algorithm object identifier and the raw key bytes
+    Other { algorithm: Vec<u8>, key: Vec<u8> }
+}
+
+impl PublicKey {
+    // Reads a SubjectPublicKeyInfo.
+    fn read(reader: &mut DerReader) -> Result<Self, X509Error> {
+        let mut sequence = reader.read_sequence()?;
+
+        let mut algorithm_identifier = sequence.read_sequence()?;
+        let algorithm = algorithm_identifier.read_oid()?;
+        let parameters = if algorithm_identifier.is_empty() {
+            None
+        } else {
+            Some(algorithm_identifier.read_element()?)
+        };
+        algorithm_identifier.finish()?;
+
+        let key = sequence.read_bit_string()?;
+        sequence.finish()?;
+
+        match algorithm {
+            oid::ED25519 => {
+                let key: &[u8; 32] = key.try_into().map_err(|_| X509Error::InvalidPublicKey)?;
+                let key = ed25519::VerifyingKey::from_bytes(key).map_err(|_| X509Error::InvalidPublicKey)?;
+                Ok(Self::Ed25519(Box::new(key)))
+            },
+            oid::EC_PUBLIC_KEY if parameters.as_ref()
+                .is_some_and(|p| p.tag == tag::OBJECT_IDENTIFIER && p.contents == oid::PRIME256V1) => {
+                let key = p256::VerifyingKey::from_uncompressed(key).map_err(|_| X509Error::InvalidPublicKey)?;
+                Ok(Self::P256(key))
+            },
+            other => Ok(Self::Other {
+                algorithm: other.to_vec(),
+                key: key.to_vec()
+            })
+        }
+    }
+
+    pub(super) fn write(&self, writer: &mut DerWriter) {
+        writer.write_sequence(|sequence| {
+            match self {
+                Self::Ed25519(key) => {
+                    sequence.write_sequence(|algorithm| algorithm.write_oid(oid::ED25519));
+                    sequence.write_bit_string(key.as_bytes());
+                },
+                Self::P256(key) => {
+                    sequence.write_sequence(|algorithm| {
+                        algorithm.write_oid(oid::EC_PUBLIC_KEY);
+                        algorithm.write_oid(oid::PRIME256V1);
+                    });
+                    sequence.write_bit_string(key.as_uncompressed());
+                },
+                Self::Other { algorithm, key } => {
+                    sequence.write_sequence(|identifier| identifier.write_oid(algorithm));
+                    sequence.write_bit_string(key);
+                }
+            }
+        });
+    }
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct NameAttribute {
+    // the encoded object identifier of the attribute type, e.g. oid::COMMON_NAME
+    pub attribute_type: Vec<u8>,
+    pub value: String
+}
+
+// A distinguished name, such as the subject or issuer.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Name {
+    pub attributes: Vec<NameAttribute>
+}
+
+impl Name {
+    pub fn common_name(&self) -> Option<&str> {
+        self.attributes.iter()
+            .find(|attribute| attribute.attribute_type == oid::COMMON_NAME)
+            .map(|attribute| attribute.value.as_str())
+    }
+
+    fn read(reader: &mut DerReader) -> Result<Self, X509Error> {
+        let mut sequence = reader.read_sequence()?;
+        let mut attributes = vec![];
+
+        while !sequence.is_empty() {
+            let mut set = sequence.read_set()?;
+            while !set.is_empty() {
+                let mut attribute = set.read_sequence()?;
+                let attribute_type = attribute.read_oid()?.to_vec();
+                let value = attribute.read_string()?;
+                attribute.finish()?;
+
+                attributes.push(NameAttribute { attribute_type, value });
+            }
+        }
+
+        Ok(Self { attributes })
+    }
+
+    pub(super) fn write(&self, writer: &mut DerWriter) {
+        writer.write_sequence(|sequence| {
+            for attribute in &self.attributes {
+                sequence.write_set(|set| {
+                    set.write_sequence(|pair| {
+                        pair.write_oid(&attribute.attribute_type);
+                        pair.write_utf8_string(&attribute.value);
+                    });
+                });
+            }
+        });
+    }
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum SubjectAltName {
+    DnsName(String),
+    // 4 bytes for IPv4, 16 for IPv6
+    IpAddress(Vec<u8>)
+}
+
+impl SubjectAltName {
+    const DNS_NAME_TAG: u8 = tag::context_specific(2, false);
+    const IP_ADDRESS_TAG: u8 = tag::context_specific(7, false);
+
+    // Reads the GeneralNames inside the extension, skipping name types we don't use.
+    fn read_all(extension_value: &[u8]) -> Result<Vec<Self>, X509Error> {
+        let mut reader = DerReader::new(extension_value);
+        let mut sequence = reader.read_sequence()?;
+        reader.finish()?;
+
+        let mut names = vec![];
+        while !sequence.is_empty() {
+            let element = sequence.read_element()?;
+            match element.tag {
+                Self::DNS_NAME_TAG => {
+                    let name = String::from_utf8(element.contents.to_vec())
+                        .map_err(|_| DerError::InvalidString)?;
+                    names.push(Self::DnsName(name));
+                },
+                Self::IP_ADDRESS_TAG => names.push(Self::IpAddress(element.contents.to_vec())),
+                _ => {}
+            }
+        }
+
+        Ok(names)
+    }
+
+    pub(super) fn write_all(names: &[Self], writer: &mut DerWriter) {
+        writer.write_sequence(|sequence| {
+            for name in names {
+                match name {
+                    Self::DnsName(dns_name) => sequence.write(Self::DNS_NAME_TAG, dns_name.as_bytes()),
+                    Self::IpAddress(address) => sequence.write(Self::IP_ADDRESS_TAG, address)
+                }
+            }
+        });
+    }
+}
+
+pub struct Certificate {
+    raw: Vec<u8>,
+    // the signed part of the certificate, TBSCertificate
+    tbs_certificate: Vec<u8>,
+    pub version: u64,
+    // big-endian, as encoded (it may be negative in certificates from non-conforming issuers)
+    pub serial_number: Vec<u8>,
+    pub signature_algorithm: SignatureAlgorithm,
+    pub issuer: Name,
+    pub subject: Name,
+    // unix timestamps, inclusive
+    pub not_before: i64,
+    pub not_after: i64,
+    pub subject_alt_names: Vec<SubjectAltName>,
+    pub public_key: PublicKey,
+    pub signature: Vec<u8>
+}
+
+impl Certificate {
+    const VERSION_TAG: u8 = tag::context_specific(0, true);
+    const ISSUER_UNIQUE_ID_TAG: u8 = tag::context_specific(1, false);
+    const SUBJECT_UNIQUE_ID_TAG: u8 = tag::context_specific(2, false);
+    const EXTENSIONS_TAG: u8 = tag::context_specific(3, true);
+
+    pub fn from_der(der: &[u8]) -> Result<Self, X509Error> {
+        let mut reader = DerReader::new(der);
+        let mut certificate = reader.read_sequence()?;
+        reader.finish()?;
+
+        let tbs_element = certificate.read_element()?;
+        if tbs_element.tag != tag::SEQUENCE {
+            return Err(X509Error::Der(DerError::UnexpectedTag(tbs_element.tag)));
+        }
+        let signature_algorithm = SignatureAlgorithm::read(&mut certificate)?;
+        let signature = certificate.read_bit_string()?.to_vec();
+        certificate.finish()?;
+
+        let mut tbs = DerReader::new(tbs_element.contents);
+
+        // version is v1 when absent, and is encoded as one less than the version number
+        let version = match tbs.read_optional(Self::VERSION_TAG)? {
+            Some(explicit) => {
+                let mut explicit = DerReader::new(explicit);
+                let version = explicit.read_small_integer()?;
+                explicit.finish()?;
+                // checked before adding one, as the encoded value can be up to u64::MAX
+                if version > 2 {
+                    return Err(X509Error::UnsupportedVersion(version.saturating_add(1)));
+                }
+                version + 1
+            },
+            None => 1
+        };
+
+        let serial_number = tbs.read(tag::INTEGER)?.to_vec();
+        if SignatureAlgorithm::read(&mut tbs)? != signature_algorithm {
+            return Err(X509Error::SignatureAlgorithmMismatch);
+        }
+
+        let issuer = Name::read(&mut tbs)?;
+
+        let mut validity = tbs.read_sequence()?;
+        let not_before = validity.read_time()?;
+        let not_after = validity.read_time()?;
+        validity.finish()?;
+
+        let subject = Name::read(&mut tbs)?;
+        let public_key = PublicKey::read(&mut tbs)?;
+
+        tbs.read_optional(Self::ISSUER_UNIQUE_ID_TAG)?;
+        tbs.read_optional(Self::SUBJECT_UNIQUE_ID_TAG)?;
+
+        let mut subject_alt_names = vec![];
+        if let Some(extensions) = tbs.read_optional(Self::EXTENSIONS_TAG)? {
+            let mut explicit = DerReader::new(extensions);
+            let mut extensions = explicit.read_sequence()?;
+            explicit.finish()?;
+
+            while !extensions.is_empty() {
+                let mut extension = extensions.read_sequence()?;
+                let extension_id = extension.read_oid()?;
+                if extension.peek_tag() == Some(tag::BOOLEAN) {
+                    extension.read_boolean()?;
+                }
+                let value = extension.read_octet_string()?;
+                extension.finish()?;
+
+                if extension_id == oid::SUBJECT_ALT_NAME {
+                    subject_alt_names = SubjectAltName::read_all(value)?;
+                }
+            }
+        }
+        tbs.finish()?;
+
+        Ok(Self {
+            raw: der.to_vec(),
+            tbs_certificate: tbs_element.raw.to_vec(),
+            version,
+            serial_number,
+            signature_algorithm,
+            issuer,
+            subject,
+            not_before,
+            not_after,
+            subject_alt_names,
+            public_key,
+            signature
+        })
+    }
+
+    pub fn as_der(&self) -> &[u8] {
+        &self.raw
+    }
+
+    pub fn is_valid_at(&self, timestamp: i64) -> bool {
+        self.not_before <= timestamp && timestamp <= self.not_after
+    }
+
+    // Checks the certificate's signature with the issuer's public key. Only the algorithms we
+    // can produce are supported; anything else fails verification.
+    pub fn verify_signature(&self, issuer_key: &PublicKey) -> bool {
+        match (&self.signature_algorithm, issuer_key) {
+            (SignatureAlgorithm::Ed25519, PublicKey::Ed25519(key)) => {
+                match self.signature.as_slice().try_into() {
+                    Ok(signature) => key.verify(&self.tbs_certificate, signature),
+                    Err(_) => false
+                }
+            },
+            (SignatureAlgorithm::EcdsaSha256, PublicKey::P256(key)) => {
+                match p256::Signature::from_der(&self.signature) {
+                    Ok(signature) => key.verify(&self.tbs_certificate, &signature),
+                    Err(_) => false
+                }
+            },
+            _ => false
+        }
+    }
+}
+
+#[cfg(test)]
+use crate::utils::formatting::hex_to_bytes;
+
+// A self-signed P-256 certificate for example.com, generated with the Python cryptography
+// package using the RFC 6979 test key.
+#[cfg(test)]
+const EXAMPLE_CERTIFICATE: &str = "\
+    3082016130820106a00302010202021234300a06082a8648ce3d04030230283114301206035504030c0b6578\
+    616d706c652e636f6d3110300e060355040a0c074578616d706c65301e170d3234303130313030303030305a\
+    170d3235303130313030303030305a30283114301206035504030c0b6578616d706c652e636f6d3110300e06\
+    0355040a0c074578616d706c653059301306072a8648ce3d020106082a8648ce3d0301070342000460fed4ba\
+    255a9d31c961eb74c6356d68c049b8923b61fa6ce669622e60f29fb67903fe1008b8bc99a41ae9e95628bc64\
+    f2f1b20c2d7e9f5177a3c294d4462299a320301e301c0603551d1104153013820b6578616d706c652e636f6d\
+    87047f000001300a06082a8648ce3d0403020349003046022100cf4e1d3e16d9a5d2e846236056a5d9ba2e5d\
+    31f1e27ddcb2fe22d9744dd4cc12022100e6c403ce4e07421ad35092a819b0edb96b36f821c301094aa8614e\
+    9a9bd0a79b";
+
+#[test]
+fn parse_test() {
+    let der = hex_to_bytes(EXAMPLE_CERTIFICATE).unwrap();
+    let certificate = Certificate::from_der(&der).unwrap();
+
+    assert_eq!(certificate.as_der(), der.as_slice());
+    assert_eq!(certificate.version, 3);
+    assert_eq!(certificate.serial_number, vec![0x12, 0x34]);
+    assert_eq!(certificate.signature_algorithm, SignatureAlgorithm::EcdsaSha256);
+    assert_eq!(certificate.subject.common_name(), Some("example.com"));
+    assert_eq!(certificate.subject.attributes[1], NameAttribute {
+        attribute_type: oid::ORGANIZATION_NAME.to_vec(),
+        value: "Example".to_string()
+    });
+    assert_eq!(certificate.issuer, certificate.subject);
+
+    // 2024-01-01 to 2025-01-01
+    assert_eq!(certificate.not_before, 1704067200);
+    assert_eq!(certificate.not_after, 1735689600);
+    assert!(certificate.is_valid_at(1720000000));
+    assert!(!certificate.is_valid_at(1735689601));
+
+    assert_eq!(certificate.subject_alt_names, vec![
+        SubjectAltName::DnsName("example.com".to_string()),
+        SubjectAltName::IpAddress(vec![127, 0, 0, 1])
+    ]);
+
+    let PublicKey::P256(key) = &certificate.public_key else {
+        panic!("expected a P-256 key");
+    };
+    assert_eq!(key.as_uncompressed(), hex_to_bytes("04\
+        60fed4ba255a9d31c961eb74c6356d68c049b8923b61fa6ce669622e60f29fb6\
+        7903fe1008b8bc99a41ae9e95628bc64f2f1b20c2d7e9f5177a3c294d4462299").unwrap());
+
+    assert!(certificate.verify_signature(&certificate.public_key));
+}
+
+#[test]
+fn parse_invalid_test() {
+    let der = hex_to_bytes(EXAMPLE_CERTIFICATE).unwrap();
+
+    // truncated
+    assert_eq!(Certificate::from_der(&der[..der.len() - 1]).err(), Some(X509Error::Der(DerError::InvalidLength)));
+
+    // trailing data after the certificate
+    let mut trailing = der.clone();
+    trailing.push(0x00);
+    assert_eq!(Certificate::from_der(&trailing).err(), Some(X509Error::Der(DerError::TrailingData(1))));
+
+    // version 4, and the largest version the DER reader accepts, which must not overflow
+    let mut version_4 = der.clone();
+    assert_eq!(version_4[8..13], [0xa0, 0x03, 0x02, 0x01, 0x02]);
+    version_4[12] = 0x03;
+    assert_eq!(Certificate::from_der(&version_4).err(), Some(X509Error::UnsupportedVersion(4)));
+    let largest_version = hex_to_bytes(&format!("30820169\
+        3082010ea00b020900ffffffffffffffff{}", &EXAMPLE_CERTIFICATE[26..])).unwrap();
+    assert_eq!(Certificate::from_der(&largest_version).err(), Some(X509Error::UnsupportedVersion(u64::MAX)));
+
+    // the outer signature algorithm changed to ecdsa-with-SHA384
+    let mut mismatched = der.clone();
+    let outer_algorithm = der.len() - 75 - 1;
+    assert_eq!(mismatched[outer_algorithm], 0x02);
+    mismatched[outer_algorithm] = 0x03;
+    assert_eq!(Certificate::from_der(&mismatched).err(), Some(X509Error::SignatureAlgorithmMismatch));
+
+    // a changed serial number breaks the signature
+    let mut tampered = der.clone();
+    tampered[16] ^= 0x01;
+    let certificate = Certificate::from_der(&tampered).unwrap();
+    assert!(!certificate.verify_signature(&certificate.public_key));
+}
@@ -0,0 +1,30 @@
+use std::fmt::{Display, Formatter};
+use crate::crypto::der::DerError;
+
+#[derive(Debug, PartialEq)]
+pub enum X509Error {
+    // The certificate is not valid DER or doesn't follow the X.509 structure.
+    Der(DerError),
+    UnsupportedVersion(u64),
+    // The signature algorithm inside the signed part differs from the outer one.
+    SignatureAlgorithmMismatch,
+    // The key is for a known algorithm but isn't a valid key, e.g. a point not on the curve.
+    InvalidPublicKey,
+}
+
+impl Display for X509Error {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            X509Error::Der(error) => write!(f, "malformed certificate: {}", error),
+            X509Error::UnsupportedVersion(version) => write!(f, "unsupported certificate version {}", version),
+            X509Error::SignatureAlgorithmMismatch => write!(f, "certificate signature algorithms don't match"),
+            X509Error::InvalidPublicKey => write!(f, "invalid certificate public key"),
+        }
+    }
+}
+
+impl From<DerError> for X509Error {
+    fn from(error: DerError) -> Self {
+        X509Error::Der(error)
+    }
+}
@@ -0,0 +1,10 @@
+mod error;
+mod certificate;
+mod self_signed;
+
+pub use error::X509Error;
+pub use certificate::Certificate;
+#[cfg(test)]
+pub use certificate::PublicKey;
+#[cfg(test)]
+pub use self_signed::{generate_self_signed, SigningKey};
@@ -0,0 +1,151 @@
+// Generation of self-signed server certificates, so the TLS server has a certificate to
+// present before an operator installs one from a CA.
+
+use crate::crypto::der::{oid, tag, DerWriter};
+use crate::crypto::{ed25519, p256};
+use super::certificate::{Certificate, Name, NameAttribute, PublicKey, SignatureAlgorithm, SubjectAltName};
+
+pub enum SigningKey {
+    // boxed because the key holds a decompressed point, much larger than the P-256 key
+    Ed25519(Box<ed25519::SigningKey>),
+    P256(p256::SigningKey)
+}
+
+impl SigningKey {
+    fn public_key(&self) -> PublicKey {
+        match self {
+            Self::Ed25519(key) => PublicKey::Ed25519(Box::new(
+                ed25519::VerifyingKey::from_bytes(key.verifying_key().as_bytes())
+                    .expect("the key was derived from a valid signing key")
+            )),
+            Self::P256(key) => PublicKey::P256(
+                p256::VerifyingKey::from_uncompressed(key.verifying_key().as_uncompressed())
+                    .expect("the key was derived from a valid signing key")
+            )
+        }
+    }
+
+    fn signature_algorithm(&self) -> SignatureAlgorithm {
+        match self {
+            Self::Ed25519(_) => SignatureAlgorithm::Ed25519,
+            Self::P256(_) => SignatureAlgorithm::EcdsaSha256
+        }
+    }
+
+    // Signs the message in the encoding certificates use: raw for Ed25519 and DER for ECDSA.
+    fn sign(&self, message: &[u8]) -> Vec<u8> {
+        match self {
+            Self::Ed25519(key) => key.sign(message).to_vec(),
+            Self::P256(key) => key.sign(message).to_der()
+        }
+    }
+}
+
+// Creates a certificate for the key that is signed by the key itself, with the common name as
+// both subject and issuer, and valid from not_before to not_after (unix timestamps).
+pub fn generate_self_signed(
+    key: &SigningKey,
+    common_name: &str,
+    subject_alt_names: &[SubjectAltName],
+    not_before: i64,
+    not_after: i64
+) -> Certificate {
+    let name = Name {
+        attributes: vec![NameAttribute {
+            attribute_type: oid::COMMON_NAME.to_vec(),
+            value: common_name.to_string()
+        }]
+    };
+    let signature_algorithm = key.signature_algorithm();
+
+    // a positive 127 bit random serial number, as CAs must use at least 64 bits of randomness
+    let mut serial_number = crate::utils::random::random_u8_32()[..16].to_vec();
+    serial_number[0] = (serial_number[0] & 0x7f) | 0x40;
+
+    let mut tbs_certificate = DerWriter::new();
+    tbs_certificate.write_sequence(|tbs| {
+        // version 3, encoded as 2
+        tbs.write_constructed(tag::context_specific(0, true), |version| version.write_small_integer(2));
+        tbs.write_unsigned_integer(&serial_number);
+        signature_algorithm.write(tbs);
+        name.write(tbs);
+        tbs.write_sequence(|validity| {
+            validity.write_time(not_before);
+            validity.write_time(not_after);
+        });
+        name.write(tbs);
+        key.public_key().write(tbs);
+
+        tbs.write_constructed(tag::context_specific(3, true), |explicit| {
+            explicit.write_sequence(|extensions| {
+                write_extension(extensions, oid::BASIC_CONSTRAINTS, true, |value| {
+                    // cA defaults to false, so the sequence is empty
+                    value.write_sequence(|_| {});
+                });
+                write_extension(extensions, oid::KEY_USAGE, true, |value| {
+                    // digitalSignature, bit 0 of a one bit string with 7 unused bits
+                    value.write(tag::BIT_STRING, &[0x07, 0x80]);
+                });
+                write_extension(extensions, oid::EXTENDED_KEY_USAGE, false, |value| {
+                    value.write_sequence(|usages| usages.write_oid(oid::SERVER_AUTH));
+                });
+                if !subject_alt_names.is_empty() {
+                    write_extension(extensions, oid::SUBJECT_ALT_NAME, false, |value| {
+                        SubjectAltName::write_all(subject_alt_names, value);
+                    });
+                }
+            });
+        });
+    });
+    let tbs_certificate = tbs_certificate.finish();
+
+    let mut certificate = DerWriter::new();
+    certificate.write_sequence(|sequence| {
+        sequence.write_raw(&tbs_certificate);
+        signature_algorithm.write(sequence);
+        sequence.write_bit_string(&key.sign(&tbs_certificate));
+    });
+
+    Certificate::from_der(&certificate.finish()).expect("generated certificates are well formed")
+}
+
+fn write_extension(writer: &mut DerWriter, extension_id: &[u8], critical: bool, write_value: impl FnOnce(&mut DerWriter)) {
+    writer.write_sequence(|extension| {
+        extension.write_oid(extension_id);
+        if critical {
+            extension.write_boolean(true);
+        }
+
+        let mut value = DerWriter::new();
+        write_value(&mut value);
+        extension.write_octet_string(&value.finish());
+    });
+}
+
+#[test]
+fn generate_self_signed_test() {
+    let keys = [
+        SigningKey::Ed25519(Box::new(ed25519::SigningKey::generate())),
+        SigningKey::P256(p256::SigningKey::generate()),
+    ];
+    let subject_alt_names = [
+        SubjectAltName::DnsName("localhost".to_string()),
+        SubjectAltName::IpAddress(vec![127, 0, 0, 1])
+    ];
+
+    for key in keys {
+        // 2024-01-01 to 2050-01-01, which needs GeneralizedTime for the end date
+        let certificate = generate_self_signed(&key, "g-vault", &subject_alt_names, 1704067200, 2524608000);
+
+        assert_eq!(certificate.version, 3);
+        assert_eq!(certificate.serial_number.len(), 16);
+        assert_eq!(certificate.signature_algorithm, key.signature_algorithm());
+        assert_eq!(certificate.subject.common_name(), Some("g-vault"));
+        assert_eq!(certificate.issuer, certificate.subject);
+        assert_eq!(certificate.not_before, 1704067200);
+        assert_eq!(certificate.not_after, 2524608000);
+        assert_eq!(certificate.subject_alt_names, subject_alt_names);
+        assert!(certificate.verify_signature(&certificate.public_key));
+        assert!(certificate.verify_signature(&key.public_key()));
+    }
+}
@@ -16,7 +16,7 @@ impl<'a> Request<'a> {
     }
 
     pub fn respond(&mut self, response: &mut Response) {
-        self.stream.write(&response.as_bytes()).unwrap();
+        self.stream.write(response.as_bytes().expose()).unwrap();
         self.stream.flush().unwrap();
     }
 }
\ No newline at end of file
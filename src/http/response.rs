@@ -1,6 +1,8 @@
-﻿pub struct Response {
+﻿use crate::utils::secret::Secret;
+
+pub struct Response {
     status_code: Option<usize>,
-    body: Option<String>
+    body: Option<Secret<String>>
 }
 
 impl Response {
@@ -15,21 +17,27 @@ impl Response {
         self.status_code = Some(status_code)
     }
 
-    pub fn with_body(&mut self, body: &str) {
-        self.body = Some(body.to_string())
+    // The body may hold passwords, so it is taken by value and wiped when the
+    // response is dropped rather than copied into a plain String.
+    pub fn with_body(&mut self, body: Secret<String>) {
+        self.body = Some(body)
     }
 
-    pub fn as_bytes(&mut self) -> Vec<u8> {
-        let mut content = vec![];
+    pub fn as_bytes(&mut self) -> Secret<Vec<u8>> {
+        // Sized up front so the buffer never reallocates and leaves a copy of
+        // the body behind.
+        let body_length = self.body.as_ref().map_or(0, |body| body.expose().len());
+        let mut content = Secret::new(Vec::with_capacity(body_length + 128));
 
-        self.build_status_line(&mut content);
-        self.build_body(&mut content);
+        self.build_status_line(content.expose_mut());
+        self.build_body(content.expose_mut());
 
         content
     }
 
     fn build_body(&self, content: &mut Vec<u8>) {
         if let Some(body) = &self.body {
+            let body = body.expose();
             let length = body.len();
             content.extend_from_slice(b"Content-Length: ");
             content.extend_from_slice(length.to_string().as_bytes());
@@ -193,26 +193,26 @@ impl ClientHelloData {
         None
     }
 
-    const X25519_GROUP: u16 = 0x001d;
+    pub const X25519_GROUP: u16 = 0x001d;
+    pub const SECP256R1_GROUP: u16 = 0x0017;
 
-    pub fn get_x25519_public_key(&self) -> Option<[u8; 32]> {
+    // Finds the client's key exchange data for the given named group.
+    fn get_key_share(&self, group: u16) -> Option<&[u8]> {
         let key = self.get_client_key_share()?;
         let key_share_data = &key.extension_data;
 
-        // Walk through all the key shares
-        let mut pos = 0;
+        // Skip the 2 byte length of the client_shares list, then walk through all the key shares
+        let mut pos = 2;
         while pos + 4 <= key_share_data.len() {
-            let group = ((key_share_data[pos] as u16) << 8) | key_share_data[pos + 1] as u16;
+            let share_group = ((key_share_data[pos] as u16) << 8) | key_share_data[pos + 1] as u16;
             let length = ((key_share_data[pos + 2] as u16) << 8) | key_share_data[pos + 3] as u16;
 
-            if group == Self::X25519_GROUP {
-                if pos + 4 + length as usize > key_share_data.len() || length != 32 {
+            if share_group == group {
+                if pos + 4 + length as usize > key_share_data.len() {
                     return None;
                 }
 
-                let mut public_key = [0u8; 32];
-                public_key.copy_from_slice(&key_share_data[pos + 4..pos + 4 + 32]);
-                return Some(public_key);
+                return Some(&key_share_data[pos + 4..pos + 4 + length as usize]);
             }
 
             pos += 4 + length as usize;
@@ -220,6 +220,21 @@ impl ClientHelloData {
 
         None
     }
+
+    pub fn get_x25519_public_key(&self) -> Option<[u8; 32]> {
+        let key_share = self.get_key_share(Self::X25519_GROUP)?;
+        key_share.try_into().ok()
+    }
+
+    // The uncompressed point 0x04 || x || y; the point itself is validated by crypto::p256.
+    pub fn get_secp256r1_public_key(&self) -> Option<&[u8]> {
+        let key_share = self.get_key_share(Self::SECP256R1_GROUP)?;
+        if key_share.len() != 65 {
+            return None;
+        }
+
+        Some(key_share)
+    }
 }
 
 pub struct ServerHelloData {
@@ -250,4 +265,31 @@ impl ServerHelloData {
     }
 }
 
+#[test]
+fn key_share_test() {
+    // client_shares list holding a 65 byte secp256r1 share followed by a 32 byte x25519 share
+    let mut extension_data = vec![0x00, 4 + 65 + 4 + 32];
+    extension_data.extend_from_slice(&[0x00, 0x17, 0x00, 65, 0x04]);
+    extension_data.extend_from_slice(&[0xaa; 64]);
+    extension_data.extend_from_slice(&[0x00, 0x1d, 0x00, 32]);
+    extension_data.extend_from_slice(&[0xbb; 32]);
+
+    let client_hello = ClientHelloData {
+        protocol_version: 0x0303,
+        random: [0; 32],
+        session_id: vec![],
+        cipher_suites: vec![0x1301],
+        compression_methods: vec![0],
+        extensions: vec![Extension {
+            extension_type: 0x0033,
+            extension_data_length: extension_data.len() as u16,
+            extension_data
+        }]
+    };
+
+    assert_eq!(client_hello.get_x25519_public_key(), Some([0xbb; 32]));
+    let secp256r1_key = client_hello.get_secp256r1_public_key().unwrap();
+    assert_eq!(secp256r1_key[0], 0x04);
+    assert_eq!(&secp256r1_key[1..], &[0xaa; 64]);
+}
 
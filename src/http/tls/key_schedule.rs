@@ -0,0 +1,107 @@
+﻿// Helpers for the TLS 1.3 key schedule (RFC 8446 section 7.1), which derives every handshake
+// and traffic secret from the (EC)DHE shared secret using HKDF.
+
+use std::fmt::{Display, Formatter};
+use crate::crypto::hkdf::hkdf::{self, HkdfError};
+use crate::crypto::sha256::sha256::Sha256;
+use crate::utils::secret::Secret;
+
+#[derive(Debug, PartialEq)]
+pub enum KeyScheduleError {
+    Hkdf(HkdfError),
+    // "tls13 " + label is longer than 255 bytes.
+    LabelTooLong(usize),
+    ContextTooLong(usize),
+}
+
+impl Display for KeyScheduleError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            KeyScheduleError::Hkdf(error) => write!(f, "{}", error),
+            KeyScheduleError::LabelTooLong(length) => write!(f, "HKDF label of {} bytes is longer than 255 bytes", length),
+            KeyScheduleError::ContextTooLong(length) => write!(f, "HKDF context of {} bytes is longer than 255 bytes", length),
+        }
+    }
+}
+
+impl From<HkdfError> for KeyScheduleError {
+    fn from(error: HkdfError) -> Self {
+        KeyScheduleError::Hkdf(error)
+    }
+}
+
+// HKDF-Expand-Label(Secret, Label, Context, Length) = HKDF-Expand(Secret, HkdfLabel, Length)
+//
+// struct {
+//     uint16 length = Length;
+//     opaque label<7..255> = "tls13 " + Label;
+//     opaque context<0..255> = Context;
+// } HkdfLabel;
+pub fn hkdf_expand_label(secret: &[u8], label: &str, context: &[u8], length: u16) -> Result<Secret<Vec<u8>>, KeyScheduleError> {
+    let full_label_length = 6 + label.len();
+    if full_label_length > 255 {
+        return Err(KeyScheduleError::LabelTooLong(full_label_length));
+    }
+
+    if context.len() > 255 {
+        return Err(KeyScheduleError::ContextTooLong(context.len()));
+    }
+
+    let mut hkdf_label = Vec::with_capacity(4 + full_label_length + context.len());
+    hkdf_label.extend_from_slice(&length.to_be_bytes());
+    hkdf_label.push(full_label_length as u8);
+    hkdf_label.extend_from_slice(b"tls13 ");
+    hkdf_label.extend_from_slice(label.as_bytes());
+    hkdf_label.push(context.len() as u8);
+    hkdf_label.extend_from_slice(context);
+
+    Ok(hkdf::expand(secret, &hkdf_label, length as usize)?)
+}
+
+// Derive-Secret(Secret, Label, Messages) = HKDF-Expand-Label(Secret, Label, Transcript-Hash(Messages), Hash.length)
+//
+// The transcript is the running hash of the handshake messages so far. It is cloned, so the
+// caller can keep adding messages to it afterwards.
+pub fn derive_secret(secret: &[u8], label: &str, transcript: &Sha256) -> Result<Secret<[u8; hkdf::HASH_LENGTH]>, KeyScheduleError> {
+    let transcript_hash = transcript.clone().finalize();
+    let derived = hkdf_expand_label(secret, label, &transcript_hash, hkdf::HASH_LENGTH as u16)?;
+
+    let mut result = Secret::new([0u8; hkdf::HASH_LENGTH]);
+    result.expose_mut().copy_from_slice(derived.expose());
+    Ok(result)
+}
+
+#[cfg(test)]
+use crate::utils::formatting::hex_to_bytes;
+
+#[test]
+fn rfc8448_key_schedule_test() {
+    // RFC 8448 section 3, "Simple 1-RTT Handshake"
+    let early_secret = hkdf::extract(&[0u8; 32], &[0u8; 32]);
+    assert_eq!(early_secret.expose().to_vec(), hex_to_bytes("33ad0a1c607ec03b09e6cd9893680ce210adf300aa1f2660e1b22e10f170f92a").unwrap());
+
+    let derived = derive_secret(early_secret.expose(), "derived", &Sha256::new()).unwrap();
+    assert_eq!(derived.expose().to_vec(), hex_to_bytes("6f2615a108c702c5678f54fc9dbab69716c076189c48250cebeac3576c3611ba").unwrap());
+
+    let shared_secret = hex_to_bytes("8bd4054fb55b9d63fdfbacf9f04b9f0d35e6d63f537563efd46272900f89492d").unwrap();
+    let handshake_secret = hkdf::extract(derived.expose(), &shared_secret);
+    assert_eq!(handshake_secret.expose().to_vec(), hex_to_bytes("1dc826e93606aa6fdc0aadc12f741b01046aa6b99f691ed221a9f0ca043fbeac").unwrap());
+
+    // server handshake traffic keys
+    let server_handshake_traffic_secret = hex_to_bytes("b67b7d690cc16c4e75e54213cb2d37b4e9c912bcded9105d42befd59d391ad38").unwrap();
+    let key = hkdf_expand_label(&server_handshake_traffic_secret, "key", b"", 16).unwrap();
+    assert_eq!(key.expose(), &hex_to_bytes("3fce516009c21727d0f2e4e86ee403bc").unwrap());
+
+    let iv = hkdf_expand_label(&server_handshake_traffic_secret, "iv", b"", 12).unwrap();
+    assert_eq!(iv.expose(), &hex_to_bytes("5d313eb2671276ee13000b30").unwrap());
+}
+
+#[test]
+fn hkdf_expand_label_limits_test() {
+    let secret = [0x01; hkdf::HASH_LENGTH];
+
+    assert_eq!(hkdf_expand_label(&secret, &"a".repeat(250), b"", 16).err(), Some(KeyScheduleError::LabelTooLong(256)));
+    assert_eq!(hkdf_expand_label(&secret, "key", &[0; 256], 16).err(), Some(KeyScheduleError::ContextTooLong(256)));
+    assert_eq!(hkdf_expand_label(&secret, "key", b"", u16::MAX).err(),
+               Some(KeyScheduleError::Hkdf(HkdfError::OutputTooLong(u16::MAX as usize))));
+}
@@ -1,4 +1,5 @@
 ﻿pub mod tls;
 mod records;
 mod handshakes;
-mod extensions;
\ No newline at end of file
+mod extensions;
+mod key_schedule;
\ No newline at end of file
@@ -1,9 +1,10 @@
 ﻿use std::io::Write;
 use std::net::TcpStream;
-use crate::crypto::x25519::Key;
+use crate::crypto::{p256, x25519};
 use crate::http::tls::extensions::Extension;
 use crate::http::tls::handshakes::{ClientHelloData, ServerHelloData};
 use crate::utils::formatting::bytes_to_hex;
+use crate::utils::secret::Secret;
 use super::records::Record;
 use super::handshakes::HandshakeMessageType;
 
@@ -12,29 +13,75 @@ pub fn do_tls(buffer: &[u8], stream: &mut TcpStream) {
     if let Record::Handshake(handshake_message) = Record::parse(buffer).unwrap() {
         if let HandshakeMessageType::ClientHello(client_hello_data) = handshake_message {
 
-            // step 2. Build and respond with ServerHello.
-            let (server_hello, server_private_key) = build_server_hello(&client_hello_data);
+            // step 2. Build the ServerHello.
+            let Some((server_hello, server_private_key)) = build_server_hello(&client_hello_data) else {
+                println!("no cipher suite or key share in common with the client");
+                send_alert(stream, HANDSHAKE_FAILURE);
+                return;
+            };
+
+            // step 3. Calculate shared secret from server private and client public, before
+            // answering so that a bad client key gets an alert rather than a ServerHello.
+            let _shared_secret = match server_private_key.shared_secret(&client_hello_data) {
+                Ok(shared_secret) => shared_secret,
+                Err(error) => {
+                    println!("could not compute shared secret: {}", error);
+                    send_alert(stream, ILLEGAL_PARAMETER);
+                    return;
+                }
+            };
+
+            // step 4. Respond with the ServerHello.
             let hello_bytes = server_hello.into_bytes();
             println!("Sending ServerHello: \n{}", bytes_to_hex(&hello_bytes, 50, ","));
             stream.write(&hello_bytes).unwrap();
-
-            // step 3. Calculate shared secret from server private and client public
-            let client_public_key = client_hello_data.get_x25519_public_key()
-                .expect("could not find client x25519 key");
-            println!("client public key length: {}", client_public_key.len());
-            println!("key value: \n{}", bytes_to_hex(&client_public_key, 32, ","));
         }
     }
 }
 
-fn build_server_hello(client_hello_data: &ClientHelloData) -> (Record, Key) {
-    let mut server_keypair = crate::crypto::x25519::KeyPair::generate();
+// Alert descriptions from RFC 8446 section 6
+const HANDSHAKE_FAILURE: u8 = 40;
+const ILLEGAL_PARAMETER: u8 = 47;
+
+// Sends a fatal alert record, which ends the handshake.
+fn send_alert(stream: &mut TcpStream, description: u8) {
+    // type - alert, legacy version TLS 1.2, length 2, level fatal
+    let alert = [0x15, 0x03, 0x03, 0x00, 0x02, 0x02, description];
+    stream.write(&alert).unwrap();
+}
+
+// The server's private key for whichever key exchange group was negotiated
+enum ServerPrivateKey {
+    X25519(x25519::Key),
+    Secp256r1(p256::PrivateKey)
+}
 
+impl ServerPrivateKey {
+    fn shared_secret(&self, client_hello_data: &ClientHelloData) -> Result<Secret<Vec<u8>>, &'static str> {
+        match self {
+            Self::X25519(private_key) => {
+                let client_public_key = client_hello_data.get_x25519_public_key()
+                    .ok_or("could not find client x25519 key")?;
+                let shared = x25519::Key::create_shared(&x25519::Key::from_bytes(&client_public_key), private_key)
+                    .map_err(|_| "client x25519 key is a low order point")?;
+                Ok(Secret::new(shared.to_vec()))
+            },
+            Self::Secp256r1(private_key) => {
+                let client_public_key = client_hello_data.get_secp256r1_public_key()
+                    .ok_or("could not find client secp256r1 key")?;
+                let client_public_key = p256::PublicKey::from_uncompressed(client_public_key)
+                    .map_err(|_| "client secp256r1 key is not a valid point")?;
+                Ok(Secret::new(private_key.diffie_hellman(&client_public_key).expose().to_vec()))
+            }
+        }
+    }
+}
+
+fn build_server_hello(client_hello_data: &ClientHelloData) -> Option<(Record, ServerPrivateKey)> {
     let legacy_version: [u8; 2] = 0x0303u16.to_be_bytes(); // TLS 1.2
     let random = crate::utils::random::random_u8_32();
     let legacy_session_id_echo = client_hello_data.session_id.clone();
-    let cipher_suite = select_cipher_suite(&client_hello_data.cipher_suites)
-        .expect("no compatible cipher suite found");
+    let cipher_suite = select_cipher_suite(&client_hello_data.cipher_suites)?;
 
     let supported_versions_data = 0x0304u16.to_be_bytes().to_vec();
     let supported_versions_extension = Extension {
@@ -43,12 +90,14 @@ fn build_server_hello(client_hello_data: &ClientHelloData) -> (Record, Key) {
         extension_data: supported_versions_data
     };
 
+    let (group, server_public_key, server_private_key) = select_key_share(client_hello_data)?;
+
     let key_share_extension_data = {
         let mut result = vec![];
-        result.extend_from_slice(&0x001du16.to_be_bytes()); // assigned value of x25519
-        result.extend_from_slice(&0x0020u16.to_be_bytes()); // 32 bytes of public key data follows
-        result.extend_from_slice(&server_keypair.public.to_vec());
-        println!("Server public key: \n{}", bytes_to_hex(&server_keypair.public.to_vec(), 32, ","));
+        result.extend_from_slice(&group.to_be_bytes());
+        result.extend_from_slice(&(server_public_key.len() as u16).to_be_bytes());
+        result.extend_from_slice(&server_public_key);
+        println!("Server public key: \n{}", bytes_to_hex(&server_public_key, 32, ","));
         result
     };
 
@@ -70,7 +119,25 @@ fn build_server_hello(client_hello_data: &ClientHelloData) -> (Record, Key) {
     };
 
     let message_type = HandshakeMessageType::ServerHello(data);
-    (Record::Handshake(message_type), server_keypair.private)
+    Some((Record::Handshake(message_type), server_private_key))
+}
+
+// Picks the key exchange group from the client's key shares, preferring x25519 over secp256r1,
+// and generates the server's key pair for it.
+fn select_key_share(client_hello_data: &ClientHelloData) -> Option<(u16, Vec<u8>, ServerPrivateKey)> {
+    if client_hello_data.get_x25519_public_key().is_some() {
+        let server_keypair = x25519::KeyPair::generate();
+        let public_key = server_keypair.public.to_vec();
+        return Some((ClientHelloData::X25519_GROUP, public_key, ServerPrivateKey::X25519(server_keypair.private)));
+    }
+
+    if client_hello_data.get_secp256r1_public_key().is_some() {
+        let server_keypair = p256::KeyPair::generate();
+        let public_key = server_keypair.public.to_vec();
+        return Some((ClientHelloData::SECP256R1_GROUP, public_key, ServerPrivateKey::Secp256r1(server_keypair.private)));
+    }
+
+    None
 }
 
 fn select_cipher_suite(client_suites: &[u16]) -> Option<[u8; 2]> {
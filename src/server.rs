@@ -7,6 +7,7 @@ use crate::http;
 use crate::http::{Request, Response};
 use crate::storage::Repository;
 use crate::utils::formatting::bytes_to_hex;
+use crate::utils::secret::Secret;
 
 pub fn start(host: &str, port: usize, static_content_path: &str) {
     let full_address = format!("{}:{}", host, port);
@@ -95,23 +96,35 @@ fn handle_api_call(request: &mut Request) {
         "/api/password" => {
             response.with_status(200);
 
-            let mut body = String::new();
-            body.push_str("[");
+            // Each entry is written straight into the pre-sized body, so no
+            // unwiped copies of a password are left behind by temporaries or
+            // by the String growing.
+            const ENTRY_TEMPLATE_LENGTH: usize = r#"{"id": "", "service_name": "", "password_text": ""},"#.len();
             let passwords = repo.get_all();
+            let capacity = 2 + passwords.iter()
+                .map(|password| ENTRY_TEMPLATE_LENGTH + password.id.to_string().len()
+                    + password.service_name.len() + password.password_text.expose().len())
+                .sum::<usize>();
+
+            let mut body = Secret::new(String::with_capacity(capacity));
+            let text = body.expose_mut();
+            text.push('[');
             for (i, password) in passwords.iter().enumerate() {
-                let json = format!(r#"{{"id": "{}", "service_name": "{}", "password_text": "{}"}}"#,
-                                   password.id,
-                                   password.service_name,
-                                   password.password_text);
-                body.push_str(&json);
+                text.push_str(r#"{"id": ""#);
+                text.push_str(&password.id.to_string());
+                text.push_str(r#"", "service_name": ""#);
+                text.push_str(&password.service_name);
+                text.push_str(r#"", "password_text": ""#);
+                text.push_str(password.password_text.expose());
+                text.push_str(r#""}"#);
 
                 if i != passwords.len() - 1 {
-                    body.push(',');
+                    text.push(',');
                 }
             }
-            body.push_str("]");
+            text.push(']');
 
-            response.with_body(&body);
+            response.with_body(body);
         },
         _ => {
             response.with_status(404);
@@ -2,6 +2,7 @@
 use std::fs::File;
 use std::path::Path;
 use crate::storage::types::Password;
+use crate::utils::secret::Secret;
 
 const FILE_NAME: &str = "vault";
 const FILE_DIRECTORY: &str = "./data";
@@ -17,11 +18,13 @@ impl Client {
     pub fn read_all(&self) -> Vec<Password> {
         let mut passwords: Vec<Password> = vec![];
 
-        for line in fs::read_to_string(build_file_path()).unwrap().lines() {
+        // the file holds every password in plain text, so wipe it once parsed
+        let contents = Secret::new(fs::read_to_string(build_file_path()).unwrap());
+        for line in contents.expose().lines() {
             let fields = line.split(",").collect::<Vec<&str>>();
             let id = fields[0].parse::<usize>().unwrap();
             let service_name = fields[1].to_string();
-            let password_text = fields[2].to_string();
+            let password_text = Secret::new(fields[2].to_string());
 
             let password = Password {
                 id,
@@ -1,5 +1,7 @@
-﻿pub struct Password {
+﻿use crate::utils::secret::Secret;
+
+pub struct Password {
     pub id: usize,
     pub service_name: String,
-    pub password_text: String,
+    pub password_text: Secret<String>,
 }
\ No newline at end of file
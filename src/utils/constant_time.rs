@@ -0,0 +1,16 @@
+// Compares two byte slices without returning early on the first difference, so the time taken
+// doesn't reveal how many leading bytes matched. Used when checking MACs and authentication tags.
+// The length of the slices is not treated as secret.
+pub fn compare(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+
+    let mut difference = 0u8;
+    for (x, y) in a.iter().zip(b.iter()) {
+        difference |= x ^ y;
+    }
+
+    // black_box stops the compiler from turning the accumulation above back into an early exit
+    std::hint::black_box(difference) == 0
+}
@@ -18,4 +18,20 @@
     };
 
     hex_output
-}
\ No newline at end of file
+}
+
+// Parses a string of hex digits (no separators) into bytes.
+pub fn hex_to_bytes(hex: &str) -> Result<Vec<u8>, &'static str> {
+    if !hex.is_ascii() {
+        return Err("Hex string contains a non-hex digit");
+    }
+
+    if !hex.len().is_multiple_of(2) {
+        return Err("Hex string has an odd number of digits");
+    }
+
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).map_err(|_| "Hex string contains a non-hex digit"))
+        .collect()
+}
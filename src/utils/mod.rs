@@ -1,2 +1,7 @@
 ﻿pub mod random;
-pub mod formatting;
\ No newline at end of file
+pub mod formatting;
+pub mod constant_time;
+pub mod zeroize;
+pub mod secret;
+#[cfg(test)]
+pub mod test_vectors;
@@ -1,18 +1,128 @@
-﻿use std::time::{SystemTime, UNIX_EPOCH};
+﻿// A cryptographically secure random number generator.
+//
+// Each thread has its own generator, seeded with 32 bytes from the operating system the first
+// time it is used. Output is produced by ChaCha20 keyed with the seed, and the first 32 bytes
+// of keystream from every request replace the key ("fast key erasure"), so a generator's
+// state can't be used to recover anything it produced earlier.
 
-pub fn random_u8_32() -> [u8; 32] {
-    // is this insecure? yes. do I care? absolutely not. i just want to finish this.
-    // todo: look into how secure crypto rng works.
+use std::cell::RefCell;
+use crate::crypto::chacha20poly1305::chacha20;
+use super::secret::Secret;
+use super::zeroize::zeroize;
 
+pub fn random_u8_32() -> [u8; 32] {
     let mut result = [0u8; 32];
+    fill_bytes(&mut result);
+    result
+}
+
+// Fills the buffer with random bytes from the current thread's generator.
+pub fn fill_bytes(dest: &mut [u8]) {
+    THREAD_RNG.with(|rng| {
+        rng.borrow_mut()
+            .get_or_insert_with(|| ChaChaRng::from_seed(os_seed()))
+            .fill_bytes(dest)
+    })
+}
 
-    let now = SystemTime::now()
-        .duration_since(UNIX_EPOCH)
-        .unwrap()
-        .as_nanos();
+// Replaces the current thread's generator with a deterministic one, so tests that rely on
+// random values (e.g. key generation) produce the same output on every run.
+#[cfg(test)]
+pub fn seed_thread_rng(seed: [u8; 32]) {
+    THREAD_RNG.with(|rng| *rng.borrow_mut() = Some(ChaChaRng::from_seed(seed)));
+}
 
-    for i in 0..16 {
-        result[i] = ((now >> (i * 8)) & 0xFF) as u8;
+thread_local! {
+    static THREAD_RNG: RefCell<Option<ChaChaRng>> = const { RefCell::new(None) };
+}
+
+pub struct ChaChaRng {
+    key: Secret<[u8; chacha20::KEY_LENGTH]>
+}
+
+impl ChaChaRng {
+    pub fn from_seed(seed: [u8; 32]) -> Self {
+        Self {
+            key: Secret::new(seed)
+        }
     }
-    result
-}
\ No newline at end of file
+
+    pub fn fill_bytes(&mut self, dest: &mut [u8]) {
+        // a fresh key is used for every request, so the nonce can stay fixed
+        let nonce = [0u8; chacha20::NONCE_LENGTH];
+
+        let mut keystream = vec![0u8; chacha20::KEY_LENGTH + dest.len()];
+        chacha20::apply_keystream(self.key.expose(), 0, &nonce, &mut keystream);
+
+        self.key.expose_mut().copy_from_slice(&keystream[..chacha20::KEY_LENGTH]);
+        dest.copy_from_slice(&keystream[chacha20::KEY_LENGTH..]);
+
+        zeroize(&mut keystream);
+    }
+}
+
+#[cfg(unix)]
+fn os_seed() -> [u8; 32] {
+    use std::io::Read;
+
+    let mut seed = [0u8; 32];
+    std::fs::File::open("/dev/urandom")
+        .and_then(|mut file| file.read_exact(&mut seed))
+        .expect("could not read from /dev/urandom");
+
+    seed
+}
+
+#[cfg(windows)]
+fn os_seed() -> [u8; 32] {
+    #[link(name = "bcrypt")]
+    extern "system" {
+        fn BCryptGenRandom(algorithm: *mut std::ffi::c_void, buffer: *mut u8, length: u32, flags: u32) -> i32;
+    }
+
+    const BCRYPT_USE_SYSTEM_PREFERRED_RNG: u32 = 0x00000002;
+
+    let mut seed = [0u8; 32];
+    // SAFETY: the buffer pointer and length describe a valid, writable array, and a null
+    // algorithm handle is allowed together with BCRYPT_USE_SYSTEM_PREFERRED_RNG
+    let status = unsafe {
+        BCryptGenRandom(std::ptr::null_mut(), seed.as_mut_ptr(), seed.len() as u32, BCRYPT_USE_SYSTEM_PREFERRED_RNG)
+    };
+    assert_eq!(status, 0, "BCryptGenRandom failed");
+
+    seed
+}
+
+#[test]
+fn seeded_rng_test() {
+    let mut first = ChaChaRng::from_seed([0x42; 32]);
+    let mut second = ChaChaRng::from_seed([0x42; 32]);
+
+    let mut a = [0u8; 100];
+    let mut b = [0u8; 100];
+    first.fill_bytes(&mut a);
+    second.fill_bytes(&mut b);
+    assert_eq!(a, b);
+
+    // the key changes after every request, so the next output is different
+    first.fill_bytes(&mut b);
+    assert_ne!(a, b);
+
+    // the first output is the ChaCha20 keystream after the 32 bytes used as the next key
+    let mut expected = [0u8; 132];
+    chacha20::apply_keystream(&[0x42; 32], 0, &[0u8; 12], &mut expected);
+    assert_eq!(a, expected[32..]);
+}
+
+#[test]
+fn thread_rng_test() {
+    let a = random_u8_32();
+    let b = random_u8_32();
+    assert_ne!(a, b);
+    assert_ne!(a, [0u8; 32]);
+
+    seed_thread_rng([0x01; 32]);
+    let seeded = random_u8_32();
+    seed_thread_rng([0x01; 32]);
+    assert_eq!(random_u8_32(), seeded);
+}
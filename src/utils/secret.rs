@@ -0,0 +1,72 @@
+// A wrapper for keys, derived secrets and passwords.
+//
+// The value is wiped when the wrapper is dropped, it never shows up in Debug output, and it has
+// to be read through `expose`, which makes every use of the secret easy to find. Comparisons
+// go through `ct_eq`, which doesn't stop at the first differing byte.
+
+use std::fmt::{Debug, Formatter};
+use super::constant_time;
+use super::zeroize::Zeroize;
+
+pub struct Secret<T: Zeroize>(T);
+
+impl<T: Zeroize> Secret<T> {
+    pub fn new(value: T) -> Self {
+        Self(value)
+    }
+
+    pub fn expose(&self) -> &T {
+        &self.0
+    }
+
+    pub fn expose_mut(&mut self) -> &mut T {
+        &mut self.0
+    }
+}
+
+impl<T: Zeroize + AsRef<[u8]>> Secret<T> {
+    // The lengths are not treated as secret.
+    pub fn ct_eq(&self, other: &Secret<T>) -> bool {
+        constant_time::compare(self.0.as_ref(), other.0.as_ref())
+    }
+}
+
+impl<T: Zeroize> From<T> for Secret<T> {
+    fn from(value: T) -> Self {
+        Self(value)
+    }
+}
+
+impl<T: Zeroize + Clone> Clone for Secret<T> {
+    fn clone(&self) -> Self {
+        Self(self.0.clone())
+    }
+}
+
+impl<T: Zeroize> Debug for Secret<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Secret([REDACTED])")
+    }
+}
+
+impl<T: Zeroize> Drop for Secret<T> {
+    fn drop(&mut self) {
+        self.0.zeroize();
+    }
+}
+
+#[test]
+fn secret_test() {
+    let secret = Secret::new(String::from("hunter2"));
+    assert_eq!(format!("{:?}", secret), "Secret([REDACTED])");
+    assert_eq!(secret.expose(), "hunter2");
+
+    assert!(secret.ct_eq(&Secret::new(String::from("hunter2"))));
+    assert!(!secret.ct_eq(&Secret::new(String::from("hunter3"))));
+    assert!(!secret.ct_eq(&Secret::new(String::from("hunter"))));
+
+    let mut key = Secret::new([0x42u8; 32]);
+    key.expose_mut()[0] = 0;
+    assert!(key.ct_eq(&key.clone()));
+}
+
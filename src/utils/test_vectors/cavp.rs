@@ -0,0 +1,163 @@
+// Reader for the response (.rsp) files of the NIST Cryptographic Algorithm Validation Program.
+//
+// A file is a list of sections. Each starts with bracketed parameters such as `[L = 32]` or
+// `[ENCRYPT]`, followed by records of `NAME = value` lines separated by blank lines. Lines
+// starting with # are comments, and a bare word in a record, like `FAIL`, is a flag.
+
+use super::read_vector_file;
+use crate::utils::formatting::hex_to_bytes;
+
+pub struct Section {
+    parameters: Vec<(String, String)>,
+    pub records: Vec<Record>
+}
+
+impl Section {
+    pub fn parameter(&self, name: &str) -> Option<&str> {
+        self.parameters.iter().find(|(key, _)| key == name).map(|(_, value)| value.as_str())
+    }
+
+    pub fn parameter_usize(&self, name: &str) -> Option<usize> {
+        self.parameter(name).map(|value| value.parse().unwrap_or_else(|_| panic!("[{name} = {value}] is not a number")))
+    }
+}
+
+pub struct Record {
+    fields: Vec<(String, String)>,
+    // file and line, for failure messages
+    location: String
+}
+
+impl Record {
+    pub fn location(&self) -> &str {
+        &self.location
+    }
+
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.fields.iter().find(|(key, _)| key == name).map(|(_, value)| value.as_str())
+    }
+
+    pub fn has(&self, name: &str) -> bool {
+        self.get(name).is_some()
+    }
+
+    pub fn hex(&self, name: &str) -> Vec<u8> {
+        let value = self.get(name).unwrap_or_else(|| panic!("{}: no {name}", self.location));
+        hex_to_bytes(value).unwrap_or_else(|error| panic!("{}: {name}: {error}", self.location))
+    }
+
+    pub fn usize(&self, name: &str) -> usize {
+        let value = self.get(name).unwrap_or_else(|| panic!("{}: no {name}", self.location));
+        value.parse().unwrap_or_else(|_| panic!("{}: {name} = {value} is not a number", self.location))
+    }
+}
+
+// Loads tests/vectors/cavp/<name>.
+pub fn load(name: &str) -> Vec<Section> {
+    parse(name, &read_vector_file(&format!("cavp/{name}")))
+}
+
+fn parse(name: &str, text: &str) -> Vec<Section> {
+    let mut sections = Vec::new();
+    let mut section = Section { parameters: Vec::new(), records: Vec::new() };
+    let mut record = Vec::new();
+    let mut record_start = 0;
+
+    // a trailing empty line closes the last record
+    for (index, line) in text.lines().chain([""]).enumerate() {
+        let line = line.trim();
+
+        if line.starts_with('#') {
+            continue;
+        }
+
+        if line.is_empty() {
+            if !record.is_empty() {
+                let location = format!("{name}:{record_start}");
+                section.records.push(Record { fields: std::mem::take(&mut record), location });
+            }
+            continue;
+        }
+
+        if let Some(parameter) = line.strip_prefix('[').and_then(|line| line.strip_suffix(']')) {
+            // parameters after records belong to a new section
+            if !section.records.is_empty() {
+                sections.push(std::mem::replace(&mut section, Section { parameters: Vec::new(), records: Vec::new() }));
+            }
+            section.parameters.push(split_field(parameter));
+            continue;
+        }
+
+        if record.is_empty() {
+            record_start = index + 1;
+        }
+        record.push(split_field(line));
+    }
+
+    if !section.records.is_empty() || !section.parameters.is_empty() {
+        sections.push(section);
+    }
+
+    sections
+}
+
+// Checks a hash against a ShortMsg or LongMsg file of the SHA validation system. Len is in
+// bits, and an empty message is written as a single 00 byte.
+pub fn check_message_digests(name: &str, hash: impl Fn(&[u8]) -> Vec<u8>) {
+    let mut count = 0;
+    for record in load(name).iter().flat_map(|section| &section.records) {
+        let message = record.hex("Msg");
+        let message = &message[..record.usize("Len") / 8];
+        assert_eq!(hash(message), record.hex("MD"), "{}", record.location());
+        count += 1;
+    }
+    assert!(count > 0, "{name} has no records");
+}
+
+// Checks a hash against a Monte Carlo file of the SHA validation system (SHAVS section 6.4),
+// where each checkpoint is the seed for the next 1000 chained hashes.
+pub fn check_monte_carlo(name: &str, hash: impl Fn(&[u8]) -> Vec<u8>) {
+    let sections = load(name);
+    let records = &sections[0].records;
+    let mut seed = records[0].hex("Seed");
+
+    for record in &records[1..] {
+        let mut digests = [seed.clone(), seed.clone(), seed];
+        for _ in 0..1000 {
+            let next = hash(&[digests[0].as_slice(), &digests[1], &digests[2]].concat());
+            digests = [digests[1].clone(), digests[2].clone(), next];
+        }
+        let [_, _, digest] = digests;
+        assert_eq!(digest, record.hex("MD"), "{}", record.location());
+        seed = digest;
+    }
+    assert_eq!(records.len(), 101, "{name} should have a seed and 100 checkpoints");
+}
+
+
+// `NAME = value`, or a bare flag with an empty value
+fn split_field(text: &str) -> (String, String) {
+    match text.split_once('=') {
+        Some((key, value)) => (key.trim().to_string(), value.trim().to_string()),
+        None => (text.trim().to_string(), String::new())
+    }
+}
+
+#[test]
+fn parse_test() {
+    let text = "#  comment\r\n\r\n[L = 32]\r\n\r\nLen = 0\r\nMsg = 00\r\n\r\nLen = 8\r\nMsg = d3\r\n\r\n\
+                [DECRYPT]\r\n[Taglen = 128]\r\n\r\nCount = 0\r\nCT = \r\nFAIL\r\n";
+    let sections = parse("test.rsp", text);
+
+    assert_eq!(sections.len(), 2);
+    assert_eq!(sections[0].parameter_usize("L"), Some(32));
+    assert_eq!(sections[0].records.len(), 2);
+    assert_eq!(sections[0].records[1].hex("Msg"), vec![0xd3]);
+    assert_eq!(sections[0].records[1].location(), "test.rsp:8");
+
+    assert_eq!(sections[1].parameter("DECRYPT"), Some(""));
+    assert_eq!(sections[1].parameter_usize("Taglen"), Some(128));
+    assert_eq!(sections[1].records[0].hex("CT"), Vec::<u8>::new());
+    assert!(sections[1].records[0].has("FAIL"));
+    assert!(!sections[1].records[0].has("PT"));
+}
@@ -0,0 +1,10 @@
+// Loaders for the test vector files under tests/vectors, which come in the formats of the NIST
+// CAVP response files and of Project Wycheproof. Only built for tests.
+
+pub mod cavp;
+pub mod wycheproof;
+
+fn read_vector_file(path: &str) -> String {
+    let full_path = format!("{}/tests/vectors/{path}", env!("CARGO_MANIFEST_DIR"));
+    std::fs::read_to_string(&full_path).unwrap_or_else(|error| panic!("could not read {full_path}: {error}, see tests/vectors/README.md"))
+}
@@ -0,0 +1,318 @@
+// Reader for the JSON test files of Project Wycheproof.
+//
+// A file has test groups, each with parameters shared by its tests (key sizes, a public key)
+// and a list of tests. Every test has an id, a comment, flags, and a result: valid tests must be
+// accepted with the expected output, invalid ones rejected, and acceptable ones may go either
+// way, like a low order X25519 public key that some implementations refuse.
+
+use super::read_vector_file;
+use crate::utils::formatting::hex_to_bytes;
+
+#[derive(Debug, PartialEq)]
+pub enum Expectation {
+    Valid,
+    Invalid,
+    Acceptable
+}
+
+pub struct TestGroup {
+    fields: Json,
+    pub tests: Vec<TestCase>
+}
+
+impl TestGroup {
+    // Looks up a field, with dots for nested objects, e.g. "publicKey.pk".
+    pub fn hex(&self, path: &str) -> Vec<u8> {
+        decode_hex(self.fields.path(path).as_str(), path, "group")
+    }
+
+    pub fn usize(&self, path: &str) -> usize {
+        self.fields.path(path).as_usize()
+    }
+}
+
+pub struct TestCase {
+    pub id: usize,
+    pub comment: String,
+    pub flags: Vec<String>,
+    pub result: Expectation,
+    fields: Json,
+    file: String
+}
+
+impl TestCase {
+    pub fn hex(&self, name: &str) -> Vec<u8> {
+        decode_hex(self.fields.path(name).as_str(), name, &self.to_string())
+    }
+
+    pub fn usize(&self, name: &str) -> usize {
+        self.fields.path(name).as_usize()
+    }
+
+    // Checks the outcome against the expected result. For valid tests, accepted has to include
+    // the output matching the expected one.
+    #[track_caller]
+    pub fn check(&self, accepted: bool) {
+        match self.result {
+            Expectation::Valid => assert!(accepted, "{self} should be accepted"),
+            Expectation::Invalid => assert!(!accepted, "{self} should be rejected"),
+            Expectation::Acceptable => {}
+        }
+    }
+}
+
+impl std::fmt::Display for TestCase {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} test {} ({}, flags {:?})", self.file, self.id, self.comment, self.flags)
+    }
+}
+
+fn decode_hex(value: &str, name: &str, context: &str) -> Vec<u8> {
+    hex_to_bytes(value).unwrap_or_else(|error| panic!("{context}: {name}: {error}"))
+}
+
+// Loads tests/vectors/wycheproof/<name>, checking that every test listed in the header was read.
+pub fn load(name: &str) -> Vec<TestGroup> {
+    let root = Parser { bytes: read_vector_file(&format!("wycheproof/{name}")).as_bytes(), position: 0 }.parse_document();
+
+    let mut groups = Vec::new();
+    for group in root.path("testGroups").as_array() {
+        let tests = group.path("tests").as_array().iter().map(|test| {
+            let result = match test.path("result").as_str() {
+                "valid" => Expectation::Valid,
+                "invalid" => Expectation::Invalid,
+                "acceptable" => Expectation::Acceptable,
+                other => panic!("{name}: unknown result \"{other}\"")
+            };
+
+            TestCase {
+                id: test.path("tcId").as_usize(),
+                comment: test.get("comment").map_or("", Json::as_str).to_string(),
+                flags: test.get("flags").map_or(&[][..], Json::as_array).iter().map(|flag| flag.as_str().to_string()).collect(),
+                result,
+                fields: test.clone(),
+                file: name.to_string()
+            }
+        }).collect();
+
+        groups.push(TestGroup { fields: group.clone(), tests });
+    }
+
+    let count: usize = groups.iter().map(|group| group.tests.len()).sum();
+    assert_eq!(count, root.path("numberOfTests").as_usize(), "{name}: numberOfTests doesn't match the tests read");
+
+    groups
+}
+
+// Just enough JSON for the test files. Numbers are only ever small integers there.
+#[derive(Clone, Debug, PartialEq)]
+enum Json {
+    Null,
+    Boolean(bool),
+    Number(f64),
+    String(String),
+    Array(Vec<Json>),
+    Object(Vec<(String, Json)>)
+}
+
+impl Json {
+    fn get(&self, key: &str) -> Option<&Json> {
+        match self {
+            Json::Object(members) => members.iter().find(|(name, _)| name == key).map(|(_, value)| value),
+            _ => None
+        }
+    }
+
+    fn path(&self, path: &str) -> &Json {
+        path.split('.').fold(self, |value, key| value.get(key).unwrap_or_else(|| panic!("no field \"{path}\"")))
+    }
+
+    fn as_str(&self) -> &str {
+        match self {
+            Json::String(value) => value,
+            other => panic!("expected a string, found {other:?}")
+        }
+    }
+
+    fn as_usize(&self) -> usize {
+        match self {
+            Json::Number(value) if value.fract() == 0.0 && *value >= 0.0 => *value as usize,
+            other => panic!("expected an unsigned integer, found {other:?}")
+        }
+    }
+
+    fn as_array(&self) -> &[Json] {
+        match self {
+            Json::Array(values) => values,
+            other => panic!("expected an array, found {other:?}")
+        }
+    }
+}
+
+// A recursive descent parser, which panics on malformed input since it only reads test data.
+struct Parser<'a> {
+    bytes: &'a [u8],
+    position: usize
+}
+
+impl Parser<'_> {
+    fn parse_document(&mut self) -> Json {
+        let value = self.parse_value();
+        self.skip_whitespace();
+        assert_eq!(self.position, self.bytes.len(), "trailing data after the JSON document");
+        value
+    }
+
+    fn parse_value(&mut self) -> Json {
+        self.skip_whitespace();
+        match self.peek() {
+            b'{' => self.parse_object(),
+            b'[' => self.parse_array(),
+            b'"' => Json::String(self.parse_string()),
+            b't' => self.parse_literal("true", Json::Boolean(true)),
+            b'f' => self.parse_literal("false", Json::Boolean(false)),
+            b'n' => self.parse_literal("null", Json::Null),
+            _ => self.parse_number()
+        }
+    }
+
+    fn parse_object(&mut self) -> Json {
+        let mut members = Vec::new();
+        self.expect(b'{');
+        self.skip_whitespace();
+        if self.peek() == b'}' {
+            self.position += 1;
+            return Json::Object(members);
+        }
+
+        loop {
+            self.skip_whitespace();
+            let name = self.parse_string();
+            self.skip_whitespace();
+            self.expect(b':');
+            members.push((name, self.parse_value()));
+            self.skip_whitespace();
+            if self.next() == b'}' {
+                return Json::Object(members);
+            }
+            self.position -= 1;
+            self.expect(b',');
+        }
+    }
+
+    fn parse_array(&mut self) -> Json {
+        let mut values = Vec::new();
+        self.expect(b'[');
+        self.skip_whitespace();
+        if self.peek() == b']' {
+            self.position += 1;
+            return Json::Array(values);
+        }
+
+        loop {
+            values.push(self.parse_value());
+            self.skip_whitespace();
+            if self.next() == b']' {
+                return Json::Array(values);
+            }
+            self.position -= 1;
+            self.expect(b',');
+        }
+    }
+
+    fn parse_string(&mut self) -> String {
+        self.expect(b'"');
+        let mut bytes = Vec::new();
+
+        loop {
+            match self.next() {
+                b'"' => break,
+                b'\\' => {
+                    let escaped = match self.next() {
+                        b'n' => '\n',
+                        b't' => '\t',
+                        b'r' => '\r',
+                        b'b' => '\u{8}',
+                        b'f' => '\u{c}',
+                        b'u' => self.parse_unicode_escape(),
+                        other => other as char
+                    };
+                    bytes.extend_from_slice(escaped.encode_utf8(&mut [0u8; 4]).as_bytes());
+                }
+                byte => bytes.push(byte)
+            }
+        }
+
+        String::from_utf8(bytes).expect("JSON strings are UTF-8")
+    }
+
+    // \uXXXX, where characters outside the basic plane are written as a surrogate pair
+    fn parse_unicode_escape(&mut self) -> char {
+        let read_hex = |parser: &mut Self| {
+            let digits = std::str::from_utf8(&parser.bytes[parser.position..parser.position + 4]).unwrap();
+            parser.position += 4;
+            u32::from_str_radix(digits, 16).expect("\\u escape with non-hex digits")
+        };
+
+        let high = read_hex(self);
+        let code = if (0xd800..0xdc00).contains(&high) {
+            assert_eq!(&self.bytes[self.position..self.position + 2], b"\\u", "unpaired surrogate");
+            self.position += 2;
+            0x10000 + ((high - 0xd800) << 10) + (read_hex(self) - 0xdc00)
+        } else {
+            high
+        };
+
+        char::from_u32(code).expect("invalid \\u escape")
+    }
+
+    fn parse_number(&mut self) -> Json {
+        let start = self.position;
+        while self.position < self.bytes.len() && b"+-0123456789.eE".contains(&self.bytes[self.position]) {
+            self.position += 1;
+        }
+
+        let text = std::str::from_utf8(&self.bytes[start..self.position]).unwrap();
+        Json::Number(text.parse().unwrap_or_else(|_| panic!("unexpected JSON at byte {start}")))
+    }
+
+    fn parse_literal(&mut self, literal: &str, value: Json) -> Json {
+        assert!(self.bytes[self.position..].starts_with(literal.as_bytes()), "unexpected JSON at byte {}", self.position);
+        self.position += literal.len();
+        value
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.position < self.bytes.len() && self.bytes[self.position].is_ascii_whitespace() {
+            self.position += 1;
+        }
+    }
+
+    fn peek(&self) -> u8 {
+        *self.bytes.get(self.position).expect("unexpected end of JSON")
+    }
+
+    fn next(&mut self) -> u8 {
+        let byte = self.peek();
+        self.position += 1;
+        byte
+    }
+
+    fn expect(&mut self, expected: u8) {
+        let position = self.position;
+        assert_eq!(self.next(), expected, "expected '{}' at byte {position}", expected as char);
+    }
+}
+
+#[test]
+fn json_test() {
+    let text = r#" {"a": [1, -2.5e1, true, false, null], "b": {"c": "x\"\\é😀"}, "d": [], "e": {}} "#;
+    let value = Parser { bytes: text.as_bytes(), position: 0 }.parse_document();
+
+    assert_eq!(value.path("a"), &Json::Array(vec![
+        Json::Number(1.0), Json::Number(-25.0), Json::Boolean(true), Json::Boolean(false), Json::Null
+    ]));
+    assert_eq!(value.path("b.c").as_str(), "x\"\\\u{e9}\u{1f600}");
+    assert!(value.path("d").as_array().is_empty());
+    assert_eq!(value.path("e"), &Json::Object(Vec::new()));
+}
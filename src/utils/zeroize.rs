@@ -0,0 +1,96 @@
+// Overwrites the bytes with zeros in a way the compiler won't optimize away, even when the
+// memory is about to be freed. Used to wipe key material once it is no longer needed.
+pub fn zeroize(bytes: &mut [u8]) {
+    for byte in bytes.iter_mut() {
+        // SAFETY: the pointer comes from a mutable reference, so it is valid and aligned
+        unsafe { std::ptr::write_volatile(byte, 0) };
+    }
+
+    // stop the writes from being reordered after whatever frees the memory
+    std::sync::atomic::compiler_fence(std::sync::atomic::Ordering::SeqCst);
+}
+
+// The same for integers wider than a byte, which are set to their default of zero.
+pub fn zeroize_words<T: Copy + Default>(words: &mut [T]) {
+    for word in words.iter_mut() {
+        // SAFETY: the pointer comes from a mutable reference, so it is valid and aligned
+        unsafe { std::ptr::write_volatile(word, T::default()) };
+    }
+    std::sync::atomic::compiler_fence(std::sync::atomic::Ordering::SeqCst);
+}
+
+// Types whose memory can be wiped in place, for use with Secret.
+pub trait Zeroize {
+    fn zeroize(&mut self);
+}
+
+impl<const N: usize> Zeroize for [u8; N] {
+    fn zeroize(&mut self) {
+        zeroize(self);
+    }
+}
+
+impl<const N: usize> Zeroize for [u64; N] {
+    fn zeroize(&mut self) {
+        zeroize_words(self);
+    }
+}
+
+impl<const N: usize> Zeroize for [u32; N] {
+    fn zeroize(&mut self) {
+        zeroize_words(self);
+    }
+}
+
+impl Zeroize for u128 {
+    fn zeroize(&mut self) {
+        zeroize_words(std::slice::from_mut(self));
+    }
+}
+
+// Only the initialized bytes are wiped. Anything left behind by an earlier reallocation has
+// already been freed and is out of reach.
+impl Zeroize for Vec<u8> {
+    fn zeroize(&mut self) {
+        zeroize(self);
+        self.clear();
+    }
+}
+
+impl Zeroize for String {
+    fn zeroize(&mut self) {
+        // SAFETY: all zero bytes are valid UTF-8
+        zeroize(unsafe { self.as_mut_vec() });
+        self.clear();
+    }
+}
+
+#[test]
+fn zeroize_test() {
+    let mut bytes = vec![0xffu8; 40];
+    bytes.zeroize();
+    assert!(bytes.is_empty());
+    // SAFETY: the vector still owns its 40 byte allocation, which was initialized above
+    unsafe { bytes.set_len(40) };
+    assert!(bytes.iter().all(|&b| b == 0));
+
+    let mut array = [0xffu8; 32];
+    array.zeroize();
+    assert_eq!(array, [0u8; 32]);
+
+    let mut words = [u32::MAX; 16];
+    words.zeroize();
+    assert_eq!(words, [0u32; 16]);
+
+    let mut words = [u64::MAX; 8];
+    words.zeroize();
+    assert_eq!(words, [0u64; 8]);
+
+    let mut value = u128::MAX;
+    value.zeroize();
+    assert_eq!(value, 0);
+
+    let mut text = String::from("correct horse battery staple");
+    text.zeroize();
+    assert!(text.is_empty());
+}